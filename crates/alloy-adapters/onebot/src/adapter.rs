@@ -15,6 +15,7 @@
 //!         host: 0.0.0.0
 //!         port: 8080
 //!         path: /onebot/v11/ws
+//!         access_token: ${BOT_TOKEN:-}   # clients must present this token
 //!       - type: ws-client
 //!         url: ws://127.0.0.1:6700/ws
 //!         access_token: ${BOT_TOKEN:-}
@@ -41,17 +42,20 @@
 //! ```
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use tracing::{debug, info, trace, warn};
 
+use crate::api_caller::ApiCaller;
+use crate::auth::signature_matches;
 use crate::bot::OneBotBot;
 use crate::config::{ConnectionConfig, OneBotConfig};
-use crate::model::event::parse_onebot_event;
+use crate::model::event::{parse_onebot_event, parse_onebot_request};
 use alloy_core::{
-    Adapter, AdapterContext, AdapterResult, BoxedBot, BoxedEvent, ConfigurableAdapter,
-    ConnectionHandle, ConnectionInfo, HttpClientConfig, TransportError, TransportResult,
-    WsClientConfig,
+    Adapter, AdapterContext, AdapterResult, BoxedBot, BoxedEvent, ClientTlsConfig,
+    ConfigurableAdapter, ConnectionHandle, ConnectionInfo, HttpClient, HttpClientConfig,
+    HttpServer, ResponseSlot, TransportError, TransportResult, WsClient, WsClientConfig, WsServer,
 };
 
 /// The OneBot v11 adapter.
@@ -63,35 +67,82 @@ pub struct OneBotAdapter {
     config: OneBotConfig,
 }
 
-#[async_trait]
-impl Adapter for OneBotAdapter {
-    fn get_bot_id(&self, conn_info: ConnectionInfo) -> TransportResult<String> {
-        // OneBot v11 uses X-Self-ID header to identify the bot
-        let bot_id = conn_info
-            .metadata
-            .get("x-self-id")
-            .cloned()
-            .ok_or_else(|| TransportError::BotIdMissing {
-                reason: format!(
-                    "x-self-id header not found in connection metadata. Remote: {:?}",
-                    conn_info.remote_addr
-                ),
-            })?;
-
-        info!(
-            bot_id = %bot_id,
-            remote_addr = ?conn_info.remote_addr,
-            "OneBot connection established"
-        );
+impl OneBotAdapter {
+    /// Returns the access tokens accepted on a ws-server `path`, or `None` if
+    /// some listener on that path is open.
+    ///
+    /// Listeners sharing a path (on different addresses) are checked
+    /// together, since a request does not say which one it arrived on.
+    fn ws_server_tokens(&self, path: &str) -> Option<Vec<&str>> {
+        let mut tokens = Vec::new();
+        for conn in self.config.enabled_connections() {
+            let ConnectionConfig::WsServer(ws) = conn else {
+                continue;
+            };
+            if ws.path.trim_start_matches('/') != path.trim_start_matches('/') {
+                continue;
+            }
+            let token = ws
+                .access_token
+                .as_ref()
+                .or(self.config.default_access_token.as_ref())
+                .filter(|t| !t.is_empty())?;
+            tokens.push(token.as_str());
+        }
+        Some(tokens)
+    }
 
-        Ok(bot_id)
+    /// Returns the tokens SSE subscribers may present on `path`, or `None`
+    /// if some http-server listener on that path has neither a token nor a
+    /// secret.
+    fn sse_tokens(&self, path: &str) -> Option<Vec<&str>> {
+        let mut tokens = Vec::new();
+        for conn in self.config.enabled_connections() {
+            let ConnectionConfig::HttpServer(http) = conn else {
+                continue;
+            };
+            if http.path.trim_start_matches('/') != path.trim_start_matches('/') {
+                continue;
+            }
+            let token = [
+                &http.access_token,
+                &self.config.default_access_token,
+                &http.secret,
+            ]
+            .into_iter()
+            .flatten()
+            .find(|t| !t.is_empty())?;
+            tokens.push(token.as_str());
+        }
+        Some(tokens)
     }
 
-    fn create_bot(&self, bot_id: &str, connection: ConnectionHandle) -> BoxedBot {
-        Arc::new(OneBotBot::new(bot_id, connection))
+    /// Returns the webhook secrets of http-server listeners on `path`, or
+    /// `None` if some listener on that path is unsigned.
+    fn http_server_secrets(&self, path: &str) -> Option<Vec<&str>> {
+        let mut secrets = Vec::new();
+        for conn in self.config.enabled_connections() {
+            let ConnectionConfig::HttpServer(http) = conn else {
+                continue;
+            };
+            if http.path.trim_start_matches('/') != path.trim_start_matches('/') {
+                continue;
+            }
+            let secret = http.secret.as_deref().filter(|s| !s.is_empty())?;
+            secrets.push(secret);
+        }
+        Some(secrets)
     }
 
-    async fn parse_event(&self, bot: &BoxedBot, data: &[u8]) -> Option<BoxedEvent> {
+    /// Parses an inbound frame; `response` is set for HTTP webhook requests.
+    ///
+    /// API responses (frames carrying `echo`) are routed to the bot's API
+    /// caller instead of becoming events.
+    fn parse_payload(
+        bot: &BoxedBot,
+        data: &[u8],
+        response: Option<ResponseSlot>,
+    ) -> Option<BoxedEvent> {
         let bot_id = bot.id();
 
         // Parse the message as JSON first
@@ -115,7 +166,11 @@ impl Adapter for OneBotAdapter {
         }
 
         // Parse as event
-        let boxed_event = match parse_onebot_event(raw) {
+        let parsed = match response {
+            Some(response) => parse_onebot_request(raw, response),
+            None => parse_onebot_event(raw),
+        };
+        let boxed_event = match parsed {
             Ok(e) => e,
             Err(e) => {
                 warn!(bot_id = %bot_id, error = %e, raw_data = %raw, "Failed to parse event raw data");
@@ -125,6 +180,124 @@ impl Adapter for OneBotAdapter {
 
         Some(boxed_event)
     }
+}
+
+/// Extracts the access token a client presented, per OneBot v11: an
+/// `Authorization: Bearer <token>` (or `Token <token>`) header, or an
+/// `access_token` query parameter.
+fn presented_token(conn_info: &ConnectionInfo) -> Option<&str> {
+    if let Some(authorization) = conn_info.metadata.get("authorization") {
+        let (scheme, token) = authorization.split_once(' ')?;
+        return (scheme.eq_ignore_ascii_case("bearer") || scheme.eq_ignore_ascii_case("token"))
+            .then(|| token.trim());
+    }
+    conn_info.query.get("access_token").map(String::as_str)
+}
+
+/// Compares two tokens in time independent of where they first differ.
+fn token_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+#[async_trait]
+impl Adapter for OneBotAdapter {
+    fn authenticate(&self, conn_info: &ConnectionInfo) -> TransportResult<()> {
+        let path = conn_info.path.as_deref().unwrap_or("/");
+        let expected = match conn_info.protocol.as_str() {
+            "websocket" => self.ws_server_tokens(path),
+            // SSE subscriptions carry no body to sign, so they present a
+            // token instead.  The stream holds every outgoing API call, so
+            // without a token to check it is not served at all.
+            "sse" => match self.sse_tokens(path) {
+                Some(tokens) if !tokens.is_empty() => Some(tokens),
+                _ => {
+                    return Err(TransportError::Unauthorized {
+                        reason: "no access token or secret configured for SSE".to_string(),
+                    });
+                }
+            },
+            _ => return Ok(()),
+        };
+        let Some(expected) = expected else {
+            return Ok(());
+        };
+        if expected.is_empty() {
+            return Ok(());
+        }
+
+        let reason = match presented_token(conn_info) {
+            None => "missing access token",
+            Some(token) if expected.iter().any(|e| token_eq(e, token)) => return Ok(()),
+            Some(_) => "invalid access token",
+        };
+        Err(TransportError::Unauthorized {
+            reason: reason.to_string(),
+        })
+    }
+
+    fn verify_request(&self, conn_info: &ConnectionInfo, body: &[u8]) -> TransportResult<()> {
+        let path = conn_info.path.as_deref().unwrap_or("/");
+        let Some(secrets) = self.http_server_secrets(path) else {
+            return Ok(());
+        };
+        if secrets.is_empty() {
+            return Ok(());
+        }
+
+        let reason = match conn_info.metadata.get("x-signature") {
+            None => "missing X-Signature",
+            Some(header) if secrets.iter().any(|s| signature_matches(s, body, header)) => {
+                return Ok(());
+            }
+            Some(_) => "invalid X-Signature",
+        };
+        Err(TransportError::Unauthorized {
+            reason: reason.to_string(),
+        })
+    }
+
+    fn get_bot_id(&self, conn_info: ConnectionInfo) -> TransportResult<String> {
+        // OneBot v11 uses X-Self-ID header to identify the bot
+        let bot_id = conn_info
+            .metadata
+            .get("x-self-id")
+            .cloned()
+            .ok_or_else(|| TransportError::BotIdMissing {
+                reason: format!(
+                    "x-self-id header not found in connection metadata. Remote: {:?}",
+                    conn_info.remote_addr
+                ),
+            })?;
+
+        info!(
+            bot_id = %bot_id,
+            remote_addr = ?conn_info.remote_addr,
+            "OneBot connection established"
+        );
+
+        Ok(bot_id)
+    }
+
+    fn create_bot(&self, bot_id: &str, connection: ConnectionHandle) -> BoxedBot {
+        Arc::new(OneBotBot::new(bot_id, connection))
+    }
+
+    async fn parse_event(&self, bot: &BoxedBot, data: &[u8]) -> Option<BoxedEvent> {
+        Self::parse_payload(bot, data, None)
+    }
+
+    async fn parse_request(
+        &self,
+        bot: &BoxedBot,
+        data: &[u8],
+        response: ResponseSlot,
+    ) -> Option<BoxedEvent> {
+        Self::parse_payload(bot, data, Some(response))
+    }
 
     async fn on_start(&self, ctx: Arc<dyn AdapterContext>) -> AdapterResult<()> {
         let enabled_count = self.config.enabled_count();
@@ -142,11 +315,9 @@ impl Adapter for OneBotAdapter {
         for conn_config in self.config.enabled_connections() {
             match conn_config {
                 ConnectionConfig::WsServer(ws_config) => {
-                    if let Some(ws_server) = ctx.transport().ws_server() {
-                        let addr = ws_config.bind_addr();
+                    if let Some(ws_server) = ctx.transport().get::<WsServer>() {
                         let handle = ws_server(
-                            addr,
-                            ws_config.path.clone(),
+                            ws_config.listen_config()?,
                             ctx.clone().as_connection_handler(),
                         )
                         .await?;
@@ -159,16 +330,41 @@ impl Adapter for OneBotAdapter {
                 }
 
                 ConnectionConfig::WsClient(ws_config) => {
-                    if let Some(ws_client) = ctx.transport().ws_client() {
+                    if let Some(ws_client) = ctx.transport().get::<WsClient>() {
                         let token = ws_config
                             .access_token
                             .as_ref()
                             .or(self.config.default_access_token.as_ref())
                             .filter(|t| !t.is_empty());
-                        let mut config = WsClientConfig::new(&ws_config.url);
+                        let heartbeat = Some(self.config.heartbeat_interval_secs)
+                            .filter(|&secs| secs > 0)
+                            .map(Duration::from_secs);
+                        let mut config = WsClientConfig::new(&ws_config.url)
+                            .with_heartbeat(heartbeat)
+                            .with_reconnect(
+                                ws_config.auto_reconnect,
+                                Duration::from_millis(ws_config.reconnect_delay_ms),
+                            )
+                            .with_connect_timeout(Duration::from_millis(
+                                ws_config.connect_timeout_ms,
+                            ))
+                            .with_call_timeout(Duration::from_millis(ws_config.api_timeout_ms));
                         if let Some(t) = token {
                             config = config.with_token(t);
                         }
+                        for (name, value) in &ws_config.headers {
+                            config = config.with_header(name, value);
+                        }
+                        if let Some(path) = &ws_config.unix_socket {
+                            config = config.with_unix_socket(path);
+                        }
+                        let mut tls = ClientTlsConfig::default()
+                            .with_insecure_skip_verify(ws_config.tls_insecure_skip_verify);
+                        tls.ca_file = ws_config.tls_ca_file.clone();
+                        tls.client_cert = ws_config.tls_client_cert.clone();
+                        tls.client_key = ws_config.tls_client_key.clone();
+                        config = config.with_tls(tls);
+                        config = config.with_proxy(ws_config.proxy.to_proxy_config());
                         let handle = ws_client(config, ctx.clone().as_connection_handler()).await?;
                         ctx.add_connection(handle);
                     } else {
@@ -179,11 +375,9 @@ impl Adapter for OneBotAdapter {
                 }
 
                 ConnectionConfig::HttpServer(http_config) => {
-                    if let Some(http_server) = ctx.transport().http_server() {
-                        let addr = http_config.bind_addr();
+                    if let Some(http_server) = ctx.transport().get::<HttpServer>() {
                         let handle = http_server(
-                            addr,
-                            http_config.path.clone(),
+                            http_config.listen_config()?,
                             ctx.clone().as_connection_handler(),
                         )
                         .await?;
@@ -194,7 +388,7 @@ impl Adapter for OneBotAdapter {
                 }
 
                 ConnectionConfig::HttpClient(http_config) => {
-                    if let Some(http_client) = ctx.transport().http_client() {
+                    if let Some(http_client) = ctx.transport().get::<HttpClient>() {
                         let bot_id = http_config.bot_id.clone();
                         let access_token = http_config
                             .access_token
@@ -202,10 +396,16 @@ impl Adapter for OneBotAdapter {
                             .or(self.config.default_access_token.as_ref())
                             .cloned();
 
-                        let mut client_config = HttpClientConfig::new(&http_config.api_url);
+                        let mut client_config = HttpClientConfig::new(&http_config.api_url)
+                            .with_timeout(Duration::from_millis(http_config.timeout_ms));
                         if let Some(token) = access_token {
                             client_config = client_config.with_token(token);
                         }
+                        if let Some(path) = &http_config.unix_socket {
+                            client_config = client_config.with_unix_socket(path);
+                        }
+                        client_config =
+                            client_config.with_proxy(http_config.proxy.to_proxy_config());
 
                         let handle =
                             http_client(bot_id, client_config, ctx.clone().as_connection_handler())
@@ -233,3 +433,169 @@ impl ConfigurableAdapter for OneBotAdapter {
         Self { config }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use alloy_core::{Dispatcher, EventRecorder, EventType, Frame, Replayer};
+    use serde_json::json;
+
+    use super::*;
+    use crate::config::{HttpServerConfig, WsServerConfig};
+
+    fn adapter() -> OneBotAdapter {
+        OneBotAdapter::from_config(OneBotConfig {
+            connections: vec![
+                ConnectionConfig::WsServer(WsServerConfig {
+                    path: "/onebot/v11/ws".to_string(),
+                    access_token: Some("secret".to_string()),
+                    ..Default::default()
+                }),
+                ConnectionConfig::WsServer(WsServerConfig {
+                    path: "/open".to_string(),
+                    ..Default::default()
+                }),
+            ],
+            ..Default::default()
+        })
+    }
+
+    fn ws_request(path: &str) -> ConnectionInfo {
+        ConnectionInfo::new("websocket").with_path(path)
+    }
+
+    #[test]
+    fn test_ws_server_authentication() {
+        let adapter = adapter();
+        let path = "/onebot/v11/ws";
+
+        for authorization in ["Bearer secret", "Token secret", "bearer secret"] {
+            let info = ws_request(path).with_metadata("authorization", authorization);
+            assert!(adapter.authenticate(&info).is_ok(), "{authorization}");
+        }
+        let query = HashMap::from([("access_token".to_string(), "secret".to_string())]);
+        assert!(
+            adapter
+                .authenticate(&ws_request(path).with_query(query))
+                .is_ok()
+        );
+
+        let wrong = ws_request(path).with_metadata("authorization", "Bearer nope");
+        assert!(matches!(
+            adapter.authenticate(&wrong),
+            Err(TransportError::Unauthorized { .. })
+        ));
+        assert!(adapter.authenticate(&ws_request(path)).is_err());
+
+        assert!(adapter.authenticate(&ws_request("/open")).is_ok());
+    }
+
+    #[test]
+    fn test_sse_authentication() {
+        let adapter = OneBotAdapter::from_config(OneBotConfig {
+            connections: vec![
+                ConnectionConfig::HttpServer(HttpServerConfig {
+                    path: "/signed".to_string(),
+                    secret: Some("webhook".to_string()),
+                    ..Default::default()
+                }),
+                ConnectionConfig::HttpServer(HttpServerConfig {
+                    path: "/token".to_string(),
+                    secret: Some("webhook".to_string()),
+                    access_token: Some("token".to_string()),
+                    ..Default::default()
+                }),
+                ConnectionConfig::HttpServer(HttpServerConfig {
+                    path: "/open".to_string(),
+                    ..Default::default()
+                }),
+            ],
+            ..Default::default()
+        });
+        let sse = |path: &str| {
+            ConnectionInfo::new("sse")
+                .with_path(path)
+                .with_metadata("x-self-id", "10001")
+        };
+
+        assert!(matches!(
+            adapter.authenticate(&sse("/signed")),
+            Err(TransportError::Unauthorized { .. })
+        ));
+        let info = sse("/signed").with_metadata("authorization", "Bearer webhook");
+        assert!(adapter.authenticate(&info).is_ok());
+
+        assert!(adapter.authenticate(&sse("/token")).is_err());
+        let info = sse("/token").with_metadata("authorization", "Bearer webhook");
+        assert!(adapter.authenticate(&info).is_err());
+        let info = sse("/token").with_metadata("authorization", "Bearer token");
+        assert!(adapter.authenticate(&info).is_ok());
+
+        // Without credentials the stream is not served to anyone.
+        assert!(adapter.authenticate(&sse("/open")).is_err());
+        let info = sse("/open").with_metadata("authorization", "Bearer anything");
+        assert!(adapter.authenticate(&info).is_err());
+        assert!(adapter.authenticate(&sse("/unknown")).is_err());
+        // POSTs are checked by their signature instead.
+        let post = ConnectionInfo::new("http").with_path("/signed");
+        assert!(adapter.authenticate(&post).is_ok());
+    }
+
+    /// Answers every message event with "pong".
+    struct Pong;
+
+    #[async_trait]
+    impl Dispatcher for Pong {
+        async fn dispatch(&self, event: BoxedEvent, bot: BoxedBot) {
+            if event.event_type() == EventType::Message {
+                bot.send(&*event, "pong").await.unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let path =
+            std::env::temp_dir().join(format!("alloy-onebot-replay-{}.jsonl", std::process::id()));
+        let message = json!({
+            "time": 0,
+            "self_id": 10001,
+            "post_type": "message",
+            "message_type": "group",
+            "sub_type": "normal",
+            "message_id": 1,
+            "group_id": 678,
+            "user_id": 12345,
+            "message": "ping",
+            "raw_message": "ping",
+            "font": 0,
+            "sender": { "user_id": 12345 },
+        });
+        let heartbeat = json!({
+            "time": 0,
+            "self_id": 10001,
+            "post_type": "meta_event",
+            "meta_event_type": "heartbeat",
+            "interval": 5000,
+            "status": {},
+        });
+
+        let recorder = EventRecorder::open(&path).unwrap();
+        recorder.record_frame("onebot", "10001", &Frame::Text(heartbeat.to_string()));
+        recorder.record("onebot", "10001", message.to_string().as_bytes());
+        drop(recorder);
+
+        let calls = Replayer::new(Arc::new(OneBotAdapter::default()), Arc::new(Pong))
+            .with_response(|_| json!({ "retcode": 0, "data": { "message_id": 2 } }))
+            .replay_file(&path)
+            .await;
+        std::fs::remove_file(&path).ok();
+
+        let calls = calls.unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].bot_id, "10001");
+        assert_eq!(calls[0].body["action"], "send_group_msg");
+        assert_eq!(calls[0].body["params"]["group_id"], 678);
+    }
+}
@@ -10,22 +10,26 @@
 //! | WebSocket (server & client) | [`WsApiCaller`] | Async echo matching — request is tagged with a numeric echo; response arrives on the shared channel and is routed to the waiting future. |
 //! | HTTP client | [`HttpApiCaller`] | Synchronous POST — request body is sent as the HTTP body; the HTTP response body is the API response. No echo is needed. |
 //!
-//! [`OneBotBot`](crate::bot::OneBotBot) holds an `Arc<dyn ApiCaller>` and is
-//! completely unaware of which transport is in use.
+//! [`OneBotBot`](crate::bot::OneBotBot) holds an [`ApiRouter`] over one
+//! caller per connection and is completely unaware of which transport is in
+//! use.
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Duration;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use serde_json::{Value, json};
 use tokio::sync::{mpsc, oneshot};
 use tokio::time::timeout;
 use tracing::{debug, warn};
 
-use alloy_core::{ApiError, ApiResult, PostJsonFn, TransportError};
+use alloy_core::{
+    ApiError, ApiResult, CallOptions, ConnectionHandle, ConnectionKind, Frame, PostJsonFn,
+    TransportError,
+};
 
 // =============================================================================
 // ApiCaller trait — internal abstraction for transport-specific call strategies
@@ -47,11 +51,18 @@ pub trait ApiCaller: Send + Sync {
     /// # Arguments
     /// * `action` – Protocol action name (e.g. `"send_private_msg"`).
     /// * `params` – JSON parameters for the action.
+    /// * `options` – Per-call options; callers honor `timeout`, retries are
+    ///   left to the bot.
     ///
     /// # Errors
     /// Returns an [`ApiError`] if the call fails, times out, or the
     /// connection is lost.
-    async fn call(&self, _action: &str, _params: Value) -> ApiResult<Value> {
+    async fn call(
+        &self,
+        _action: &str,
+        _params: Value,
+        _options: &CallOptions,
+    ) -> ApiResult<Value> {
         Err(ApiError::NotSupported)
     }
 
@@ -76,6 +87,27 @@ pub trait ApiCaller: Send + Sync {
     ///
     /// The default implementation is a no-op.
     fn on_disconnect(&self) {}
+
+    /// Returns `false` if [`call`](ApiCaller::call) always fails, so the
+    /// [`ApiRouter`] never picks this caller.
+    fn can_call(&self) -> bool {
+        true
+    }
+}
+
+/// Creates the caller matching the connection's transport.
+pub(crate) fn caller_for(connection: ConnectionHandle) -> Arc<dyn ApiCaller> {
+    let call_timeout = connection.call_timeout();
+    match connection.kind {
+        // HTTP outbound: all data lives directly in the variant
+        ConnectionKind::HttpClient { post_json } => Arc::new(HttpApiCaller::new(post_json)),
+        // WebSocket: echo-based async caller
+        ConnectionKind::Ws { message_tx } => Arc::new(
+            WsApiCaller::new(message_tx).with_timeout(call_timeout.unwrap_or(DEFAULT_API_TIMEOUT)),
+        ),
+        // HTTP server: receive-only, cannot issue API calls
+        ConnectionKind::HttpServer { .. } => Arc::new(DisabledApiCaller::new()),
+    }
 }
 
 // =============================================================================
@@ -85,7 +117,10 @@ pub trait ApiCaller: Send + Sync {
 /// [`ApiCaller`] for transports that do not support API calls.
 ///
 /// Used by HTTP server connections (which are receive-only).
-/// Any attempt to call an API will return an error.
+/// Any attempt to call an API will return an error, and so does
+/// [`Bot::send`](alloy_core::Bot::send); webhook events are answered with
+/// quick operations instead (see
+/// [`OneBotBot::quick_reply`](crate::bot::OneBotBot::quick_reply)).
 pub struct DisabledApiCaller;
 
 impl DisabledApiCaller {
@@ -96,12 +131,23 @@ impl DisabledApiCaller {
 }
 
 #[async_trait]
-impl ApiCaller for DisabledApiCaller {}
+impl ApiCaller for DisabledApiCaller {
+    fn can_call(&self) -> bool {
+        false
+    }
+}
 
 // =============================================================================
 // WsApiCaller — echo-based async request/response for WebSocket
 // =============================================================================
 
+/// How long a [`WsApiCaller`] waits for a response unless the connection or
+/// the call sets another timeout.
+pub(crate) const DEFAULT_API_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Echo IDs shared by every [`WsApiCaller`].
+static NEXT_ECHO: AtomicU64 = AtomicU64::new(1);
+
 /// [`ApiCaller`] for WebSocket transports (both server and client mode).
 ///
 /// Each [`call`](ApiCaller::call) invocation:
@@ -112,33 +158,40 @@ impl ApiCaller for DisabledApiCaller {}
 /// 4. Awaits the one-shot receiver, which is resolved by
 ///    [`on_incoming_response`](ApiCaller::on_incoming_response) when the
 ///    matching response arrives from the peer.
+///
+/// Echo IDs come from a process-wide counter, so a response can be offered to
+/// every caller of a bot and only the one that sent the request claims it.
 pub struct WsApiCaller {
-    /// WebSocket write channel — sends serialized JSON bytes to the WS loop.
-    message_tx: mpsc::Sender<Vec<u8>>,
+    /// WebSocket write channel — requests go out as JSON text frames.
+    message_tx: mpsc::Sender<Frame>,
     /// Pending call map: echo_id → sender half of the response channel.
     pending_calls: Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>,
-    /// Monotonically increasing echo counter.
-    echo_counter: AtomicU64,
-    /// How long to wait for a response before giving up.
+    /// How long to wait for a response before giving up, unless the call
+    /// sets its own timeout.
     api_timeout: Duration,
 }
 
 impl WsApiCaller {
     /// Creates a new `WsApiCaller` from the WebSocket write channel.
-    pub fn new(message_tx: mpsc::Sender<Vec<u8>>) -> Self {
+    pub fn new(message_tx: mpsc::Sender<Frame>) -> Self {
         Self {
             message_tx,
             pending_calls: Arc::new(Mutex::new(HashMap::new())),
-            echo_counter: AtomicU64::new(1),
-            api_timeout: Duration::from_secs(30),
+            api_timeout: DEFAULT_API_TIMEOUT,
         }
     }
+
+    /// Sets the default response timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.api_timeout = timeout;
+        self
+    }
 }
 
 #[async_trait]
 impl ApiCaller for WsApiCaller {
-    async fn call(&self, action: &str, params: Value) -> ApiResult<Value> {
-        let echo = self.echo_counter.fetch_add(1, Ordering::SeqCst);
+    async fn call(&self, action: &str, params: Value, options: &CallOptions) -> ApiResult<Value> {
+        let echo = NEXT_ECHO.fetch_add(1, Ordering::Relaxed);
 
         // Register pending response channel before sending so we never miss a
         // response that arrives before we start awaiting.
@@ -154,15 +207,15 @@ impl ApiCaller for WsApiCaller {
 
         debug!(action = %action, echo = %echo, "Calling OneBot API via WebSocket");
 
-        let request_bytes = serde_json::to_vec(&request)?;
-        if let Err(e) = self.message_tx.send(request_bytes).await {
+        let request_text = serde_json::to_string(&request)?;
+        if let Err(e) = self.message_tx.send(Frame::Text(request_text)).await {
             // Remove the pending entry so it doesn't dangle.
             self.pending_calls.lock().remove(&echo);
             return Err(TransportError::SendFailed(e.to_string()).into());
         }
 
         // Await the response with a timeout.
-        match timeout(self.api_timeout, rx).await {
+        match timeout(options.timeout.unwrap_or(self.api_timeout), rx).await {
             Ok(Ok(response)) => Ok(response),
             Ok(Err(_)) => {
                 // Channel closed — transport was shut down.
@@ -185,8 +238,8 @@ impl ApiCaller for WsApiCaller {
             let _ = tx.send(data.clone());
             true
         } else {
-            // Echo arrived but no waiter — was probably already timed out.
-            warn!(echo = %echo, "Received WS API response for unknown echo (timed out?)");
+            // Not ours — another connection's, or already timed out.  The
+            // router warns if no caller claims it.
             false
         }
     }
@@ -227,7 +280,7 @@ impl HttpApiCaller {
 
 #[async_trait]
 impl ApiCaller for HttpApiCaller {
-    async fn call(&self, action: &str, params: Value) -> ApiResult<Value> {
+    async fn call(&self, action: &str, params: Value, options: &CallOptions) -> ApiResult<Value> {
         let body = json!({
             "action": action,
             "params": params,
@@ -235,10 +288,393 @@ impl ApiCaller for HttpApiCaller {
 
         debug!(action = %action, "Calling OneBot API via HTTP");
 
-        let response_json = (self.post_json)(body)
+        (self.post_json)(body, options.timeout)
             .await
-            .map_err(|e| ApiError::Other(format!("HTTP request failed: {e}")))?;
+            .map_err(|e| match e {
+                TransportError::Timeout => ApiError::Timeout,
+                e => e.into(),
+            })
+    }
+}
+
+// =============================================================================
+// ApiRouter — failover across all connections of a bot
+// =============================================================================
+
+/// Smoothing factor of the latency moving average (weight of the new sample).
+const LATENCY_EWMA_WEIGHT: f64 = 0.2;
+
+/// One connection's caller plus its observed health.
+struct Route {
+    conn_id: u64,
+    caller: Arc<dyn ApiCaller>,
+    /// Connection-level failures since the last success.
+    failures: AtomicU32,
+    /// Moving average of successful call latency, in microseconds.
+    latency_us: AtomicU64,
+    /// Timeout of calls that do not set one.
+    call_timeout: Duration,
+}
+
+impl Route {
+    fn record_success(&self, elapsed: Duration) {
+        self.failures.store(0, Ordering::Relaxed);
+        let sample = elapsed.as_micros() as f64;
+        let previous = self.latency_us.load(Ordering::Relaxed);
+        let average = if previous == 0 {
+            sample
+        } else {
+            previous as f64 * (1.0 - LATENCY_EWMA_WEIGHT) + sample * LATENCY_EWMA_WEIGHT
+        };
+        self.latency_us.store(average as u64, Ordering::Relaxed);
+    }
+
+    fn record_failure(&self) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Lower is healthier.
+    fn rank(&self) -> (u32, u64) {
+        (
+            self.failures.load(Ordering::Relaxed),
+            self.latency_us.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// [`ApiCaller`] over every connection of one bot.
+///
+/// Calls go to the healthiest connection that can make them — fewest recent
+/// connection failures, then lowest latency — and fall through to the next
+/// one when the request never left the failed connection
+/// ([`ApiError::is_unsent`]).  Other connection failures, timeouts among
+/// them, may have reached the implementation already; they only fall through
+/// if the call's [`RetryPolicy`](alloy_core::RetryPolicy) would repeat them.
+/// Errors returned by the implementation itself are passed back immediately.
+///
+/// All attempts share one deadline: the call's timeout, or else the default
+/// of the first connection tried.
+pub struct ApiRouter {
+    routes: RwLock<Vec<Arc<Route>>>,
+}
+
+impl ApiRouter {
+    /// Creates a router for the bot's first connection.
+    pub fn new(connection: ConnectionHandle) -> Self {
+        let router = Self {
+            routes: RwLock::new(Vec::new()),
+        };
+        router.attach(connection);
+        router
+    }
+
+    /// Adds a connection.
+    pub fn attach(&self, connection: ConnectionHandle) {
+        let conn_id = connection.conn_id();
+        let call_timeout = connection.call_timeout().unwrap_or(DEFAULT_API_TIMEOUT);
+        self.attach_caller(conn_id, caller_for(connection), call_timeout);
+    }
+
+    fn attach_caller(&self, conn_id: u64, caller: Arc<dyn ApiCaller>, call_timeout: Duration) {
+        self.routes.write().push(Arc::new(Route {
+            conn_id,
+            caller,
+            failures: AtomicU32::new(0),
+            latency_us: AtomicU64::new(0),
+            call_timeout,
+        }));
+    }
+
+    /// Returns the default call timeout of the connection calls go out on
+    /// first.
+    pub fn call_timeout(&self) -> Duration {
+        self.ranked()
+            .first()
+            .map_or(DEFAULT_API_TIMEOUT, |route| route.call_timeout)
+    }
+
+    /// Removes a connection, failing its pending calls.
+    pub fn detach(&self, conn_id: u64) {
+        let removed = {
+            let mut routes = self.routes.write();
+            let pos = routes.iter().position(|r| r.conn_id == conn_id);
+            pos.map(|pos| routes.remove(pos))
+        };
+        if let Some(route) = removed {
+            route.caller.on_disconnect();
+        }
+    }
+
+    /// Returns the routes able to make calls, healthiest first.
+    fn ranked(&self) -> Vec<Arc<Route>> {
+        let mut routes: Vec<_> = self
+            .routes
+            .read()
+            .iter()
+            .filter(|r| r.caller.can_call())
+            .cloned()
+            .collect();
+        // Stable: ties keep attach order.
+        routes.sort_by_key(|r| r.rank());
+        routes
+    }
+}
+
+#[async_trait]
+impl ApiCaller for ApiRouter {
+    async fn call(&self, action: &str, params: Value, options: &CallOptions) -> ApiResult<Value> {
+        let routes = self.ranked();
+        let budget = options
+            .timeout
+            .or_else(|| routes.first().map(|route| route.call_timeout))
+            .unwrap_or(DEFAULT_API_TIMEOUT);
+        let deadline = tokio::time::Instant::now() + budget;
+
+        let mut last_error = ApiError::NotSupported;
+        for route in routes {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() {
+                return Err(ApiError::Timeout);
+            }
+            let attempt = options.clone().with_timeout(remaining);
+            let started = Instant::now();
+            match route.caller.call(action, params.clone(), &attempt).await {
+                Ok(response) => {
+                    route.record_success(started.elapsed());
+                    return Ok(response);
+                }
+                Err(e) if e.is_connection_error() => {
+                    route.record_failure();
+                    if !e.is_unsent() && !options.retry.allows_retry(&e) {
+                        return Err(e);
+                    }
+                    warn!(
+                        action = %action,
+                        conn_id = route.conn_id,
+                        error = %e,
+                        "API call failed on connection, trying the next one"
+                    );
+                    last_error = e;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error)
+    }
+
+    fn on_incoming_response(&self, data: &Value) -> bool {
+        let consumed = self
+            .routes
+            .read()
+            .iter()
+            .any(|r| r.caller.on_incoming_response(data));
+        if !consumed {
+            warn!(echo = ?data.get("echo"), "Received API response for unknown echo (timed out?)");
+        }
+        consumed
+    }
+
+    fn on_disconnect(&self) {
+        for route in self.routes.write().drain(..) {
+            route.caller.on_disconnect();
+        }
+    }
+
+    fn can_call(&self) -> bool {
+        self.routes.read().iter().any(|r| r.caller.can_call())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_core::RetryPolicy;
+
+    use super::*;
+
+    /// Answers with its name, or always fails with `error`.
+    struct FlakyCaller {
+        name: &'static str,
+        error: Option<ApiError>,
+    }
+
+    #[async_trait]
+    impl ApiCaller for FlakyCaller {
+        async fn call(
+            &self,
+            _action: &str,
+            _params: Value,
+            _options: &CallOptions,
+        ) -> ApiResult<Value> {
+            match &self.error {
+                Some(e) => Err(e.clone()),
+                None => Ok(json!(self.name)),
+            }
+        }
+    }
+
+    fn router(callers: Vec<(u64, FlakyCaller)>) -> ApiRouter {
+        let router = ApiRouter {
+            routes: RwLock::new(Vec::new()),
+        };
+        for (conn_id, caller) in callers {
+            router.attach_caller(conn_id, Arc::new(caller), DEFAULT_API_TIMEOUT);
+        }
+        router
+    }
+
+    #[tokio::test]
+    async fn test_ws_caller_per_call_timeout() {
+        let (tx, mut rx) = mpsc::channel(4);
+        let caller = WsApiCaller::new(tx).with_timeout(Duration::from_secs(60));
+        let options = CallOptions::new().with_timeout(Duration::from_millis(20));
+
+        let result = caller.call("get_status", json!({}), &options).await;
+        assert!(matches!(result, Err(ApiError::Timeout)));
+        assert!(caller.pending_calls.lock().is_empty());
+        assert!(matches!(rx.try_recv(), Ok(Frame::Text(_))));
+    }
+
+    #[tokio::test]
+    async fn test_router_fails_over_on_connection_errors() {
+        let router = router(vec![
+            (
+                1,
+                FlakyCaller {
+                    name: "ws",
+                    error: Some(ApiError::NotConnected),
+                },
+            ),
+            (
+                2,
+                FlakyCaller {
+                    name: "http",
+                    error: None,
+                },
+            ),
+        ]);
+
+        assert_eq!(
+            router
+                .call("get_status", json!({}), &CallOptions::default())
+                .await
+                .unwrap(),
+            "http"
+        );
+        // The failed connection is now ranked last.
+        assert_eq!(router.ranked()[0].conn_id, 2);
+
+        router.detach(2);
+        assert!(matches!(
+            router
+                .call("get_status", json!({}), &CallOptions::default())
+                .await,
+            Err(ApiError::NotConnected)
+        ));
+    }
+
+    /// Never answers: waits out the call's timeout.
+    struct StalledCaller;
+
+    #[async_trait]
+    impl ApiCaller for StalledCaller {
+        async fn call(
+            &self,
+            _action: &str,
+            _params: Value,
+            options: &CallOptions,
+        ) -> ApiResult<Value> {
+            tokio::time::sleep(options.timeout.unwrap_or(DEFAULT_API_TIMEOUT)).await;
+            Err(ApiError::Timeout)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_router_fails_over_on_timeouts_only_when_retrying() {
+        let timing_out = || {
+            router(vec![
+                (
+                    1,
+                    FlakyCaller {
+                        name: "ws",
+                        error: Some(ApiError::Timeout),
+                    },
+                ),
+                (
+                    2,
+                    FlakyCaller {
+                        name: "http",
+                        error: None,
+                    },
+                ),
+            ])
+        };
+        let retry = RetryPolicy::new(1, Duration::ZERO);
+
+        // The message may have gone out: sending it again could post it twice.
+        let send = timing_out()
+            .call("send_group_msg", json!({}), &CallOptions::default())
+            .await;
+        assert!(matches!(send, Err(ApiError::Timeout)));
+
+        let options = CallOptions::new().with_retry(retry.without_timeouts());
+        let probe = timing_out().call("get_status", json!({}), &options).await;
+        assert!(matches!(probe, Err(ApiError::Timeout)));
+
+        let options = CallOptions::new().with_retry(retry);
+        let query = timing_out().call("get_status", json!({}), &options).await;
+        assert_eq!(query.unwrap(), "http");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_router_shares_one_deadline() {
+        let router = ApiRouter {
+            routes: RwLock::new(Vec::new()),
+        };
+        for conn_id in 1..=3 {
+            router.attach_caller(conn_id, Arc::new(StalledCaller), Duration::from_secs(10));
+        }
+        let options = CallOptions::new().with_retry(RetryPolicy::new(1, Duration::ZERO));
+
+        let started = tokio::time::Instant::now();
+        let result = router.call("get_status", json!({}), &options).await;
+        assert!(matches!(result, Err(ApiError::Timeout)));
+        assert_eq!(started.elapsed(), Duration::from_secs(10));
+
+        let started = tokio::time::Instant::now();
+        let options = options.with_timeout(Duration::from_secs(2));
+        let result = router.call("get_status", json!({}), &options).await;
+        assert!(matches!(result, Err(ApiError::Timeout)));
+        assert_eq!(started.elapsed(), Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn test_router_returns_request_errors_without_failover() {
+        let router = router(vec![
+            (
+                1,
+                FlakyCaller {
+                    name: "ws",
+                    error: Some(ApiError::ApiError {
+                        retcode: 100,
+                        message: "bad request".into(),
+                    }),
+                },
+            ),
+            (
+                2,
+                FlakyCaller {
+                    name: "http",
+                    error: None,
+                },
+            ),
+        ]);
 
-        Ok(response_json)
+        assert!(matches!(
+            router
+                .call("get_status", json!({}), &CallOptions::default())
+                .await,
+            Err(ApiError::ApiError { retcode: 100, .. })
+        ));
+        assert_eq!(router.ranked()[0].conn_id, 1);
     }
 }
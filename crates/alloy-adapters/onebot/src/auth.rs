@@ -0,0 +1,83 @@
+//! Signature checks for events POSTed to the adapter's HTTP servers.
+//!
+//! The OneBot implementation signs each event body with the shared `secret`
+//! and sends `X-Signature: sha1=<hex HMAC-SHA1 of the body>`.
+
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+/// Returns `true` if `header` (the `X-Signature` value) is the signature of
+/// `body` under `secret`.
+pub(crate) fn signature_matches(secret: &str, body: &[u8], header: &str) -> bool {
+    let Some(signature) = header.strip_prefix("sha1=").and_then(from_hex) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha1>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Decodes a hex string of either case.
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha1>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        let hex: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        format!("sha1={hex}")
+    }
+
+    #[test]
+    fn test_signature_matches() {
+        let body = br#"{"post_type":"message"}"#;
+        let header = sign("secret", body);
+
+        assert!(signature_matches("secret", body, &header));
+        assert!(signature_matches(
+            "secret",
+            body,
+            &header.to_uppercase().replace("SHA1=", "sha1=")
+        ));
+        assert!(!signature_matches("other", body, &header));
+        assert!(!signature_matches("secret", b"{}", &header));
+        assert!(!signature_matches(
+            "secret",
+            body,
+            header.trim_start_matches("sha1=")
+        ));
+        assert!(!signature_matches(
+            "secret",
+            body,
+            &header[..header.len() - 2]
+        ));
+        assert!(!signature_matches("secret", body, "sha1=zz"));
+    }
+
+    #[test]
+    fn test_rfc2202_vector() {
+        assert!(signature_matches(
+            "Jefe",
+            b"what do ya want for nothing?",
+            "sha1=effcdf6ae5eb2fa2d27416d5f184df9c259a7c79"
+        ));
+    }
+}
@@ -17,114 +17,186 @@
 //!         
 //!         // Or use the generic send method (passes event directly)
 //!         bot.send(event.as_ref(), "Reply!").await.ok();
+//!
+//!         // Or push to a chat without an originating event
+//!         bot.send_to(&Target::group("87654321"), &msg).await.ok();
 //!     }
 //! }
 //! ```
 
-use std::any::Any;
-use std::sync::Arc;
+use std::any::{Any, TypeId};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use serde_json::{Value, json};
 
-use crate::api_caller::{ApiCaller, DisabledApiCaller, HttpApiCaller, WsApiCaller};
+use crate::api_caller::{ApiCaller, ApiRouter};
 use crate::model::api::{
     Credentials, FriendInfo, GetMsgResponse, GroupInfo, GroupMemberInfo, LoginInfo, Status,
     StrangerInfo, VersionInfo,
 };
-use crate::model::event::{GroupMessageEvent, PrivateMessageEvent};
+use crate::model::event::OneBotEvent;
 use crate::model::message::OneBotMessage;
 use crate::model::segment::Segment;
-use alloy_core::{ApiError, ApiResult, Bot, ErasedMessage, Event, MessageSegment};
-use alloy_core::{ConnectionHandle, ConnectionKind};
+use alloy_core::{
+    ApiError, ApiResult, Bot, CallOptions, CallPriority, ErasedMessage, Event, MessageSegment,
+    RetryPolicy, SceneKind, Target,
+};
+use alloy_core::{ConnectionHandle, OutboundScheduler};
 
 // =============================================================================
 // OneBotBot
 // =============================================================================
 
+/// Minimum timeout of file uploads and downloads.
+const FILE_TRANSFER_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Minimum timeout of actions that fetch or assemble large payloads.
+const SLOW_CALL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Maximum timeout of trivial status queries, so a dead connection is
+/// noticed fast.
+const QUICK_CALL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Retries of idempotent queries.
+const QUERY_RETRY: RetryPolicy = RetryPolicy::new(2, Duration::from_millis(500));
+
 /// A OneBot v11 Bot implementation.
 ///
-/// Wraps an [`ApiCaller`] that handles the transport-specific request/response
-/// strategy (WebSocket echo-matching or direct HTTP POST).
+/// Wraps an [`ApiRouter`] that handles the transport-specific request/response
+/// strategy (WebSocket echo-matching or direct HTTP POST) for each of the
+/// bot's connections and fails over between them.
 pub struct OneBotBot {
     /// Bot ID (self_id from events).
     id: String,
     /// Transport-specific API call mechanism.
-    pub(crate) api_caller: Arc<dyn ApiCaller>,
+    pub(crate) api_caller: ApiRouter,
+    /// Rate limiter every call goes through, if the bridge configured one.
+    outbound: OnceLock<Arc<OutboundScheduler>>,
 }
 
 impl OneBotBot {
     /// Creates a new `OneBotBot` from a connection handle.
     ///
     /// Automatically selects the appropriate [`ApiCaller`] implementation
-    /// based on the connection type.
+    /// based on the connection type; further connections are added through
+    /// [`Bot::attach_connection`].
     pub fn new(id: impl Into<String>, connection: ConnectionHandle) -> Self {
-        let api_caller: Arc<dyn ApiCaller> = match connection.kind {
-            // HTTP outbound: all data lives directly in the variant
-            ConnectionKind::HttpClient { post_json } => Arc::new(HttpApiCaller::new(post_json)),
-            // WebSocket: echo-based async caller
-            ConnectionKind::Ws { message_tx } => Arc::new(WsApiCaller::new(message_tx)),
-            // HTTP server: receive-only, cannot issue API calls
-            ConnectionKind::HttpServer { .. } => Arc::new(DisabledApiCaller::new()),
-        };
         Self {
             id: id.into(),
-            api_caller,
+            api_caller: ApiRouter::new(connection),
+            outbound: OnceLock::new(),
         }
     }
 
     /// Internal method to send a message after converting it to OneBotMessage.
     ///
-    /// Extracts the session information from the event and routes to either
-    /// `send_group_msg` or `send_private_msg`.
-    async fn send_internal(
-        &self,
-        event: &dyn Event,
-        onebot_msg: OneBotMessage,
-    ) -> ApiResult<String> {
-        let target_id = {
-            if let Some(group_msg) = event.as_any().downcast_ref::<GroupMessageEvent>() {
-                Some((true, group_msg.group_id))
-            } else if let Some(private_msg) = event.as_any().downcast_ref::<PrivateMessageEvent>() {
-                Some((false, private_msg.user_id))
-            } else if let Some(raw_json) = event.raw_json()
-                && let Ok(parsed) = serde_json::from_str::<Value>(raw_json)
-            {
-                if let Some(group_id) = parsed.get("group_id").and_then(Value::as_i64) {
-                    Some((true, group_id))
-                } else {
-                    parsed
-                        .get("user_id")
-                        .and_then(Value::as_i64)
-                        .map(|user_id| (false, user_id))
-                }
-            } else {
-                None
+    /// Routes to `send_group_msg` or `send_private_msg` by the target's scene;
+    /// OneBot v11 has no channels.
+    async fn send_internal(&self, target: &Target, onebot_msg: OneBotMessage) -> ApiResult<String> {
+        let id: i64 = target
+            .id
+            .parse()
+            .map_err(|_| ApiError::UnsupportedTarget(target.clone()))?;
+        let message_id = match target.scene {
+            SceneKind::Group => self.send_group_msg(id, onebot_msg).await?,
+            SceneKind::Private => self.send_private_msg(id, onebot_msg).await?,
+            SceneKind::Channel => return Err(ApiError::UnsupportedTarget(target.clone())),
+        };
+        Ok(message_id.to_string())
+    }
+
+    /// Returns the options [`call_api`](Bot::call_api) uses for `action`.
+    ///
+    /// Timeouts follow the default of the connection the call goes out on
+    /// (`api_timeout_ms` / `timeout_ms`): file transfers and other slow
+    /// actions wait at least 300 and 120 seconds, trivial status queries at
+    /// most 10 seconds, and everything else the default itself.  Queries
+    /// (`get_*`, `can_*`) are idempotent and retried after connection
+    /// failures, except that status queries are not retried after a
+    /// timeout.  Moderation actions are sent with high priority and bulk
+    /// list queries with low priority.  Start from these to tweak a single
+    /// call:
+    ///
+    /// ```rust,ignore
+    /// let options = bot
+    ///     .default_call_options("get_forward_msg")
+    ///     .with_timeout(Duration::from_secs(300));
+    /// bot.call_api_with("get_forward_msg", json!({ "id": id }), &options).await?;
+    /// ```
+    pub fn default_call_options(&self, action: &str) -> CallOptions {
+        let quick = matches!(
+            action,
+            "get_status"
+                | "get_version_info"
+                | "get_login_info"
+                | "get_csrf_token"
+                | "can_send_image"
+                | "can_send_record"
+        );
+        let default = self.api_caller.call_timeout();
+        let timeout = match action {
+            "upload_group_file" | "upload_private_file" | "download_file" => {
+                Some(FILE_TRANSFER_TIMEOUT.max(default))
             }
+            "get_forward_msg"
+            | "send_group_forward_msg"
+            | "send_private_forward_msg"
+            | "get_record"
+            | "get_image"
+            | "get_group_member_list"
+            | "get_group_msg_history" => Some(SLOW_CALL_TIMEOUT.max(default)),
+            _ if quick => Some(QUICK_CALL_TIMEOUT.min(default)),
+            _ => None,
         };
 
-        let (is_group, id) = target_id.ok_or(ApiError::MissingSession)?;
-        let message_id = if is_group {
-            self.send_group_msg(id, onebot_msg).await?
+        let retry = if quick {
+            QUERY_RETRY.without_timeouts()
+        } else if action.starts_with("get_") || action.starts_with("can_") {
+            QUERY_RETRY
         } else {
-            self.send_private_msg(id, onebot_msg).await?
+            RetryPolicy::none()
         };
-        Ok(message_id.to_string())
-    }
-}
 
-// =============================================================================
-// Bot Trait Implementation
-// =============================================================================
+        let priority = match action {
+            "delete_msg"
+            | "set_group_kick"
+            | "set_group_ban"
+            | "set_group_anonymous_ban"
+            | "set_group_whole_ban" => CallPriority::High,
+            "clean_cache" => CallPriority::Low,
+            _ if action.starts_with("get_") && action.ends_with("_list") => CallPriority::Low,
+            _ => CallPriority::Normal,
+        };
 
-#[async_trait]
-impl Bot for OneBotBot {
-    fn id(&self) -> &str {
-        &self.id
+        CallOptions {
+            timeout,
+            retry,
+            priority,
+        }
     }
 
-    async fn call_api(&self, action: &str, params: Value) -> ApiResult<Value> {
-        let response = self.api_caller.call(action, params).await?;
+    /// Makes one attempt of a call and unwraps the response envelope.
+    ///
+    /// Waits for the outbound scheduler first, so retries are throttled
+    /// like any other call; the wait counts towards `options.timeout`.
+    async fn call_once(
+        &self,
+        action: &str,
+        params: Value,
+        options: &CallOptions,
+    ) -> ApiResult<Value> {
+        let (_permit, options) = match self.outbound.get() {
+            Some(scheduler) => {
+                let (permit, options) = scheduler
+                    .acquire_with(action, call_target(action, &params).as_ref(), options)
+                    .await?;
+                (Some(permit), options)
+            }
+            None => (None, options.clone()),
+        };
+        let response = self.api_caller.call(action, params, &options).await?;
         if let Some(retcode) = response.get("retcode").and_then(Value::as_i64)
             && retcode != 0
         {
@@ -139,24 +211,134 @@ impl Bot for OneBotBot {
         Ok(response.get("data").cloned().unwrap_or(response))
     }
 
-    async fn send(&self, event: &dyn Event, message: &str) -> ApiResult<String> {
-        self.send_internal(event, Segment::text(message).into())
+    /// Answers a webhook event with a OneBot quick operation.
+    ///
+    /// The operation (e.g. `{"reply": "hi", "at_sender": false}` or
+    /// `{"approve": true}`) is returned as the body of the HTTP response that
+    /// delivered `event`, so it works on receive-only http-server
+    /// connections.  Each event can be answered once, and only while the
+    /// implementation is still waiting for the response.
+    pub fn quick_operation(&self, event: &dyn Event, operation: Value) -> ApiResult<()> {
+        let root = event
+            .downgrade_any(TypeId::of::<OneBotEvent>())
+            .and_then(|e| e.downcast::<OneBotEvent>().ok())
+            .ok_or(ApiError::NotSupported)?;
+        let slot = root.response().ok_or(ApiError::NotSupported)?;
+        let body = serde_json::to_vec(&operation)?;
+        if !slot.respond(body) {
+            return Err(ApiError::Other(
+                "event was already answered or its request has ended".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Answers a webhook event with `message`, as a `reply` quick operation.
+    ///
+    /// Unlike [`Bot::send_message`] this needs no connection that can make
+    /// API calls, which suits receive-only http-server deployments; the
+    /// platform reports no message ID for it.  See
+    /// [`quick_operation`](Self::quick_operation) for when it can be used.
+    pub fn quick_reply(&self, event: &dyn Event, message: &dyn ErasedMessage) -> ApiResult<()> {
+        let onebot_msg = OneBotMessage::from_erased_message(message);
+        self.quick_operation(event, json!({ "reply": onebot_msg, "at_sender": false }))
+    }
+
+    /// Sends `message` in reply to `event`.
+    async fn reply(&self, event: &dyn Event, onebot_msg: OneBotMessage) -> ApiResult<String> {
+        let session = event.session().ok_or(ApiError::MissingSession)?;
+        self.send_internal(&session.target(), onebot_msg).await
+    }
+}
+
+/// Returns the conversation a call posts into, for per-target limits and
+/// ordering.
+///
+/// Only `send_*` actions have one.  Uploads, lookups and moderation merely
+/// name a group, and must neither queue behind its messages nor spend its
+/// budget.
+fn call_target(action: &str, params: &Value) -> Option<Target> {
+    if !action.starts_with("send_") {
+        return None;
+    }
+    let id = |key| match params.get(key)? {
+        Value::Number(n) => Some(n.to_string()),
+        Value::String(s) => Some(s.clone()),
+        _ => None,
+    };
+    id("group_id")
+        .map(Target::group)
+        .or_else(|| id("user_id").map(Target::user))
+}
+
+// =============================================================================
+// Bot Trait Implementation
+// =============================================================================
+
+#[async_trait]
+impl Bot for OneBotBot {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Calls the API with [`default_call_options`](OneBotBot::default_call_options)
+    /// for `action`.
+    async fn call_api(&self, action: &str, params: Value) -> ApiResult<Value> {
+        self.call_api_with(action, params, &self.default_call_options(action))
             .await
     }
 
+    async fn call_api_with(
+        &self,
+        action: &str,
+        params: Value,
+        options: &CallOptions,
+    ) -> ApiResult<Value> {
+        options
+            .retry
+            .run(action, || self.call_once(action, params.clone(), options))
+            .await
+    }
+
+    async fn send(&self, event: &dyn Event, message: &str) -> ApiResult<String> {
+        self.reply(event, Segment::text(message).into()).await
+    }
+
     async fn send_message(
         &self,
         event: &dyn Event,
         message: &dyn ErasedMessage,
     ) -> ApiResult<String> {
-        self.send_internal(event, OneBotMessage::from_erased_message(message))
+        self.reply(event, OneBotMessage::from_erased_message(message))
             .await
     }
 
+    async fn send_to(&self, target: &Target, message: &dyn ErasedMessage) -> ApiResult<String> {
+        self.send_internal(target, OneBotMessage::from_erased_message(message))
+            .await
+    }
+
+    fn call_target(&self, action: &str, params: &Value) -> Option<Target> {
+        call_target(action, params)
+    }
+
     fn as_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
         self
     }
 
+    fn attach_connection(&self, connection: ConnectionHandle) -> bool {
+        self.api_caller.attach(connection);
+        true
+    }
+
+    fn attach_outbound(&self, scheduler: Arc<OutboundScheduler>) -> bool {
+        self.outbound.set(scheduler).is_ok()
+    }
+
+    async fn detach_connection(&self, connection: &ConnectionHandle) {
+        self.api_caller.detach(connection.conn_id());
+    }
+
     async fn on_disconnect(&self) {
         self.api_caller.on_disconnect();
     }
@@ -504,3 +686,224 @@ impl OneBotBot {
         ()
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use alloy_core::{OutboundConfig, RateLimit};
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+
+    /// A bot whose HTTP API connection answers every call successfully.
+    fn http_bot() -> OneBotBot {
+        let post_json: alloy_core::PostJsonFn = Arc::new(|_body, _timeout| {
+            Box::pin(async { Ok(json!({ "retcode": 0, "data": { "message_id": 1 } })) })
+        });
+        let connection =
+            ConnectionHandle::new_http_client("10001", post_json, CancellationToken::new());
+        OneBotBot::new("10001", connection)
+    }
+
+    /// A bot whose HTTP API connection records every call and answers it
+    /// with message ID 7.
+    fn recording_bot() -> (OneBotBot, Arc<parking_lot::Mutex<Vec<Value>>>) {
+        let calls = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let post_json: alloy_core::PostJsonFn = Arc::new(move |body, _timeout| {
+            recorded.lock().push(body);
+            Box::pin(async { Ok(json!({ "retcode": 0, "data": { "message_id": 7 } })) })
+        });
+        let connection =
+            ConnectionHandle::new_http_client("10001", post_json, CancellationToken::new());
+        (OneBotBot::new("10001", connection), calls)
+    }
+
+    #[tokio::test]
+    async fn test_reply_to_notice() {
+        let (bot, calls) = recording_bot();
+
+        let event = crate::model::event::parse_onebot_event(
+            r#"{"time":0,"self_id":10001,"post_type":"notice","notice_type":"essence","sub_type":"add","group_id":678,"sender_id":12345,"operator_id":1,"message_id":9}"#,
+        )
+        .unwrap();
+        assert_eq!(bot.send(&*event, "hi").await.unwrap(), "7");
+
+        // Notices of unknown types have no session to reply to.
+        let event = crate::model::event::parse_onebot_event(
+            r#"{"time":0,"self_id":10001,"post_type":"notice","notice_type":"some_unknown","group_id":678,"user_id":12345}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            bot.send(&*event, "hi").await,
+            Err(ApiError::MissingSession)
+        ));
+
+        let calls = calls.lock();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0]["action"], "send_group_msg");
+        assert_eq!(calls[0]["params"]["group_id"], 678);
+    }
+
+    #[tokio::test]
+    async fn test_send_to() {
+        let (bot, calls) = recording_bot();
+        let message = OneBotMessage::from(Segment::text("hi"));
+
+        let id = bot.send_to(&Target::group("678"), &message).await.unwrap();
+        assert_eq!(id, "7");
+        let id = bot.send_to(&Target::user("12345"), &message).await.unwrap();
+        assert_eq!(id, "7");
+        {
+            let calls = calls.lock();
+            assert_eq!(calls.len(), 2);
+            assert_eq!(calls[0]["action"], "send_group_msg");
+            assert_eq!(calls[0]["params"]["group_id"], 678);
+            assert_eq!(calls[0]["params"]["message"][0]["data"]["text"], "hi");
+            assert_eq!(calls[1]["action"], "send_private_msg");
+            assert_eq!(calls[1]["params"]["user_id"], 12345);
+            assert!(calls[1]["params"].get("group_id").is_none());
+        }
+
+        for target in [Target::channel("678"), Target::group("not-a-number")] {
+            let err = bot.send_to(&target, &message).await.unwrap_err();
+            assert!(
+                matches!(&err, ApiError::UnsupportedTarget(t) if *t == target),
+                "{err:?}"
+            );
+        }
+        assert_eq!(calls.lock().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reply_by_quick_operation() {
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let connection = ConnectionHandle::new_http_server("10001", tx, CancellationToken::new());
+        let bot = OneBotBot::new("10001", connection);
+
+        let (slot, answer) = alloy_core::ResponseSlot::channel();
+        let event = crate::model::event::parse_onebot_request(
+            r#"{"time":0,"self_id":10001,"post_type":"message","message_type":"private","sub_type":"friend","message_id":1,"user_id":12345,"message":"ping","raw_message":"ping","font":0,"sender":{"user_id":12345}}"#,
+            slot,
+        )
+        .unwrap();
+
+        // The connection cannot make calls, so only the quick reply works.
+        assert!(bot.send(&*event, "pong").await.is_err());
+        let message: OneBotMessage = Segment::text("pong").into();
+        bot.quick_reply(&*event, &message).unwrap();
+        let body: Value = serde_json::from_slice(&answer.await.unwrap()).unwrap();
+        assert_eq!(body["reply"][0]["data"]["text"], "pong");
+        assert!(bot.quick_reply(&*event, &message).is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_call_api_is_throttled() {
+        let bot = http_bot();
+        let config =
+            OutboundConfig::default().with_action_limit("send_group_msg", RateLimit::new(1.0, 1));
+        let scheduler = Arc::new(OutboundScheduler::new("10001", config));
+        assert!(bot.attach_outbound(scheduler.clone()));
+
+        let started = tokio::time::Instant::now();
+        for _ in 0..3 {
+            bot.call_api("send_group_msg", json!({ "group_id": 1, "message": "hi" }))
+                .await
+                .unwrap();
+        }
+        assert!(started.elapsed() >= Duration::from_secs(2));
+        assert_eq!(scheduler.stats().throttled, 2);
+
+        // Unlimited actions go straight out.
+        let started = tokio::time::Instant::now();
+        bot.call_api("get_status", json!({})).await.unwrap();
+        assert!(started.elapsed() < Duration::from_millis(1));
+    }
+
+    /// A bot whose HTTP API connection never answers and times out calls
+    /// after `timeout`.
+    fn silent_bot(timeout: Duration) -> (OneBotBot, Arc<AtomicU32>) {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let counter = attempts.clone();
+        let post_json: alloy_core::PostJsonFn = Arc::new(move |_body, call_timeout| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            Box::pin(async move {
+                tokio::time::sleep(call_timeout.unwrap_or(timeout)).await;
+                Err(alloy_core::TransportError::Timeout)
+            })
+        });
+        let connection =
+            ConnectionHandle::new_http_client("10001", post_json, CancellationToken::new())
+                .with_call_timeout(timeout);
+        (OneBotBot::new("10001", connection), attempts)
+    }
+
+    #[test]
+    fn test_default_call_options() {
+        let bot = http_bot();
+        let upload = bot.default_call_options("upload_group_file");
+        assert_eq!(upload.timeout, Some(FILE_TRANSFER_TIMEOUT));
+        assert_eq!(upload.retry, RetryPolicy::none());
+
+        let members = bot.default_call_options("get_group_member_list");
+        assert_eq!(members.timeout, Some(SLOW_CALL_TIMEOUT));
+        assert_eq!(members.retry, QUERY_RETRY);
+        assert_eq!(members.priority, CallPriority::Low);
+
+        let status = bot.default_call_options("get_status");
+        assert_eq!(status.timeout, Some(QUICK_CALL_TIMEOUT));
+        assert!(!status.retry.retry_timeouts);
+
+        let send = bot.default_call_options("send_group_msg");
+        assert_eq!(send, CallOptions::default());
+
+        let ban = bot.default_call_options("set_group_ban");
+        assert_eq!(ban.priority, CallPriority::High);
+
+        // Timeouts follow the connection's default.
+        let (short, _) = silent_bot(Duration::from_secs(5));
+        let status = short.default_call_options("get_status");
+        assert_eq!(status.timeout, Some(Duration::from_secs(5)));
+        let (long, _) = silent_bot(Duration::from_secs(600));
+        let members = long.default_call_options("get_group_member_list");
+        assert_eq!(members.timeout, Some(Duration::from_secs(600)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_status_probe_fails_fast() {
+        let (bot, attempts) = silent_bot(Duration::from_secs(5));
+        let started = tokio::time::Instant::now();
+        let result = bot.call_api("get_status", json!({})).await;
+        assert!(matches!(result, Err(ApiError::Timeout)));
+        assert_eq!(started.elapsed(), Duration::from_secs(5));
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_call_target() {
+        assert_eq!(
+            call_target(
+                "send_group_msg",
+                &json!({ "group_id": 123, "message": "hi" })
+            ),
+            Some(Target::group("123"))
+        );
+        assert_eq!(
+            call_target("send_msg", &json!({ "user_id": "42", "message": "hi" })),
+            Some(Target::user("42"))
+        );
+        assert_eq!(
+            call_target("get_stranger_info", &json!({ "user_id": 42 })),
+            None
+        );
+        assert_eq!(call_target("get_status", &json!({})), None);
+        for action in [
+            "upload_group_file",
+            "get_group_member_info",
+            "set_group_ban",
+        ] {
+            assert_eq!(call_target(action, &json!({ "group_id": 123 })), None);
+        }
+    }
+}
@@ -15,6 +15,18 @@
 //!         type: ws-client
 //!         url: ws://127.0.0.1:6700/ws
 //!         access_token: ${BOT_TOKEN:-}
+//!         headers:
+//!           X-Client-Role: Universal
+//!         # For wss:// URLs with a private CA or mutual TLS:
+//!         # tls_ca_file: /etc/alloy/ca.pem
+//!         # tls_client_cert: /etc/alloy/client.pem
+//!         # tls_client_key: /etc/alloy/client-key.pem
+//!         # Proxy for outbound connections (HTTPS_PROXY etc. are used otherwise):
+//!         # proxy: socks5h://127.0.0.1:1080
+//!         # proxy_username: alice
+//!         # proxy_password: ${PROXY_PASSWORD:-}
+//!         # no_proxy: [localhost, 10.0.0.0/8]
+//!         # proxy_from_env: false
 //!
 //!       # WebSocket server - listen for incoming connections
 //!       - name: listener
@@ -23,6 +35,15 @@
 //!         host: 0.0.0.0
 //!         port: 8080
 //!         path: /onebot/v11/ws
+//!         # Serve wss:// instead of ws:// (both files are needed)
+//!         # tls_cert: /etc/alloy/cert.pem
+//!         # tls_key: /etc/alloy/key.pem
+//!         ping_interval_secs: 30       # drop clients not answering within
+//!         pong_timeout_secs: 10        # pong_timeout_secs of a ping
+//!         # max_connections: 100
+//!         # max_connections_per_ip: 4
+//!         # send_overflow: drop-oldest # or block (default), disconnect
+//!         api_timeout_ms: 30000        # default API call timeout
 //!
 //!       # HTTP webhook (receive events)
 //!       - name: webhook
@@ -31,6 +52,8 @@
 //!         host: 0.0.0.0
 //!         port: 9000
 //!         path: /onebot/callback
+//!         secret: ${WEBHOOK_SECRET:-}   # verify X-Signature when set
+//!         access_token: ${BOT_TOKEN:-}  # required of SSE subscribers (GET)
 //!
 //!       # HTTP client (send API calls)
 //!       - name: api-client
@@ -38,10 +61,28 @@
 //!         type: http-client
 //!         api_url: http://127.0.0.1:5700
 //!
+//!       # Same-host deployments can use Unix sockets instead of TCP ports
+//!       - name: local
+//!         enabled: false
+//!         type: ws-server
+//!         unix_socket: /run/alloy/onebot.sock
+//!         socket_mode: "660"
+//!         path: /onebot/v11/ws
+//!       - name: local-api
+//!         enabled: false
+//!         type: http-client
+//!         api_url: http://localhost
+//!         unix_socket: /run/onebot/api.sock
+//!
 //!     # Global settings for all connections
 //!     heartbeat_interval_secs: 30
 //! ```
 
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use alloy_core::{ProxyConfig, SendOverflow, ServerTlsConfig, TransportError, TransportResult};
 use serde::{Deserialize, Serialize};
 
 /// OneBot adapter configuration.
@@ -151,6 +192,51 @@ pub struct WsServerConfig {
 
     /// Access token for authentication.
     pub access_token: Option<String>,
+
+    /// Listen on this Unix socket instead of `host`/`port`.
+    pub unix_socket: Option<PathBuf>,
+
+    /// Octal permissions of the socket file (default: "660"; requires
+    /// `unix_socket`).
+    pub socket_mode: Option<String>,
+
+    /// Serve TLS with this PEM certificate chain (requires `tls_key`; not
+    /// with `unix_socket`).
+    ///
+    /// Listeners sharing `host`/`port` must use the same files.  Changes to
+    /// the files are picked up without a restart.
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM private key of `tls_cert`.
+    pub tls_key: Option<PathBuf>,
+
+    /// Seconds between pings to each client (default: 30, 0 disables).
+    pub ping_interval_secs: u64,
+
+    /// Seconds a client may take to answer a ping before it is disconnected
+    /// (default: 10).
+    pub pong_timeout_secs: u64,
+
+    /// Disconnect clients that send no message for this many seconds
+    /// (default: 0, never).
+    pub idle_timeout_secs: u64,
+
+    /// Maximum concurrent connections on this listener.
+    pub max_connections: Option<usize>,
+
+    /// Maximum concurrent connections from one IP address.
+    pub max_connections_per_ip: Option<usize>,
+
+    /// Outgoing messages queued per connection (default: 256).
+    pub send_queue_capacity: usize,
+
+    /// What to do when a client falls that far behind: `block` (default),
+    /// `drop-oldest` or `disconnect`.
+    pub send_overflow: SendOverflow,
+
+    /// Default API call timeout in milliseconds (default: 30000); slow
+    /// actions such as file uploads use longer ones.
+    pub api_timeout_ms: u64,
 }
 
 impl Default for WsServerConfig {
@@ -162,17 +248,99 @@ impl Default for WsServerConfig {
             port: 8080,
             path: "/onebot/v11/ws".to_string(),
             access_token: None,
+            unix_socket: None,
+            socket_mode: None,
+            tls_cert: None,
+            tls_key: None,
+            ping_interval_secs: 30,
+            pong_timeout_secs: 10,
+            idle_timeout_secs: 0,
+            max_connections: None,
+            max_connections_per_ip: None,
+            send_queue_capacity: 256,
+            send_overflow: SendOverflow::default(),
+            api_timeout_ms: 30000,
         }
     }
 }
 
 impl WsServerConfig {
-    /// Returns the bind address string.
+    /// Returns the bind address: `host:port`, or `unix:<path>` with
+    /// `unix_socket`.
     pub fn bind_addr(&self) -> String {
-        format!("{}:{}", self.host, self.port)
+        bind_addr(&self.host, self.port, self.unix_socket.as_deref())
+    }
+
+    /// Returns the transport settings of this listener.
+    ///
+    /// Fails if the TLS files or the socket mode do not fit the listener.
+    pub fn listen_config(&self) -> TransportResult<alloy_core::WsServerConfig> {
+        let secs = |secs| Some(secs).filter(|&s| s > 0).map(Duration::from_secs);
+        let mut config = alloy_core::WsServerConfig::new(self.bind_addr(), &self.path)
+            .with_ping(
+                secs(self.ping_interval_secs),
+                Duration::from_secs(self.pong_timeout_secs),
+            )
+            .with_idle_timeout(secs(self.idle_timeout_secs))
+            .with_send_queue(self.send_queue_capacity, self.send_overflow)
+            .with_call_timeout(Duration::from_millis(self.api_timeout_ms));
+        config.max_connections = self.max_connections;
+        config.max_connections_per_ip = self.max_connections_per_ip;
+        config.tls = listener_tls(
+            self.unix_socket.as_deref(),
+            self.tls_cert.as_deref(),
+            self.tls_key.as_deref(),
+        )?;
+        config.socket_mode =
+            listener_socket_mode(self.unix_socket.as_deref(), self.socket_mode.as_deref())?;
+        Ok(config)
+    }
+}
+
+/// Formats a listener address: `host:port`, or `unix:<path>`.
+fn bind_addr(host: &str, port: u16, unix_socket: Option<&Path>) -> String {
+    match unix_socket {
+        Some(path) => format!("unix:{}", path.display()),
+        None => format!("{host}:{port}"),
+    }
+}
+
+/// Returns the TLS files of a listener, refusing a lone `tls_cert` or
+/// `tls_key` and TLS on a Unix socket.
+fn listener_tls(
+    unix_socket: Option<&Path>,
+    tls_cert: Option<&Path>,
+    tls_key: Option<&Path>,
+) -> TransportResult<Option<ServerTlsConfig>> {
+    let invalid = |reason: &str| Err(TransportError::InvalidConfig(reason.to_string()));
+    match (tls_cert, tls_key) {
+        (None, None) => Ok(None),
+        (Some(_), Some(_)) if unix_socket.is_some() => {
+            invalid("`tls_cert`/`tls_key` cannot be used with `unix_socket`")
+        }
+        (Some(cert), Some(key)) => Ok(Some(ServerTlsConfig::new(cert, key))),
+        _ => invalid("`tls_cert` and `tls_key` go together"),
     }
 }
 
+/// Parses the octal `socket_mode` of a listener on `unix_socket`.
+fn listener_socket_mode(
+    unix_socket: Option<&Path>,
+    socket_mode: Option<&str>,
+) -> TransportResult<Option<u32>> {
+    let Some(mode) = socket_mode else {
+        return Ok(None);
+    };
+    if unix_socket.is_none() {
+        return Err(TransportError::InvalidConfig(
+            "`socket_mode` needs `unix_socket`".into(),
+        ));
+    }
+    u32::from_str_radix(mode, 8)
+        .map(Some)
+        .map_err(|_| TransportError::InvalidConfig(format!("invalid socket mode `{mode}`")))
+}
+
 /// WebSocket client configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -189,11 +357,43 @@ pub struct WsClientConfig {
     /// Access token for authentication.
     pub access_token: Option<String>,
 
+    /// Extra headers sent with the handshake.
+    pub headers: HashMap<String, String>,
+
     /// Whether to automatically reconnect on disconnection.
     pub auto_reconnect: bool,
 
-    /// Reconnection delay in milliseconds.
+    /// Delay before the first reconnect attempt, in milliseconds; later
+    /// attempts back off exponentially from it.
     pub reconnect_delay_ms: u64,
+
+    /// Milliseconds allowed for each connection attempt, handshake included
+    /// (default: 10000).
+    pub connect_timeout_ms: u64,
+
+    /// Connect through this Unix socket; `url` then only supplies the path.
+    pub unix_socket: Option<PathBuf>,
+
+    /// Extra PEM CA certificates to trust for `wss://` URLs.
+    pub tls_ca_file: Option<PathBuf>,
+
+    /// PEM client certificate chain, for servers requiring mutual TLS.
+    pub tls_client_cert: Option<PathBuf>,
+
+    /// PEM private key of `tls_client_cert`.
+    pub tls_client_key: Option<PathBuf>,
+
+    /// Accept any server certificate.  For testing with self-signed
+    /// certificates only.
+    pub tls_insecure_skip_verify: bool,
+
+    /// Default API call timeout in milliseconds (default: 30000); slow
+    /// actions such as file uploads use longer ones.
+    pub api_timeout_ms: u64,
+
+    /// Outbound proxy.
+    #[serde(flatten)]
+    pub proxy: ProxySettings,
 }
 
 impl Default for WsClientConfig {
@@ -203,8 +403,17 @@ impl Default for WsClientConfig {
             enabled: true,
             url: "ws://127.0.0.1:6700/ws".to_string(),
             access_token: None,
+            headers: HashMap::new(),
             auto_reconnect: true,
             reconnect_delay_ms: 5000,
+            connect_timeout_ms: 10000,
+            unix_socket: None,
+            tls_ca_file: None,
+            tls_client_cert: None,
+            tls_client_key: None,
+            tls_insecure_skip_verify: false,
+            api_timeout_ms: 30000,
+            proxy: ProxySettings::default(),
         }
     }
 }
@@ -229,7 +438,38 @@ pub struct HttpServerConfig {
     pub path: String,
 
     /// Secret for verifying webhook signatures.
+    ///
+    /// When set, every POST must carry a matching
+    /// `X-Signature: sha1=<HMAC-SHA1 of the body>` header or is rejected
+    /// with 401.
     pub secret: Option<String>,
+
+    /// Access token SSE subscribers must present, like WebSocket clients do.
+    ///
+    /// Falls back to `default_access_token`, then to `secret`; a path
+    /// without any of them refuses SSE subscriptions.
+    pub access_token: Option<String>,
+
+    /// Listen on this Unix socket instead of `host`/`port`.
+    pub unix_socket: Option<PathBuf>,
+
+    /// Octal permissions of the socket file (default: "660"; requires
+    /// `unix_socket`).
+    pub socket_mode: Option<String>,
+
+    /// Serve TLS with this PEM certificate chain (requires `tls_key`; not
+    /// with `unix_socket`).
+    ///
+    /// Listeners sharing `host`/`port` must use the same files.  Changes to
+    /// the files are picked up without a restart.
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM private key of `tls_cert`.
+    pub tls_key: Option<PathBuf>,
+
+    /// Milliseconds a webhook request waits for a quick operation before it
+    /// is answered with an empty response (default: 5000).
+    pub response_timeout_ms: u64,
 }
 
 impl Default for HttpServerConfig {
@@ -241,14 +481,37 @@ impl Default for HttpServerConfig {
             port: 9000,
             path: "/onebot/callback".to_string(),
             secret: None,
+            access_token: None,
+            unix_socket: None,
+            socket_mode: None,
+            tls_cert: None,
+            tls_key: None,
+            response_timeout_ms: 5000,
         }
     }
 }
 
 impl HttpServerConfig {
-    /// Returns the bind address string.
+    /// Returns the bind address: `host:port`, or `unix:<path>` with
+    /// `unix_socket`.
     pub fn bind_addr(&self) -> String {
-        format!("{}:{}", self.host, self.port)
+        bind_addr(&self.host, self.port, self.unix_socket.as_deref())
+    }
+
+    /// Returns the transport settings of this listener.
+    ///
+    /// Fails if the TLS files or the socket mode do not fit the listener.
+    pub fn listen_config(&self) -> TransportResult<alloy_core::HttpServerConfig> {
+        let mut config = alloy_core::HttpServerConfig::new(self.bind_addr(), &self.path)
+            .with_response_deadline(Duration::from_millis(self.response_timeout_ms));
+        config.tls = listener_tls(
+            self.unix_socket.as_deref(),
+            self.tls_cert.as_deref(),
+            self.tls_key.as_deref(),
+        )?;
+        config.socket_mode =
+            listener_socket_mode(self.unix_socket.as_deref(), self.socket_mode.as_deref())?;
+        Ok(config)
     }
 }
 
@@ -272,8 +535,17 @@ pub struct HttpClientConfig {
     /// Access token for authentication.
     pub access_token: Option<String>,
 
-    /// Request timeout in milliseconds.
+    /// Request timeout in milliseconds, the default for API calls (slow
+    /// actions such as file uploads use longer ones).
     pub timeout_ms: u64,
+
+    /// Send requests through this Unix socket; `api_url` then only supplies
+    /// the path.
+    pub unix_socket: Option<PathBuf>,
+
+    /// Outbound proxy; ignored with `unix_socket`.
+    #[serde(flatten)]
+    pub proxy: ProxySettings,
 }
 
 impl Default for HttpClientConfig {
@@ -285,6 +557,56 @@ impl Default for HttpClientConfig {
             api_url: "http://127.0.0.1:5700".to_string(),
             access_token: None,
             timeout_ms: 30000,
+            unix_socket: None,
+            proxy: ProxySettings::default(),
+        }
+    }
+}
+
+/// Proxy settings shared by the client connections.
+///
+/// Without `proxy`, `HTTPS_PROXY` / `HTTP_PROXY` / `ALL_PROXY` and `NO_PROXY`
+/// are read from the environment unless `proxy_from_env` is off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxySettings {
+    /// Proxy URL: `http://`, `socks5://` or `socks5h://` (proxy-side DNS).
+    pub proxy: Option<String>,
+
+    /// Proxy user name; overrides credentials in `proxy`.
+    pub proxy_username: Option<String>,
+
+    /// Proxy password.
+    pub proxy_password: Option<String>,
+
+    /// Hosts, IP addresses or CIDR ranges to reach directly.
+    pub no_proxy: Vec<String>,
+
+    /// Whether to use the proxy environment variables when `proxy` is unset.
+    pub proxy_from_env: bool,
+}
+
+impl Default for ProxySettings {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            proxy_username: None,
+            proxy_password: None,
+            no_proxy: Vec::new(),
+            proxy_from_env: true,
+        }
+    }
+}
+
+impl ProxySettings {
+    /// Converts to the transport-level proxy configuration.
+    pub fn to_proxy_config(&self) -> ProxyConfig {
+        ProxyConfig {
+            url: self.proxy.clone(),
+            username: self.proxy_username.clone(),
+            password: self.proxy_password.clone(),
+            no_proxy: self.no_proxy.clone(),
+            from_env: self.proxy_from_env,
         }
     }
 }
@@ -303,7 +625,7 @@ mod tests {
                     host: "0.0.0.0".to_string(),
                     port: 8080,
                     path: "/ws".to_string(),
-                    access_token: None,
+                    ..Default::default()
                 }),
                 ConnectionConfig::WsClient(WsClientConfig {
                     name: "backup-client".to_string(),
@@ -345,6 +667,105 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_unix_socket_listen_config() {
+        let mut config = WsServerConfig::default();
+        assert_eq!(config.bind_addr(), "0.0.0.0:8080");
+
+        config.unix_socket = Some("/run/alloy/onebot.sock".into());
+        config.socket_mode = Some("600".to_string());
+        let listen = config.listen_config().unwrap();
+        assert_eq!(listen.addr, "unix:/run/alloy/onebot.sock");
+        assert_eq!(listen.socket_mode, Some(0o600));
+
+        config.socket_mode = Some("rw".to_string());
+        assert!(config.listen_config().is_err());
+
+        // A socket mode without a socket is a mistake.
+        let config = WsServerConfig {
+            socket_mode: Some("600".to_string()),
+            ..Default::default()
+        };
+        assert!(config.listen_config().is_err());
+    }
+
+    #[test]
+    fn test_ws_server_listen_config() {
+        let config: WsServerConfig = serde_json::from_value(serde_json::json!({
+            "port": 6700,
+            "path": "/ws",
+            "ping_interval_secs": 0,
+            "idle_timeout_secs": 120,
+            "max_connections_per_ip": 2,
+            "send_overflow": "drop-oldest",
+        }))
+        .unwrap();
+        let listen = config.listen_config().unwrap();
+
+        assert_eq!(listen.addr, "0.0.0.0:6700");
+        assert_eq!(listen.path, "/ws");
+        assert_eq!(listen.ping_interval, None);
+        assert_eq!(listen.pong_timeout, Duration::from_secs(10));
+        assert_eq!(listen.idle_timeout, Some(Duration::from_secs(120)));
+        assert_eq!(listen.max_connections, None);
+        assert_eq!(listen.max_connections_per_ip, Some(2));
+        assert_eq!(listen.send_queue_capacity, 256);
+        assert_eq!(listen.send_overflow, SendOverflow::DropOldest);
+        assert_eq!(listen.call_timeout, Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_client_proxy_settings() {
+        let config: ConnectionConfig = serde_json::from_value(serde_json::json!({
+            "type": "ws-client",
+            "url": "wss://example.com/ws",
+            "proxy": "socks5h://127.0.0.1:1080",
+            "proxy_username": "alice",
+            "no_proxy": ["localhost"],
+        }))
+        .unwrap();
+        let ConnectionConfig::WsClient(ws) = config else {
+            panic!("expected a ws-client connection");
+        };
+        let proxy = ws.proxy.to_proxy_config();
+        assert_eq!(proxy.url.as_deref(), Some("socks5h://127.0.0.1:1080"));
+        assert_eq!(proxy.username.as_deref(), Some("alice"));
+        assert_eq!(proxy.no_proxy, ["localhost"]);
+        assert!(proxy.from_env);
+
+        let http: HttpClientConfig =
+            serde_json::from_value(serde_json::json!({ "proxy_from_env": false })).unwrap();
+        let proxy = http.proxy.to_proxy_config();
+        assert_eq!(proxy.url, None);
+        assert!(!proxy.from_env);
+    }
+
+    #[test]
+    fn test_tls_listen_config() {
+        let mut config = HttpServerConfig {
+            tls_cert: Some("/etc/alloy/cert.pem".into()),
+            ..Default::default()
+        };
+        // Either file alone is refused.
+        assert!(config.listen_config().is_err());
+
+        config.tls_key = Some("/etc/alloy/key.pem".into());
+        let listen = config.listen_config().unwrap();
+        assert_eq!(listen.addr, "0.0.0.0:9000");
+        assert_eq!(
+            listen.tls,
+            Some(ServerTlsConfig::new(
+                "/etc/alloy/cert.pem",
+                "/etc/alloy/key.pem"
+            ))
+        );
+
+        // TLS is not served on Unix sockets.
+        config.unix_socket = Some("/run/alloy/onebot.sock".into());
+        let err = config.listen_config().unwrap_err();
+        assert!(err.to_string().contains("unix_socket"), "{err}");
+    }
+
     #[test]
     fn test_enabled_connections() {
         let config = OneBotConfig {
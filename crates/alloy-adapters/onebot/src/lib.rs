@@ -32,6 +32,7 @@
 
 mod adapter;
 mod api_caller;
+mod auth;
 pub mod bot;
 pub mod config;
 pub mod model;
@@ -39,8 +40,8 @@ pub mod model;
 pub use adapter::OneBotAdapter;
 pub use bot::OneBotBot;
 pub use config::{
-    ConnectionConfig, HttpClientConfig, HttpServerConfig, OneBotConfig, WsClientConfig,
-    WsServerConfig,
+    ConnectionConfig, HttpClientConfig, HttpServerConfig, OneBotConfig, ProxySettings,
+    WsClientConfig, WsServerConfig,
 };
 
 // Re-export segment types
@@ -64,9 +65,10 @@ pub use model::api::{
 
 // Re-export event types
 pub use model::event::{
-    FriendAddEvent, FriendRecallEvent, FriendRequestEvent, GroupAdminEvent, GroupBanEvent,
-    GroupDecreaseEvent, GroupIncreaseEvent, GroupMessageEvent, GroupRecallEvent, GroupRequestEvent,
-    GroupUploadEvent, HeartbeatEvent, HonorEvent, LifecycleEvent, LuckyKingEvent, MessageEvent,
-    MetaEvent, NoticeEvent, NotifyEvent, OneBotEvent, PokeEvent, PrivateMessageEvent, RequestEvent,
+    EssenceEvent, FriendAddEvent, FriendRecallEvent, FriendRequestEvent, GroupAdminEvent,
+    GroupBanEvent, GroupCardEvent, GroupDecreaseEvent, GroupIncreaseEvent, GroupMessageEvent,
+    GroupRecallEvent, GroupRequestEvent, GroupUploadEvent, HeartbeatEvent, HonorEvent,
+    LifecycleEvent, LuckyKingEvent, MessageEvent, MetaEvent, NoticeEvent, NotifyEvent, OfflineFile,
+    OfflineFileEvent, OneBotEvent, PokeEvent, PrivateMessageEvent, RequestEvent, TitleEvent,
     UploadedFile,
 };
@@ -18,8 +18,9 @@
 //! │   └── GroupMessageEvent   { group_id, anonymous, sub_type }
 //! ├── NoticeEvent {}                                     ← type = "notice"
 //! │   ├── GroupUploadEvent, GroupAdminEvent, …
+//! │   ├── GroupCardEvent, OfflineFileEvent, EssenceEvent  (extensions)
 //! │   └── NotifyEvent { group_id, user_id }
-//! │       ├── PokeEvent, LuckyKingEvent, HonorEvent
+//! │       ├── PokeEvent, LuckyKingEvent, HonorEvent, TitleEvent
 //! ├── RequestEvent {}                                    ← type = "request"
 //! │   ├── FriendRequestEvent
 //! │   └── GroupRequestEvent
@@ -37,7 +38,7 @@
 
 use std::sync::Arc;
 
-use alloy_core::BoxedEvent;
+use alloy_core::{BoxedEvent, ResponseSlot};
 use alloy_macros::BotEvent;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -55,6 +56,7 @@ pub struct OneBotEvent {
     /// Unix timestamp when the event occurred.
     pub time: i64,
     /// Bot's QQ ID.
+    #[event(bot_id)]
     pub self_id: i64,
     /// Post type discriminator (e.g. "message", "notice", "request", "meta_event").
     pub post_type: String,
@@ -62,6 +64,9 @@ pub struct OneBotEvent {
     #[serde(skip)]
     #[event(raw_json)]
     raw: Option<Arc<str>>,
+    /// Where a quick operation for this event is sent (HTTP webhooks only).
+    #[serde(skip)]
+    response: Option<ResponseSlot>,
 }
 
 impl OneBotEvent {
@@ -69,6 +74,16 @@ impl OneBotEvent {
     pub fn set_raw(&mut self, raw: &str) {
         self.raw = Some(Arc::from(raw));
     }
+
+    /// Attaches the slot the HTTP response to this event is written to.
+    pub fn set_response(&mut self, response: ResponseSlot) {
+        self.response = Some(response);
+    }
+
+    /// Returns the response slot, if the event arrived as an HTTP request.
+    pub fn response(&self) -> Option<&ResponseSlot> {
+        self.response.as_ref()
+    }
 }
 
 /// Message event with common fields.
@@ -128,6 +143,7 @@ pub struct GroupMessageEvent {
     /// Sub-type ("normal", "anonymous", "notice").
     pub sub_type: String,
     /// Group ID.
+    #[event(group_id)]
     pub group_id: i64,
     /// Anonymous user info (if anonymous).
     #[serde(default)]
@@ -164,6 +180,7 @@ pub struct GroupUploadEvent {
     #[serde(flatten)]
     pub parent: NoticeEvent,
 
+    #[event(group_id)]
     pub group_id: i64,
     #[event(user_id)]
     pub user_id: i64,
@@ -178,6 +195,7 @@ pub struct GroupAdminEvent {
     pub parent: NoticeEvent,
 
     pub sub_type: String,
+    #[event(group_id)]
     pub group_id: i64,
     #[event(user_id)]
     pub user_id: i64,
@@ -191,6 +209,7 @@ pub struct GroupDecreaseEvent {
     pub parent: NoticeEvent,
 
     pub sub_type: String,
+    #[event(group_id)]
     pub group_id: i64,
     pub operator_id: i64,
     #[event(user_id)]
@@ -205,6 +224,7 @@ pub struct GroupIncreaseEvent {
     pub parent: NoticeEvent,
 
     pub sub_type: String,
+    #[event(group_id)]
     pub group_id: i64,
     pub operator_id: i64,
     #[event(user_id)]
@@ -219,6 +239,7 @@ pub struct GroupBanEvent {
     pub parent: NoticeEvent,
 
     pub sub_type: String,
+    #[event(group_id)]
     pub group_id: i64,
     pub operator_id: i64,
     #[event(user_id)]
@@ -244,6 +265,7 @@ pub struct GroupRecallEvent {
     #[serde(flatten)]
     pub parent: NoticeEvent,
 
+    #[event(group_id)]
     pub group_id: i64,
     #[event(user_id)]
     pub user_id: i64,
@@ -263,7 +285,66 @@ pub struct FriendRecallEvent {
     pub message_id: i64,
 }
 
-/// Notify event with common fields shared by poke / lucky_king / honor.
+/// Group card (member nickname) change — a go-cqhttp extension.
+#[derive(Debug, Clone, Serialize, Deserialize, BotEvent)]
+#[event(name = "notice.group_card")]
+pub struct GroupCardEvent {
+    #[event(parent)]
+    #[serde(flatten)]
+    pub parent: NoticeEvent,
+
+    #[event(group_id)]
+    pub group_id: i64,
+    #[event(user_id)]
+    pub user_id: i64,
+    pub card_new: String,
+    #[serde(default)]
+    pub card_old: String,
+}
+
+/// File sent through a private offline transfer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineFile {
+    pub name: String,
+    pub size: i64,
+    #[serde(default)]
+    pub url: String,
+}
+
+/// Offline file received from a friend — a go-cqhttp extension.
+#[derive(Debug, Clone, Serialize, Deserialize, BotEvent)]
+#[event(name = "notice.offline_file")]
+pub struct OfflineFileEvent {
+    #[event(parent)]
+    #[serde(flatten)]
+    pub parent: NoticeEvent,
+
+    #[event(user_id)]
+    pub user_id: i64,
+    pub file: OfflineFile,
+}
+
+/// Message added to (`sub_type = "add"`) or removed from (`"delete"`) the
+/// group's essence list — a go-cqhttp extension.
+#[derive(Debug, Clone, Serialize, Deserialize, BotEvent)]
+#[event(name = "notice.essence")]
+pub struct EssenceEvent {
+    #[event(parent)]
+    #[serde(flatten)]
+    pub parent: NoticeEvent,
+
+    pub sub_type: String,
+    #[event(group_id)]
+    pub group_id: i64,
+    /// Author of the message.
+    #[event(user_id)]
+    pub sender_id: i64,
+    pub operator_id: i64,
+    pub message_id: i64,
+}
+
+/// Notify event with common fields shared by poke / lucky_king / honor /
+/// title.
 ///
 /// `Deref` → [`NoticeEvent`] → [`OneBotEvent`].
 #[derive(Debug, Clone, Serialize, Deserialize, BotEvent)]
@@ -286,6 +367,7 @@ pub struct PokeEvent {
     pub parent: NotifyEvent,
 
     #[serde(default)]
+    #[event(group_id)]
     pub group_id: Option<i64>,
     pub target_id: i64,
 }
@@ -297,6 +379,7 @@ pub struct LuckyKingEvent {
     #[serde(flatten)]
     pub parent: NotifyEvent,
 
+    #[event(group_id)]
     pub group_id: i64,
     pub target_id: i64,
 }
@@ -308,10 +391,24 @@ pub struct HonorEvent {
     #[serde(flatten)]
     pub parent: NotifyEvent,
 
+    #[event(group_id)]
     pub group_id: i64,
     pub honor_type: String,
 }
 
+/// Group member title change — a go-cqhttp extension.
+#[derive(Debug, Clone, Serialize, Deserialize, BotEvent)]
+#[event(name = "notice.notify.title")]
+pub struct TitleEvent {
+    #[event(parent)]
+    #[serde(flatten)]
+    pub parent: NotifyEvent,
+
+    #[event(group_id)]
+    pub group_id: i64,
+    pub title: String,
+}
+
 /// Request event base — matches any event with `post_type = "request"`.
 #[derive(Debug, Clone, Serialize, Deserialize, BotEvent)]
 #[event(name = "request", type = "request")]
@@ -344,6 +441,7 @@ pub struct GroupRequestEvent {
     pub parent: RequestEvent,
 
     pub sub_type: String,
+    #[event(group_id)]
     pub group_id: i64,
     #[event(user_id)]
     pub user_id: i64,
@@ -403,6 +501,21 @@ pub struct HeartbeatEvent {
 ///
 /// The adapter calls this from `parse_event`.
 pub fn parse_onebot_event(raw: &str) -> serde_json::Result<BoxedEvent> {
+    parse_with_response(raw, None)
+}
+
+/// Parses a webhook request body, attaching `response` so handlers can
+/// answer it with a quick operation.
+///
+/// The adapter calls this from `parse_request`.
+pub fn parse_onebot_request(raw: &str, response: ResponseSlot) -> serde_json::Result<BoxedEvent> {
+    parse_with_response(raw, Some(response))
+}
+
+fn parse_with_response(
+    raw: &str,
+    response: Option<ResponseSlot>,
+) -> serde_json::Result<BoxedEvent> {
     // Pre-parse to extract type discriminators
     let v: Value = serde_json::from_str(raw)?;
     let post_type = v.get("post_type").and_then(|v| v.as_str()).unwrap_or("");
@@ -411,6 +524,9 @@ pub fn parse_onebot_event(raw: &str) -> serde_json::Result<BoxedEvent> {
         ($ty:ty) => {{
             let mut event: $ty = serde_json::from_value(v)?;
             event.set_raw(raw);
+            if let Some(response) = response {
+                event.set_response(response);
+            }
             Ok(Arc::new(event))
         }};
     }
@@ -435,12 +551,16 @@ pub fn parse_onebot_event(raw: &str) -> serde_json::Result<BoxedEvent> {
                 "friend_add" => attach_raw!(FriendAddEvent),
                 "group_recall" => attach_raw!(GroupRecallEvent),
                 "friend_recall" => attach_raw!(FriendRecallEvent),
+                "group_card" => attach_raw!(GroupCardEvent),
+                "offline_file" => attach_raw!(OfflineFileEvent),
+                "essence" => attach_raw!(EssenceEvent),
                 "notify" => {
                     let sub_type = v.get("sub_type").and_then(|v| v.as_str()).unwrap_or("");
                     match sub_type {
                         "poke" => attach_raw!(PokeEvent),
                         "lucky_king" => attach_raw!(LuckyKingEvent),
                         "honor" => attach_raw!(HonorEvent),
+                        "title" => attach_raw!(TitleEvent),
                         _ => attach_raw!(NotifyEvent),
                     }
                 }
@@ -470,7 +590,111 @@ pub fn parse_onebot_event(raw: &str) -> serde_json::Result<BoxedEvent> {
             // Unknown post_type — fall back to root event
             let mut event: OneBotEvent = serde_json::from_value(v)?;
             event.set_raw(raw);
+            if let Some(response) = response {
+                event.set_response(response);
+            }
             Ok(Arc::new(event))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_core::SceneKind;
+
+    fn message_json(message_type: &str, group_id: Option<i64>) -> String {
+        let mut v = serde_json::json!({
+            "time": 0,
+            "self_id": 10001,
+            "post_type": "message",
+            "message_type": message_type,
+            "sub_type": "normal",
+            "message_id": 1,
+            "user_id": 12345,
+            "message": "hi",
+            "raw_message": "hi",
+            "font": 0,
+            "sender": { "user_id": 12345 },
+        });
+        if let Some(id) = group_id {
+            v["group_id"] = id.into();
+        }
+        v.to_string()
+    }
+
+    #[test]
+    fn test_group_message_session() {
+        let event = parse_onebot_event(&message_json("group", Some(678))).unwrap();
+        let session = event.session().unwrap();
+        assert_eq!(session.platform, "onebot");
+        assert_eq!(session.bot_id, "10001");
+        assert_eq!(session.scene, SceneKind::Group);
+        assert_eq!(session.scene_id, "678");
+        assert_eq!(session.user_id.as_deref(), Some("12345"));
+    }
+
+    #[test]
+    fn test_private_message_session() {
+        let event = parse_onebot_event(&message_json("private", None)).unwrap();
+        let session = event.session().unwrap();
+        assert!(session.is_private());
+        assert_eq!(session.scene_id, "12345");
+        assert_eq!(session.to_string(), "onebot:10001:private:12345:12345");
+    }
+
+    #[test]
+    fn test_extension_notice_sessions() {
+        let notice = |fields: serde_json::Value| {
+            let mut v = serde_json::json!({ "time": 0, "self_id": 10001, "post_type": "notice" });
+            v.as_object_mut()
+                .unwrap()
+                .extend(fields.as_object().unwrap().clone());
+            parse_onebot_event(&v.to_string()).unwrap()
+        };
+
+        let event = notice(serde_json::json!({
+            "notice_type": "group_card", "group_id": 678, "user_id": 12345,
+            "card_new": "alice", "card_old": "",
+        }));
+        assert_eq!(event.event_name(), "onebot.notice.group_card");
+        assert_eq!(event.session().unwrap().scene_id, "678");
+
+        let event = notice(serde_json::json!({
+            "notice_type": "offline_file", "user_id": 12345,
+            "file": { "name": "a.txt", "size": 3, "url": "" },
+        }));
+        let session = event.session().unwrap();
+        assert!(session.is_private());
+        assert_eq!(session.scene_id, "12345");
+
+        let event = notice(serde_json::json!({
+            "notice_type": "notify", "sub_type": "title", "group_id": 678,
+            "user_id": 12345, "title": "elder",
+        }));
+        assert_eq!(event.event_name(), "onebot.notice.notify.title");
+        let session = event.session().unwrap();
+        assert_eq!(session.scene, SceneKind::Group);
+        assert_eq!(session.user_id.as_deref(), Some("12345"));
+    }
+
+    #[tokio::test]
+    async fn test_request_carries_response_slot() {
+        let (slot, rx) = ResponseSlot::channel();
+        let event = parse_onebot_request(&message_json("group", Some(678)), slot).unwrap();
+
+        let root = event
+            .downgrade_any(std::any::TypeId::of::<OneBotEvent>())
+            .and_then(|e| e.downcast::<OneBotEvent>().ok())
+            .unwrap();
+        assert!(root.response().unwrap().respond(b"{}".to_vec()));
+        assert_eq!(rx.await.unwrap(), b"{}");
+
+        let event = parse_onebot_event(&message_json("group", Some(678))).unwrap();
+        let root = event
+            .downgrade_any(std::any::TypeId::of::<OneBotEvent>())
+            .and_then(|e| e.downcast::<OneBotEvent>().ok())
+            .unwrap();
+        assert!(root.response().is_none());
+    }
+}
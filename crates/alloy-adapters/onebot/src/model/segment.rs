@@ -157,7 +157,16 @@ impl MessageSegmentTrait for Segment {
         match self {
             Segment::Text(data) => Some(RichTextSegment::Text(data.text.clone())),
             Segment::Image(data) => Some(RichTextSegment::Image(data.file.clone())),
+            Segment::At(data) if data.qq == "all" => Some(RichTextSegment::AtAll),
             Segment::At(data) => Some(RichTextSegment::At(data.qq.clone())),
+            Segment::Reply(data) => Some(RichTextSegment::Reply(data.id.clone())),
+            Segment::Face(data) => Some(RichTextSegment::Face(data.id.clone())),
+            Segment::Record(data) => Some(RichTextSegment::Audio(data.file.clone())),
+            Segment::Video(data) => Some(RichTextSegment::Video(data.file.clone())),
+            Segment::Share(data) => Some(RichTextSegment::Link {
+                url: data.url.clone(),
+                title: Some(data.title.clone()),
+            }),
             _ => None,
         }
     }
@@ -167,6 +176,22 @@ impl MessageSegmentTrait for Segment {
             RichTextSegment::Text(s) => Some(Segment::text(s)),
             RichTextSegment::Image(r) => Some(Segment::image(r)),
             RichTextSegment::At(id) => Some(Segment::At(AtData { qq: id.clone() })),
+            RichTextSegment::AtAll => Some(Segment::at_all()),
+            RichTextSegment::Reply(id) => Some(Segment::reply(id)),
+            RichTextSegment::Face(id) => Some(Segment::Face(FaceData { id: id.clone() })),
+            RichTextSegment::Audio(r) => Some(Segment::record(r)),
+            RichTextSegment::Video(r) => Some(Segment::video(r)),
+            // Few implementations can send `share`, so links go out as text.
+            RichTextSegment::Link { url, title } => Some(Segment::text(match title {
+                Some(title) => format!("{title} {url}"),
+                None => url.clone(),
+            })),
+            // OneBot v11 has no file segment; files go through upload APIs,
+            // so only the name is sent along.
+            RichTextSegment::File(r) => {
+                Some(Segment::text(r.rsplit(['/', '\\']).next().unwrap_or(r)))
+            }
+            other => Some(Segment::text(other.to_string())),
         }
     }
 }
@@ -935,4 +960,42 @@ mod tests {
         assert!(!image.is_text());
         assert_eq!(image.as_text(), None);
     }
+
+    #[test]
+    fn test_rich_text_round_trip() {
+        let segments = [
+            Segment::at_all(),
+            Segment::reply("42"),
+            Segment::face(178),
+            Segment::record("a.amr"),
+            Segment::video("v.mp4"),
+        ];
+        for seg in segments {
+            let rich = seg.as_rich_text().unwrap();
+            assert_eq!(Segment::from_rich_text_segment(&rich), Some(seg));
+        }
+
+        assert_eq!(
+            Segment::at_all().as_rich_text(),
+            Some(RichTextSegment::AtAll)
+        );
+        // Links and files degrade to text.
+        let share = Segment::share("https://example.com", "Example");
+        assert_eq!(
+            Segment::from_rich_text_segment(&share.as_rich_text().unwrap()),
+            Some(Segment::text("Example https://example.com"))
+        );
+        let link = RichTextSegment::Link {
+            url: "https://example.com".into(),
+            title: None,
+        };
+        assert_eq!(
+            Segment::from_rich_text_segment(&link),
+            Some(Segment::text("https://example.com"))
+        );
+        assert_eq!(
+            Segment::from_rich_text_segment(&RichTextSegment::File("/tmp/f.zip".into())),
+            Some(Segment::text("f.zip"))
+        );
+    }
 }
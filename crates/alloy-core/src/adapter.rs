@@ -23,8 +23,9 @@
 //!     }
 //!
 //!     async fn on_start(&self, ctx: Arc<dyn AdapterContext>) -> AdapterResult<()> {
-//!         if let Some(ws) = ctx.transport().ws_server() {
-//!             let handle = ws.listen("0.0.0.0:8080", "/ws", ctx.as_connection_handler()).await?;
+//!         if let Some(ws) = ctx.transport().get::<WsServer>() {
+//!             let config = WsServerConfig::new("0.0.0.0:8080", "/ws");
+//!             let handle = ws(config, ctx.as_connection_handler()).await?;
 //!             ctx.add_listener(handle).await;
 //!         }
 //!         Ok(())
@@ -40,7 +41,8 @@ use crate::bot::BoxedBot;
 use crate::error::{AdapterResult, TransportResult};
 use crate::event::BoxedEvent;
 use crate::transport::{
-    ConnectionHandle, ConnectionHandler, ConnectionInfo, ListenerHandle, TransportContext,
+    ConnectionHandle, ConnectionHandler, ConnectionInfo, Frame, ListenerHandle, ResponseSlot,
+    TransportContext,
 };
 
 // =============================================================================
@@ -83,6 +85,22 @@ pub trait AdapterContext: Send + Sync {
 ///   — receives [`AdapterContext`] for transport access.
 #[async_trait]
 pub trait Adapter: Send + Sync {
+    /// Check the credentials of an incoming server connection.
+    ///
+    /// Called before the connection is accepted; an error rejects it with
+    /// `401 Unauthorized`.  The default accepts every connection.
+    fn authenticate(&self, _conn_info: &ConnectionInfo) -> TransportResult<()> {
+        Ok(())
+    }
+
+    /// Check the raw body of an incoming HTTP request, e.g. its signature.
+    ///
+    /// An error rejects the request with `401 Unauthorized` before any event
+    /// is parsed.  The default accepts every request.
+    fn verify_request(&self, _conn_info: &ConnectionInfo, _body: &[u8]) -> TransportResult<()> {
+        Ok(())
+    }
+
     /// Extract a bot ID from connection metadata.
     ///
     /// Called when a new transport connection is established.
@@ -103,14 +121,44 @@ pub trait Adapter: Send + Sync {
     /// (e.g., forwarding API responses to the bot instance).
     async fn parse_event(&self, bot: &BoxedBot, data: &[u8]) -> Option<BoxedEvent>;
 
+    /// Parse a frame received on a WebSocket-style connection into an event.
+    ///
+    /// Adapters whose protocol distinguishes text from binary frames, or
+    /// cares about close codes, override this.  The default ignores close
+    /// frames and passes the payload of the others to
+    /// [`parse_event`](Self::parse_event).
+    async fn parse_frame(&self, bot: &BoxedBot, frame: &Frame) -> Option<BoxedEvent> {
+        if frame.is_close() {
+            return None;
+        }
+        self.parse_event(bot, frame.payload()).await
+    }
+
+    /// Parse a request that can be answered through `response` into an event.
+    ///
+    /// Adapters supporting in-band answers (e.g. webhook quick operations)
+    /// keep `response` with the event; handlers answering later fill it.
+    /// The default ignores it and delegates to
+    /// [`parse_event`](Self::parse_event).
+    async fn parse_request(
+        &self,
+        bot: &BoxedBot,
+        data: &[u8],
+        response: ResponseSlot,
+    ) -> Option<BoxedEvent> {
+        drop(response);
+        self.parse_event(bot, data).await
+    }
+
     /// Called when the adapter should start.
     ///
     /// Use the context to access transport capabilities and register listeners.
     ///
     /// ```rust,ignore
     /// async fn on_start(&self, ctx: Arc<dyn AdapterContext>) -> AdapterResult<()> {
-    ///     if let Some(ws_server) = ctx.transport().ws_server() {
-    ///         let handle = ws_server.listen("0.0.0.0:8080", "/ws", ctx.as_connection_handler()).await?;
+    ///     if let Some(ws_server) = ctx.transport().get::<WsServer>() {
+    ///         let config = WsServerConfig::new("0.0.0.0:8080", "/ws");
+    ///         let handle = ws_server(config, ctx.as_connection_handler()).await?;
     ///         ctx.add_listener(handle).await;
     ///     }
     ///     Ok(())
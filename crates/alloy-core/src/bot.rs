@@ -9,9 +9,13 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use serde_json::Value;
 
-use crate::error::ApiResult;
+use crate::call::CallOptions;
+use crate::error::{ApiError, ApiResult};
 use crate::event::Event;
 use crate::message::ErasedMessage;
+use crate::outbound::OutboundScheduler;
+use crate::session::Target;
+use crate::transport::ConnectionHandle;
 
 /// The core Bot trait.
 ///
@@ -26,7 +30,9 @@ use crate::message::ErasedMessage;
 /// # API Design
 ///
 /// - `call_api`: Raw API call with action name and JSON parameters
+/// - `call_api_with`: The same, with a per-call timeout, retries and priority
 /// - `send`: Unified message sending that extracts session from event
+/// - `send_to`: Proactive message sending to an explicit [`Target`]
 ///
 /// Concrete implementations (e.g., `OneBotBot`) should provide
 /// strongly-typed API methods on top of `call_api`.
@@ -49,6 +55,33 @@ pub trait Bot: Send + Sync + 'static {
     /// The raw JSON response from the API.
     async fn call_api(&self, action: &str, params: Value) -> ApiResult<Value>;
 
+    /// Calls a raw API with per-call [`CallOptions`].
+    ///
+    /// The default implementation retries [`call_api`](Self::call_api) per
+    /// `options.retry` and enforces `options.timeout` on top of the
+    /// connection's own timeout, so it can shorten the wait but not extend
+    /// it; the priority is ignored.  Adapters override it to hand the
+    /// options down to the transport.
+    async fn call_api_with(
+        &self,
+        action: &str,
+        params: Value,
+        options: &CallOptions,
+    ) -> ApiResult<Value> {
+        options
+            .retry
+            .run(action, || async {
+                let call = self.call_api(action, params.clone());
+                match options.timeout {
+                    Some(timeout) => tokio::time::timeout(timeout, call)
+                        .await
+                        .map_err(|_| ApiError::Timeout)?,
+                    None => call.await,
+                }
+            })
+            .await
+    }
+
     /// Sends a message in response to an event.
     ///
     /// This method extracts the session information (user_id, group_id, etc.)
@@ -76,6 +109,33 @@ pub trait Bot: Send + Sync + 'static {
         message: &dyn ErasedMessage,
     ) -> ApiResult<String>;
 
+    /// Sends a rich (type-erased) message to an explicit target.
+    ///
+    /// Unlike [`send_message`](Self::send_message) this needs no originating
+    /// event, so it suits scheduled jobs, webhooks and relays.
+    ///
+    /// The default implementation returns [`ApiError::UnsupportedTarget`].
+    ///
+    /// # Returns
+    ///
+    /// The message ID if successful.
+    async fn send_to(&self, target: &Target, message: &dyn ErasedMessage) -> ApiResult<String> {
+        let _ = message;
+        Err(ApiError::UnsupportedTarget(target.clone()))
+    }
+
+    /// Returns the conversation a raw call of `action` posts a message into.
+    ///
+    /// Used to order and rate-limit calls per conversation when the bot is
+    /// wrapped in a [`ScheduledBot`](crate::outbound::ScheduledBot).  Calls
+    /// that merely name a group, like lookups or uploads, have none.
+    ///
+    /// The default implementation returns `None`.
+    fn call_target(&self, action: &str, params: &Value) -> Option<Target> {
+        let _ = (action, params);
+        None
+    }
+
     /// Returns self as an `Arc<dyn Any>` for safe downcasting.
     ///
     /// This method takes `Arc<Self>` to enable safe downcasting to concrete types
@@ -90,6 +150,45 @@ pub trait Bot: Send + Sync + 'static {
     /// ```
     fn as_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync>;
 
+    /// Offers an additional connection for an already registered bot.
+    ///
+    /// Called by the [`AdapterBridge`](crate::bridge::AdapterBridge) when a
+    /// second connection identifies as this bot.  Return `true` to keep it
+    /// (the bot then stays registered until every accepted connection has
+    /// closed) or `false` to ignore it.
+    ///
+    /// The default implementation returns `false`.
+    fn attach_connection(&self, connection: ConnectionHandle) -> bool {
+        let _ = connection;
+        false
+    }
+
+    /// Offers the scheduler that outbound calls should pass through.
+    ///
+    /// Called by the [`AdapterBridge`](crate::bridge::AdapterBridge) right
+    /// after the bot is created, when outbound limits are configured.  Return
+    /// `true` if every API call of the bot, including those made on behalf
+    /// of typed or message-sending methods, will hold an
+    /// [`OutboundPermit`](crate::outbound::OutboundPermit) from it.
+    ///
+    /// The default implementation returns `false`; the bridge then wraps the
+    /// bot in a [`ScheduledBot`](crate::outbound::ScheduledBot) instead,
+    /// which only sees the calls made through this trait.
+    fn attach_outbound(&self, scheduler: Arc<OutboundScheduler>) -> bool {
+        let _ = scheduler;
+        false
+    }
+
+    /// Called when one of several connections closes while others remain.
+    ///
+    /// When the last connection closes, [`on_disconnect`](Self::on_disconnect)
+    /// is called instead.
+    ///
+    /// The default implementation does nothing.
+    async fn detach_connection(&self, connection: &ConnectionHandle) {
+        let _ = connection;
+    }
+
     /// Called when the transport connection is lost.
     ///
     /// Implementations should clean up any pending state, such as:
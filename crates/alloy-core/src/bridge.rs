@@ -2,13 +2,26 @@
 //!
 //! The [`AdapterBridge`] sits between the transport layer and the adapter,
 //! handling common bot lifecycle (registration, event dispatch, cleanup) automatically.
+//! Registration and disconnection are announced to plugins as
+//! [`BotConnectedEvent`] / [`BotDisconnectedEvent`].
+//!
+//! A bot may have several connections (say, a reverse WebSocket plus an HTTP
+//! API client).  Later connections are offered to the existing bot through
+//! [`Bot::attach_connection`](crate::bot::Bot::attach_connection), and the bot
+//! stays registered until the last accepted connection closes.
+//!
+//! With [`with_outbound`](AdapterBridge::with_outbound) every new bot is
+//! given its own [`OutboundScheduler`], which rate-limits and queues the
+//! calls it makes.  Bots that do not take it themselves are wrapped in a
+//! [`ScheduledBot`] before they are registered or dispatched to.
+//!
 //! Its methods are organized into three traits to clarify who may call what:
 //!
 //! | Trait | Caller | Methods |
 //! |---|---|---|
-//! | [`ConnectionHandler`](crate::transport::ConnectionHandler) | transport layer | `get_bot_id`, `create_bot`, `on_message`, `on_disconnect`, `on_error` |
+//! | [`ConnectionHandler`](crate::transport::ConnectionHandler) | transport layer | `authenticate`, `verify_request`, `get_bot_id`, `create_bot`, `on_message`, `on_request`, `on_disconnect`, `on_error` |
 //! | [`AdapterContext`](crate::adapter::AdapterContext) | adapter implementation | `transport`, `add_listener`, `add_connection`, `get_bot` |
-//! | (direct methods) | runtime | `on_start`, `on_shutdown`, `bot_ids`, `bot_count` |
+//! | (direct methods) | runtime | `with_recorder`, `with_registry`, `with_outbound`, `on_start`, `on_shutdown`, `bot_ids`, `bot_count`, `connection_statuses` |
 //!
 //! # Architecture
 //!
@@ -31,9 +44,14 @@ use crate::adapter::{Adapter, AdapterContext};
 use crate::bot::BoxedBot;
 use crate::error::AdapterResult;
 use crate::event::{BoxedEvent, EventType};
+use crate::lifecycle::{BotConnectedEvent, BotDisconnectedEvent};
 use crate::message::RichText;
+use crate::outbound::{OutboundConfig, OutboundScheduler, ScheduledBot};
+use crate::record::EventRecorder;
+use crate::registry::BotRegistry;
 use crate::transport::{
-    ConnectionHandle, ConnectionHandler, ConnectionInfo, ListenerHandle, TransportContext,
+    ConnectionHandle, ConnectionHandler, ConnectionInfo, ConnectionStatus, Frame, ListenerHandle,
+    ResponseSlot, TransportContext,
 };
 
 /// Event dispatcher — receives protocol events and distributes them to handlers.
@@ -66,36 +84,83 @@ pub trait Dispatcher: Send + Sync {
 ///
 /// Each `AdapterBridge` manages bots for exactly one adapter instance.
 pub struct AdapterBridge {
+    /// Adapter name, used as the platform in the bot registry and recordings.
+    name: String,
     adapter: Arc<dyn Adapter>,
     /// Active bots by ID.
-    bots: RwLock<HashMap<String, BoxedBot>>,
+    bots: RwLock<HashMap<String, BotEntry>>,
     /// Event dispatcher — distributes parsed events to handlers.
     event_dispatcher: Arc<dyn Dispatcher>,
     /// Available transport capabilities.
     transport: TransportContext,
     /// Active listener handles (to keep them alive).
     listeners: Mutex<Vec<ListenerHandle>>,
-    /// Active connection handles.
-    connections: Mutex<HashMap<String, ConnectionHandle>>,
+    /// Active connection handles, by [`ConnectionHandle::conn_id`].
+    connections: Mutex<HashMap<u64, ConnectionHandle>>,
+    /// Optional raw-frame recorder.
+    recorder: Option<Arc<EventRecorder>>,
+    /// Optional runtime-wide bot registry mirrored by `bots`.
+    registry: Option<Arc<BotRegistry>>,
+    /// Outbound limits given to each new bot.
+    outbound: Option<OutboundConfig>,
 }
 
 impl AdapterBridge {
-    /// Creates a new adapter bridge.
+    /// Creates a new adapter bridge for the adapter registered as `name`.
     pub fn new(
+        name: impl Into<String>,
         adapter: Arc<dyn Adapter>,
         event_dispatcher: Arc<dyn Dispatcher>,
         transport: TransportContext,
     ) -> Self {
         Self {
+            name: name.into(),
             adapter,
             bots: RwLock::new(HashMap::new()),
             event_dispatcher,
             transport,
             listeners: Mutex::new(Vec::new()),
             connections: Mutex::new(HashMap::new()),
+            recorder: None,
+            registry: None,
+            outbound: None,
         }
     }
 
+    /// Records every raw inbound frame to `recorder`, tagged with the
+    /// adapter name.
+    ///
+    /// See [`record`](crate::record) for replaying the resulting file.
+    pub fn with_recorder(mut self, recorder: Arc<EventRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Registers and unregisters this bridge's bots in `registry`, with the
+    /// adapter name as their platform.
+    pub fn with_registry(mut self, registry: Arc<BotRegistry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Routes the API calls of each bot through its own
+    /// [`OutboundScheduler`] with the given limits.
+    ///
+    /// Bots that accept it in
+    /// [`Bot::attach_outbound`](crate::bot::Bot::attach_outbound) schedule
+    /// every API call they make; all others are wrapped in a
+    /// [`ScheduledBot`], which schedules the calls made through the
+    /// [`Bot`](crate::bot::Bot) trait.
+    pub fn with_outbound(mut self, config: OutboundConfig) -> Self {
+        self.outbound = Some(config);
+        self
+    }
+
+    /// Returns the adapter name this bridge was created with.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
     // =========================================================================
     // Runtime-facing methods
     // =========================================================================
@@ -125,6 +190,32 @@ impl AdapterBridge {
     pub fn bot_count(&self) -> usize {
         self.bots.read().len()
     }
+
+    /// Returns the number of open connections of the given bot (0 if the bot
+    /// is unknown).
+    pub fn connection_count(&self, bot_id: &str) -> usize {
+        self.bots
+            .read()
+            .get(bot_id)
+            .map_or(0, |entry| entry.connections.len())
+    }
+
+    /// Returns the bot ID and reconnect state of every connection the
+    /// adapter added that reports one (i.e. self-reconnecting clients).
+    pub fn connection_statuses(&self) -> Vec<(String, ConnectionStatus)> {
+        self.connections
+            .lock()
+            .values()
+            .filter_map(|handle| Some((handle.id.clone(), handle.status()?)))
+            .collect()
+    }
+}
+
+/// A registered bot and the connections it accepted.
+struct BotEntry {
+    bot: BoxedBot,
+    /// [`ConnectionHandle::conn_id`]s, in the order they were attached.
+    connections: Vec<u64>,
 }
 
 // =============================================================================
@@ -133,28 +224,161 @@ impl AdapterBridge {
 
 #[async_trait]
 impl ConnectionHandler for AdapterBridge {
+    fn authenticate(&self, conn_info: &ConnectionInfo) -> crate::error::TransportResult<()> {
+        self.adapter.authenticate(conn_info)
+    }
+
+    fn verify_request(
+        &self,
+        conn_info: &ConnectionInfo,
+        body: &[u8],
+    ) -> crate::error::TransportResult<()> {
+        self.adapter.verify_request(conn_info, body)
+    }
+
     fn get_bot_id(&self, conn_info: ConnectionInfo) -> crate::error::TransportResult<String> {
         self.adapter.get_bot_id(conn_info)
     }
 
     fn create_bot(&self, bot_id: &str, connection: ConnectionHandle) {
+        self.replace_rebound_connection(&connection);
         let mut bots = self.bots.write();
-        if bots.contains_key(bot_id) {
-            warn!(bot_id = %bot_id, "Bot already exists, not registering");
+        if let Some(entry) = bots.get_mut(bot_id) {
+            let conn_id = connection.conn_id();
+            if entry.bot.attach_connection(connection) {
+                entry.connections.push(conn_id);
+                debug!(
+                    bot_id = %bot_id,
+                    conn_id,
+                    connections = entry.connections.len(),
+                    "Attached connection to existing bot"
+                );
+            } else {
+                warn!(bot_id = %bot_id, "Bot already exists and does not accept more connections");
+            }
             return;
         }
 
-        let bot = self.adapter.create_bot(bot_id, connection);
-        bots.insert(bot_id.to_string(), bot);
+        let conn_id = connection.conn_id();
+        let mut bot = self.adapter.create_bot(bot_id, connection);
+        if let Some(config) = &self.outbound {
+            let scheduler = Arc::new(OutboundScheduler::new(bot_id, config.clone()));
+            if !bot.attach_outbound(scheduler.clone()) {
+                trace!(bot_id = %bot_id, "Scheduling outbound calls in a wrapper");
+                bot = Arc::new(ScheduledBot::new(bot, scheduler));
+            }
+        }
+        bots.insert(
+            bot_id.to_string(),
+            BotEntry {
+                bot: bot.clone(),
+                connections: vec![conn_id],
+            },
+        );
+        drop(bots);
+        if let Some(registry) = &self.registry {
+            registry.register(&self.name, bot.clone());
+        }
         debug!(bot_id = %bot_id, "Bot registered");
+
+        self.spawn_dispatch(Arc::new(BotConnectedEvent::new(bot_id)), bot);
+    }
+
+    async fn on_message(&self, bot_id: &str, frame: &Frame) {
+        let Some(bot) = self.inbound_bot(bot_id) else {
+            return;
+        };
+        if let Some(recorder) = &self.recorder {
+            recorder.record_frame(&self.name, bot_id, frame);
+        }
+        let event = self.adapter.parse_frame(&bot, frame).await;
+        self.handle_event(bot_id, bot, event);
+    }
+
+    async fn on_request(&self, bot_id: &str, data: &[u8], response: ResponseSlot) {
+        let Some(bot) = self.inbound_bot(bot_id) else {
+            return;
+        };
+        if let Some(recorder) = &self.recorder {
+            recorder.record(&self.name, bot_id, data);
+        }
+        let event = self.adapter.parse_request(&bot, data, response).await;
+        self.handle_event(bot_id, bot, event);
+    }
+
+    async fn on_disconnect(&self, connection: &ConnectionHandle) {
+        let bot_id = connection.id.as_str();
+        let conn_id = connection.conn_id();
+
+        // The bot, and how many connections it still has.
+        let (bot, remaining) = {
+            let mut bots = self.bots.write();
+            let Some(entry) = bots.get_mut(bot_id) else {
+                return;
+            };
+            let Some(pos) = entry.connections.iter().position(|&id| id == conn_id) else {
+                // A connection the bot declined; nothing depends on it.
+                return;
+            };
+            entry.connections.remove(pos);
+            let remaining = entry.connections.len();
+            let bot = entry.bot.clone();
+            if remaining == 0 {
+                bots.remove(bot_id);
+            }
+            (bot, remaining)
+        };
+
+        if remaining > 0 {
+            bot.detach_connection(connection).await;
+            info!(bot_id = %bot_id, conn_id, remaining, "Connection closed, bot still reachable");
+            return;
+        }
+
+        if let Some(registry) = &self.registry {
+            registry.unregister(&self.name, bot_id);
+        }
+        bot.on_disconnect().await;
+        info!(bot_id = %bot_id, "Connection closed");
+
+        self.spawn_dispatch(Arc::new(BotDisconnectedEvent::new(bot_id)), bot);
     }
+}
 
-    async fn on_message(&self, bot_id: &str, data: &[u8]) {
-        let Some(bot) = self.bots.read().get(bot_id).cloned() else {
+impl AdapterBridge {
+    /// Replaces the tracked connection that `connection` continues, if any.
+    ///
+    /// A self-reconnecting client that comes back as another bot hands out a
+    /// new handle on the same status channel; the old handle would keep
+    /// reporting the previous bot ID.
+    fn replace_rebound_connection(&self, connection: &ConnectionHandle) {
+        let Some(status) = connection.watch_status() else {
             return;
         };
+        let mut connections = self.connections.lock();
+        let stale = connections
+            .iter()
+            .find(|(conn_id, handle)| {
+                **conn_id != connection.conn_id()
+                    && handle
+                        .watch_status()
+                        .is_some_and(|other| other.same_channel(&status))
+            })
+            .map(|(&conn_id, _)| conn_id);
+        if let Some(conn_id) = stale {
+            connections.remove(&conn_id);
+            connections.insert(connection.conn_id(), connection.clone());
+        }
+    }
 
-        let Some(event) = self.adapter.parse_event(&bot, data).await else {
+    /// Returns the bot an inbound frame is for, if it is still registered.
+    fn inbound_bot(&self, bot_id: &str) -> Option<BoxedBot> {
+        self.bots.read().get(bot_id).map(|e| e.bot.clone())
+    }
+
+    /// Logs and dispatches an event parsed from an inbound frame.
+    fn handle_event(&self, bot_id: &str, bot: BoxedBot, event: Option<BoxedEvent>) {
+        let Some(event) = event else {
             return;
         };
 
@@ -171,20 +395,16 @@ impl ConnectionHandler for AdapterBridge {
             }
         }
 
-        // Dispatch in a separate task so we don't block the transport receiver.
+        self.spawn_dispatch(event, bot);
+    }
+
+    /// Dispatches in a separate task so we don't block the transport receiver.
+    fn spawn_dispatch(&self, event: BoxedEvent, bot: BoxedBot) {
         let dispatcher = self.event_dispatcher.clone();
         tokio::spawn(async move {
             dispatcher.dispatch(event, bot).await;
         });
     }
-
-    async fn on_disconnect(&self, bot_id: &str) {
-        let bot = self.bots.write().remove(bot_id);
-        if let Some(bot) = bot {
-            bot.on_disconnect().await;
-            info!(bot_id = %bot_id, "Connection closed");
-        }
-    }
 }
 
 // =============================================================================
@@ -211,14 +431,206 @@ impl AdapterContext for AdapterContextWrapper {
         self.bridge
             .connections
             .lock()
-            .insert(handle.id.clone(), handle);
+            .insert(handle.conn_id(), handle);
     }
 
     fn get_bot(&self, id: &str) -> Option<BoxedBot> {
-        self.bridge.bots.read().get(id).cloned()
+        self.bridge.bots.read().get(id).map(|e| e.bot.clone())
     }
 
     fn as_connection_handler(&self) -> Arc<dyn ConnectionHandler> {
         self.bridge.clone() as Arc<dyn ConnectionHandler>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+    use std::sync::OnceLock;
+    use std::time::Duration;
+
+    use serde_json::Value;
+    use tokio::sync::{mpsc, watch};
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+    use crate::bot::Bot;
+    use crate::call::CallPriority;
+    use crate::error::{ApiError, ApiResult, TransportResult};
+    use crate::event::Event;
+    use crate::message::ErasedMessage;
+    use crate::outbound::RateLimit;
+
+    struct TestBot {
+        id: String,
+        /// Whether the bot accepts a scheduler.
+        throttled: bool,
+        outbound: OnceLock<Arc<OutboundScheduler>>,
+    }
+
+    #[async_trait]
+    impl Bot for TestBot {
+        fn id(&self) -> &str {
+            &self.id
+        }
+
+        async fn call_api(&self, _action: &str, _params: Value) -> ApiResult<Value> {
+            Ok(Value::Null)
+        }
+
+        async fn send(&self, _event: &dyn Event, _message: &str) -> ApiResult<String> {
+            Err(ApiError::NotSupported)
+        }
+
+        async fn send_message(
+            &self,
+            _event: &dyn Event,
+            _message: &dyn ErasedMessage,
+        ) -> ApiResult<String> {
+            Err(ApiError::NotSupported)
+        }
+
+        fn as_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+            self
+        }
+
+        fn attach_outbound(&self, scheduler: Arc<OutboundScheduler>) -> bool {
+            self.throttled && self.outbound.set(scheduler).is_ok()
+        }
+    }
+
+    /// Creates [`TestBot`]s, taking a scheduler unless their ID starts with
+    /// `plain`.
+    #[derive(Default)]
+    struct TestAdapter {
+        bots: Mutex<Vec<Arc<TestBot>>>,
+    }
+
+    #[async_trait]
+    impl Adapter for TestAdapter {
+        fn get_bot_id(&self, _conn_info: ConnectionInfo) -> TransportResult<String> {
+            Ok("10001".to_string())
+        }
+
+        fn create_bot(&self, bot_id: &str, _connection: ConnectionHandle) -> BoxedBot {
+            let bot = Arc::new(TestBot {
+                id: bot_id.to_string(),
+                throttled: !bot_id.starts_with("plain"),
+                outbound: OnceLock::new(),
+            });
+            self.bots.lock().push(bot.clone());
+            bot
+        }
+
+        async fn parse_event(&self, _bot: &BoxedBot, _data: &[u8]) -> Option<BoxedEvent> {
+            None
+        }
+
+        async fn on_start(&self, _ctx: Arc<dyn AdapterContext>) -> AdapterResult<()> {
+            Ok(())
+        }
+    }
+
+    struct NoDispatch;
+
+    #[async_trait]
+    impl Dispatcher for NoDispatch {
+        async fn dispatch(&self, _event: BoxedEvent, _bot: BoxedBot) {}
+    }
+
+    fn connect(bridge: &AdapterBridge, bot_id: &str) {
+        let (tx, _rx) = mpsc::channel(1);
+        bridge.create_bot(
+            bot_id,
+            ConnectionHandle::new_ws(bot_id, tx, CancellationToken::new()),
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_outbound_scheduler_per_bot() {
+        let adapter = Arc::new(TestAdapter::default());
+        let config =
+            OutboundConfig::default().with_action_limit("send_msg", RateLimit::new(1.0, 1));
+        let bridge = AdapterBridge::new(
+            "test",
+            adapter.clone(),
+            Arc::new(NoDispatch),
+            TransportContext::default(),
+        )
+        .with_outbound(config);
+
+        connect(&bridge, "10001");
+        connect(&bridge, "10002");
+        connect(&bridge, "plain");
+
+        let bots = adapter.bots.lock().clone();
+        let [first, second, plain] = &bots[..] else {
+            panic!("expected three bots");
+        };
+        assert!(plain.outbound.get().is_none());
+
+        // A bot that takes no scheduler is throttled by the bridge.
+        let wrapped = bridge.bots.read()["plain"].bot.clone();
+        let started = tokio::time::Instant::now();
+        for _ in 0..2 {
+            wrapped.call_api("send_msg", Value::Null).await.unwrap();
+        }
+        assert_eq!(started.elapsed(), Duration::from_secs(1));
+        assert!(wrapped.as_any().downcast::<TestBot>().is_ok());
+
+        // Each bot has a budget of its own.
+        let first = first.outbound.get().expect("scheduler attached");
+        let second = second.outbound.get().expect("scheduler attached");
+        assert!(!Arc::ptr_eq(first, second));
+        for scheduler in [first, second] {
+            scheduler
+                .acquire("send_msg", None, CallPriority::Normal)
+                .await
+                .unwrap();
+        }
+        assert_eq!(first.stats().throttled + second.stats().throttled, 0);
+        first
+            .acquire("send_msg", None, CallPriority::Normal)
+            .await
+            .unwrap();
+        assert_eq!(first.stats().throttled, 1);
+    }
+
+    #[tokio::test]
+    async fn test_connection_statuses_follow_rebind() {
+        let bridge = Arc::new(AdapterBridge::new(
+            "test",
+            Arc::new(TestAdapter::default()),
+            Arc::new(NoDispatch),
+            TransportContext::default(),
+        ));
+        let ctx = AdapterContextWrapper {
+            bridge: bridge.clone(),
+        };
+
+        let (tx, _rx) = mpsc::channel(1);
+        let (status_tx, status_rx) = watch::channel(ConnectionStatus::Connected);
+        let token = CancellationToken::new();
+        let first = ConnectionHandle::new_ws("10001", tx.clone(), token.clone())
+            .with_status(status_rx.clone());
+        ctx.add_connection(first.clone());
+        bridge.create_bot("10001", first.clone());
+        assert_eq!(
+            bridge.connection_statuses(),
+            [("10001".to_string(), ConnectionStatus::Connected)]
+        );
+
+        // The client reconnected and now reports another bot.
+        bridge.on_disconnect(&first).await;
+        let second = ConnectionHandle::new_ws("20002", tx, token).with_status(status_rx);
+        bridge.create_bot("20002", second);
+        status_tx.send_replace(ConnectionStatus::Reconnecting { attempt: 1 });
+        assert_eq!(
+            bridge.connection_statuses(),
+            [(
+                "20002".to_string(),
+                ConnectionStatus::Reconnecting { attempt: 1 }
+            )]
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Per-call options for API calls.
+//!
+//! [`CallOptions`] tunes a single [`Bot::call_api_with`](crate::Bot::call_api_with)
+//! call: how long to wait for the response, whether to retry when the
+//! connection fails, and how urgent the call is relative to others waiting to
+//! go out.
+//!
+//! ```rust,ignore
+//! use std::time::Duration;
+//! use alloy_core::{CallOptions, RetryPolicy};
+//!
+//! let options = CallOptions::new()
+//!     .with_timeout(Duration::from_secs(120))
+//!     .with_retry(RetryPolicy::new(2, Duration::from_millis(500)));
+//! bot.call_api_with("get_forward_msg", params, &options).await?;
+//! ```
+
+use std::future::Future;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::error::{ApiError, ApiResult};
+
+/// Options for one API call.
+///
+/// The default waits for the connection's own timeout, does not retry and
+/// has [`CallPriority::Normal`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallOptions {
+    /// How long to wait for the response; `None` uses the default of the
+    /// connection the call goes out on.
+    pub timeout: Option<Duration>,
+    /// Retries after connection failures.
+    pub retry: RetryPolicy,
+    /// Urgency relative to other calls waiting to be sent.
+    pub priority: CallPriority,
+}
+
+impl CallOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the response timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the retry policy.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets the priority.
+    pub fn with_priority(mut self, priority: CallPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// When to repeat a call that failed because of the connection.
+///
+/// Only calls failing with a connection error
+/// ([`ApiError::is_connection_error`](crate::ApiError::is_connection_error))
+/// are retried; errors returned by the implementation are final.  A call
+/// that timed out may still have been carried out, so retries are meant for
+/// idempotent actions such as queries — retrying a send can post the message
+/// twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts after the first one; 0 disables retrying.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each further one.
+    pub backoff: Duration,
+    /// Whether calls that timed out are retried too.
+    ///
+    /// Turn off for quick probes, where a timeout already says the
+    /// connection is dead and each retry would wait out the timeout again.
+    pub retry_timeouts: bool,
+}
+
+impl RetryPolicy {
+    /// Makes a single attempt.
+    pub const fn none() -> Self {
+        Self::new(0, Duration::ZERO)
+    }
+
+    /// Retries up to `max_retries` times, waiting `backoff`, then twice as
+    /// long, and so on.
+    pub const fn new(max_retries: u32, backoff: Duration) -> Self {
+        Self {
+            max_retries,
+            backoff,
+            retry_timeouts: true,
+        }
+    }
+
+    /// Does not retry calls that timed out.
+    pub const fn without_timeouts(mut self) -> Self {
+        self.retry_timeouts = false;
+        self
+    }
+
+    /// Returns `true` if a call that failed with `error` is worth another
+    /// attempt.
+    fn retries(&self, error: &ApiError) -> bool {
+        error.is_connection_error() && (self.retry_timeouts || !matches!(error, ApiError::Timeout))
+    }
+
+    /// Returns `true` if the policy repeats calls that failed with `error`.
+    ///
+    /// Lets callers that pick another connection after a failure keep to
+    /// the same rules.
+    pub fn allows_retry(&self, error: &ApiError) -> bool {
+        self.max_retries > 0 && self.retries(error)
+    }
+
+    /// Runs `call` until it succeeds, fails with an error that is not worth
+    /// retrying, or the retries are used up.
+    pub async fn run<T, F, Fut>(&self, action: &str, mut call: F) -> ApiResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ApiResult<T>>,
+    {
+        let mut retry = 0;
+        loop {
+            match call().await {
+                Err(e) if self.retries(&e) && retry < self.max_retries => {
+                    let delay = self.backoff.saturating_mul(1 << retry.min(16));
+                    retry += 1;
+                    debug!(action = %action, retry, delay = ?delay, error = %e, "Retrying API call");
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+/// Urgency of a call.
+///
+/// Used to order calls that wait in an outbound queue; calls that go out
+/// immediately are not affected.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum CallPriority {
+    /// Background work, e.g. bulk queries.
+    Low,
+    /// Ordinary calls.
+    #[default]
+    Normal,
+    /// Calls that should overtake the rest, e.g. moderation.
+    High,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_retry_policy_retries_connection_errors_only() {
+        let attempts = AtomicU32::new(0);
+        let policy = RetryPolicy::new(2, Duration::ZERO);
+
+        let result: ApiResult<()> = policy
+            .run("get_status", || async {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                Err(ApiError::Timeout)
+            })
+            .await;
+        assert!(matches!(result, Err(ApiError::Timeout)));
+        assert_eq!(attempts.swap(0, Ordering::Relaxed), 3);
+
+        let result: ApiResult<()> = policy
+            .run("get_status", || async {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                Err(ApiError::NotSupported)
+            })
+            .await;
+        assert!(matches!(result, Err(ApiError::NotSupported)));
+        assert_eq!(attempts.swap(0, Ordering::Relaxed), 1);
+
+        let result: ApiResult<()> = policy
+            .without_timeouts()
+            .run("get_status", || async {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                Err(ApiError::Timeout)
+            })
+            .await;
+        assert!(matches!(result, Err(ApiError::Timeout)));
+        assert_eq!(attempts.swap(0, Ordering::Relaxed), 1);
+
+        let result: ApiResult<()> = policy
+            .without_timeouts()
+            .run("get_status", || async {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                Err(ApiError::NotConnected)
+            })
+            .await;
+        assert!(matches!(result, Err(ApiError::NotConnected)));
+        assert_eq!(attempts.load(Ordering::Relaxed), 3);
+    }
+}
@@ -5,6 +5,8 @@
 
 use thiserror::Error;
 
+use crate::session::Target;
+
 // =============================================================================
 // Transport Errors
 // =============================================================================
@@ -54,6 +56,17 @@ pub enum TransportError {
     #[error("I/O error: {0}")]
     Io(String),
 
+    /// A request got no response in time.
+    #[error("request timed out")]
+    Timeout,
+
+    /// The peer failed authentication.
+    #[error("unauthorized: {reason}")]
+    Unauthorized {
+        /// Reason for rejection.
+        reason: String,
+    },
+
     /// Bot already exists.
     #[error("bot with ID '{id}' already exists")]
     BotAlreadyExists {
@@ -126,11 +139,44 @@ pub enum ApiError {
     /// The event does not have the required session information.
     #[error("missing session info")]
     MissingSession,
+    /// The bot cannot send messages to this kind of target.
+    #[error("unsupported message target: {0}")]
+    UnsupportedTarget(Target),
+    /// The bot's outbound queue was full and the call was rejected or
+    /// dropped.
+    #[error("outbound queue is full")]
+    QueueFull,
     /// Other error.
     #[error("{0}")]
     Other(String),
 }
 
+impl ApiError {
+    /// Returns `true` if the call failed because of the connection rather
+    /// than the request, i.e. it may succeed over another connection.
+    ///
+    /// Except for [`is_unsent`](Self::is_unsent) errors the request may
+    /// still have been carried out.
+    pub fn is_connection_error(&self) -> bool {
+        matches!(
+            self,
+            ApiError::NotConnected | ApiError::Timeout | ApiError::Transport(_)
+        )
+    }
+
+    /// Returns `true` if the request never left the connection, so sending
+    /// it again cannot carry it out twice.
+    pub fn is_unsent(&self) -> bool {
+        matches!(
+            self,
+            ApiError::NotConnected
+                | ApiError::Transport(
+                    TransportError::SendFailed(_) | TransportError::ConnectionFailed { .. }
+                )
+        )
+    }
+}
+
 impl From<serde_json::Error> for ApiError {
     fn from(err: serde_json::Error) -> Self {
         Self::SerializationError(err.to_string())
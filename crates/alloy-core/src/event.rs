@@ -31,6 +31,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use super::message::{Message, MessageSegment, RichTextSegment};
+use super::session::Session;
 
 // ============================================================================
 // Event Type Classification
@@ -176,6 +177,25 @@ pub trait Event: AsText + Any + Send + Sync {
         None
     }
 
+    /// Returns the ID of the bot that received this event, if the payload
+    /// carries it.
+    ///
+    /// Generated by the derive macro from a field marked `#[event(bot_id)]`.
+    fn get_bot_id(&self) -> Option<String> {
+        None
+    }
+
+    /// Returns the [`Session`] this event belongs to.
+    ///
+    /// The derive macro builds it from the bot id, the user id and the
+    /// nearest `#[event(group_id)]` / `#[event(channel_id)]` field; events
+    /// with a user but no group or channel are treated as private chats.
+    /// Returns `None` when the event carries no bot id or has no chat at all
+    /// (e.g. heartbeats).
+    fn session(&self) -> Option<Session> {
+        None
+    }
+
     /// Attempts to downgrade to any type identified by `TypeId`, returned as `Box<dyn Any>`.
     ///
     /// This follows the parent chain:
@@ -20,21 +20,37 @@
 //! - **Event**: Type-erased event trait for protocol-specific types
 //! - **EventType**: Event classification system
 //! - **EventContext**: Wrapper for extracted event data
+//! - **Session**: Platform-agnostic "who / where" key of an event
+//! - **Target**: Destination for proactive messages
+//! - **BotConnectedEvent**, **BotDisconnectedEvent**: Synthetic bot lifecycle events
 //!
 //! ### Bots
 //! - **Bot**: Protocol-agnostic bot trait
+//! - **BotRegistry**: Runtime-wide lookup of connected bots
+//! - **CallOptions**: Timeout, retries and priority of a single API call
+//! - **OutboundScheduler**: Per-bot rate limits and queueing of outbound calls
 //!
 //! ### Adapters
 //! - **Adapter**: Protocol implementation trait
 //! - **AdapterBridge**: Transport capability access for adapters
+//!
+//! ### Recording
+//! - **EventRecorder**: Appends raw inbound frames to a JSONL file
+//! - **Replayer**: Feeds a recording back through an adapter and dispatcher
 
 // Core modules
 pub mod adapter;
 pub mod bot;
 pub mod bridge;
+pub mod call;
 pub mod error;
 pub mod event;
+pub mod lifecycle;
 pub mod message;
+pub mod outbound;
+pub mod record;
+pub mod registry;
+pub mod session;
 pub mod transport;
 
 // Re-export linkme so downstream crates don't need to add it as a direct
@@ -45,14 +61,25 @@ pub use linkme;
 pub use adapter::{Adapter, AdapterContext, BoxedAdapter, ConfigurableAdapter};
 pub use bot::{Bot, BoxedBot};
 pub use bridge::{AdapterBridge, Dispatcher};
+pub use call::{CallOptions, CallPriority, RetryPolicy};
 pub use error::{
     AdapterError, AdapterResult, ApiError, ApiResult, TransportError, TransportResult,
 };
 pub use event::{AsText, BoxedEvent, Event, EventType};
+pub use lifecycle::{BotConnectedEvent, BotDisconnectedEvent};
 pub use message::{ErasedMessage, Message, MessageSegment, RichText, RichTextSegment};
+pub use outbound::{
+    OutboundConfig, OutboundPermit, OutboundScheduler, OutboundStats, QueueOverflow, RateLimit,
+    ScheduledBot,
+};
+pub use record::{CapturedCall, EventRecorder, RecordedFrame, Replayer, read_recording};
+pub use registry::{BotChange, BotRegistry};
+pub use session::{SceneKind, Session, Target};
 pub use transport::{
-    ConnectionHandle, ConnectionHandler, ConnectionInfo, ConnectionKind, HTTP_LISTEN_REGISTRY,
-    HTTP_START_CLIENT_REGISTRY, HttpClientConfig, HttpListenFn, HttpStartClientFn, ListenerHandle,
-    PostJsonFn, TransportContext, WS_CONNECT_REGISTRY, WS_LISTEN_REGISTRY, WsClientConfig,
-    WsConnectFn, WsListenFn,
+    CAPABILITY_REGISTRY, Capability, CapabilityProvider, ClientTlsConfig, ConnectionHandle,
+    ConnectionHandler, ConnectionInfo, ConnectionKind, ConnectionStatus, Frame, HttpClient,
+    HttpClientConfig, HttpListenFn, HttpServer, HttpServerConfig, HttpStartClientFn,
+    ListenerHandle, Loopback, LoopbackConnectFn, LoopbackPeer, PostJsonFn, ProxyConfig,
+    ResponseSlot, SendOverflow, ServerTlsConfig, TransportContext, WsClient, WsClientConfig,
+    WsConnectFn, WsListenFn, WsServer, WsServerConfig, capability_providers,
 };
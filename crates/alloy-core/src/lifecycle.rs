@@ -0,0 +1,142 @@
+//! Synthetic bot lifecycle events.
+//!
+//! The [`AdapterBridge`](crate::bridge::AdapterBridge) dispatches these
+//! through the regular [`Dispatcher`](crate::bridge::Dispatcher) when a bot
+//! registers or its connection closes, so plugins can react with ordinary
+//! handlers:
+//!
+//! ```rust,ignore
+//! async fn greet(event: Event<BotConnectedEvent>, bot: BoxedBot) {
+//!     bot.send_to(&Target::group("123456"), &RichText::new().text("I'm back"))
+//!         .await
+//!         .ok();
+//! }
+//!
+//! define_plugin! {
+//!     name: "greeter",
+//!     handlers: [on::<BotConnectedEvent>().handler(greet)],
+//! }
+//! ```
+//!
+//! Both events are [`EventType::Meta`], report the platform `"alloy"`
+//! regardless of the adapter, and carry no message or session.
+
+use std::any::{Any, TypeId};
+use std::time::SystemTime;
+
+use crate::event::{Event, EventType};
+use crate::message::{Message, RichTextSegment};
+
+/// Platform name reported by framework-generated events.
+pub const SYNTHETIC_PLATFORM: &str = "alloy";
+
+static EMPTY_MESSAGE: Message<RichTextSegment> = Message::new();
+
+/// Implements [`Event`] for a lifecycle event with a `bot_id` field.
+macro_rules! impl_lifecycle_event {
+    ($ty:ty, $name:literal) => {
+        impl Event for $ty {
+            fn event_name(&self) -> &'static str {
+                $name
+            }
+
+            fn platform(&self) -> &'static str {
+                SYNTHETIC_PLATFORM
+            }
+
+            fn event_type(&self) -> EventType {
+                EventType::Meta
+            }
+
+            fn as_any(&self) -> &dyn Any {
+                self
+            }
+
+            fn get_bot_id(&self) -> Option<String> {
+                Some(self.bot_id.clone())
+            }
+
+            fn downgrade_any(&self, type_id: TypeId) -> Option<Box<dyn Any>> {
+                (type_id == TypeId::of::<Self>()).then(|| Box::new(self.clone()) as Box<dyn Any>)
+            }
+
+            type Segment = RichTextSegment;
+
+            fn get_message(&self) -> &Message<RichTextSegment> {
+                &EMPTY_MESSAGE
+            }
+        }
+    };
+}
+
+/// A bot registered with its adapter and is ready to send.
+#[derive(Debug, Clone)]
+pub struct BotConnectedEvent {
+    /// ID of the bot that came online.
+    pub bot_id: String,
+    /// When the bot was registered.
+    pub time: SystemTime,
+}
+
+impl BotConnectedEvent {
+    /// Creates the event, stamped with the current time.
+    pub fn new(bot_id: impl Into<String>) -> Self {
+        Self {
+            bot_id: bot_id.into(),
+            time: SystemTime::now(),
+        }
+    }
+}
+
+impl_lifecycle_event!(BotConnectedEvent, "bot.connected");
+
+/// A bot's connection closed and the bot was unregistered.
+///
+/// The bot handle dispatched with this event can no longer send.
+#[derive(Debug, Clone)]
+pub struct BotDisconnectedEvent {
+    /// ID of the bot that went offline.
+    pub bot_id: String,
+    /// When the connection closed.
+    pub time: SystemTime,
+}
+
+impl BotDisconnectedEvent {
+    /// Creates the event, stamped with the current time.
+    pub fn new(bot_id: impl Into<String>) -> Self {
+        Self {
+            bot_id: bot_id.into(),
+            time: SystemTime::now(),
+        }
+    }
+}
+
+impl_lifecycle_event!(BotDisconnectedEvent, "bot.disconnected");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::AsText;
+
+    #[test]
+    fn test_lifecycle_event_identity() {
+        let event = BotConnectedEvent::new("10001");
+        let erased: &dyn Event = &event;
+
+        assert_eq!(erased.event_type(), EventType::Meta);
+        assert_eq!(erased.get_bot_id().as_deref(), Some("10001"));
+        assert!(erased.session().is_none());
+        assert!(event.get_plain_text().is_empty());
+
+        let downgraded = erased
+            .downgrade_any(TypeId::of::<BotConnectedEvent>())
+            .and_then(|b| b.downcast::<BotConnectedEvent>().ok())
+            .unwrap();
+        assert_eq!(downgraded.bot_id, "10001");
+        assert!(
+            erased
+                .downgrade_any(TypeId::of::<BotDisconnectedEvent>())
+                .is_none()
+        );
+    }
+}
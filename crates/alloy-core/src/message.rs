@@ -30,9 +30,17 @@ use serde::{Deserialize, Serialize};
 /// # Variants
 ///
 /// - `Text`: Plain text content
-/// - `Image`: An image, identified by a platform-specific reference string
-/// - `At`: A user mention, identified by a user ID string
+/// - `Image`, `Audio`, `Video`, `File`: Media, identified by a
+///   platform-specific reference string
+/// - `At` / `AtAll`: A user mention, or a mention of everyone in the chat
+/// - `Reply`: A quote of an earlier message
+/// - `Face`: A platform emoji / sticker
+/// - `Link`: A link share
+///
+/// The enum is `#[non_exhaustive]`: adapters should map the variants their
+/// platform supports and drop the rest.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum RichTextSegment {
     /// Plain text content.
     Text(String),
@@ -42,6 +50,25 @@ pub enum RichTextSegment {
     /// A user mention. The string is the user identifier
     /// (e.g., QQ number, Discord user ID).
     At(String),
+    /// A mention of everyone in the chat.
+    AtAll,
+    /// A reply to an earlier message. The string is the message ID.
+    Reply(String),
+    /// A platform emoji / sticker. The string is the platform's face ID.
+    Face(String),
+    /// An audio / voice clip, as a platform-specific reference.
+    Audio(String),
+    /// A video, as a platform-specific reference.
+    Video(String),
+    /// A file attachment, as a platform-specific reference.
+    File(String),
+    /// A link share.
+    Link {
+        /// Target URL.
+        url: String,
+        /// Display title, if the platform provides one.
+        title: Option<String>,
+    },
 }
 
 // ============================================================================
@@ -72,8 +99,8 @@ pub trait MessageSegment: Debug + Clone + Display + Send + Sync + 'static {
     ///
     /// The default implementation returns `Some(RichTextSegment::Text)` if this is a
     /// text segment (via [`as_text()`](MessageSegment::as_text)), or `None` otherwise.
-    /// Adapters should override this to properly convert image and at-mention
-    /// segments into their rich-text equivalents.
+    /// Adapters should override this to properly convert media, mention and
+    /// reply segments into their rich-text equivalents.
     fn as_rich_text(&self) -> Option<RichTextSegment> {
         self.as_text().map(RichTextSegment::text)
     }
@@ -83,8 +110,8 @@ pub trait MessageSegment: Debug + Clone + Display + Send + Sync + 'static {
     /// The default implementation returns `None` (no conversion possible).
     /// Adapters should override this to support cross-protocol message forwarding.
     ///
-    /// `Text` segments should always be convertible. `Image` and `At` segments
-    /// should be converted where the protocol supports them.
+    /// `Text` segments should always be convertible. Other variants should be
+    /// converted where the protocol supports them.
     fn from_rich_text_segment(seg: &RichTextSegment) -> Option<Self> {
         if let RichTextSegment::Text(s) = seg {
             Some(Self::text(s))
@@ -108,6 +135,13 @@ impl MessageSegment for RichTextSegment {
             RichTextSegment::Text(_) => "text",
             RichTextSegment::Image(_) => "image",
             RichTextSegment::At(_) => "at",
+            RichTextSegment::AtAll => "at_all",
+            RichTextSegment::Reply(_) => "reply",
+            RichTextSegment::Face(_) => "face",
+            RichTextSegment::Audio(_) => "audio",
+            RichTextSegment::Video(_) => "video",
+            RichTextSegment::File(_) => "file",
+            RichTextSegment::Link { .. } => "link",
         }
     }
 
@@ -135,6 +169,17 @@ impl Display for RichTextSegment {
             RichTextSegment::Text(s) => write!(f, "{s}"),
             RichTextSegment::Image(r) => write!(f, "[Image: {r}]"),
             RichTextSegment::At(id) => write!(f, "@{id}"),
+            RichTextSegment::AtAll => write!(f, "@all"),
+            RichTextSegment::Reply(id) => write!(f, "[Reply: {id}]"),
+            RichTextSegment::Face(id) => write!(f, "[Face: {id}]"),
+            RichTextSegment::Audio(r) => write!(f, "[Audio: {r}]"),
+            RichTextSegment::Video(r) => write!(f, "[Video: {r}]"),
+            RichTextSegment::File(r) => write!(f, "[File: {r}]"),
+            RichTextSegment::Link { url, title: None } => write!(f, "[Link: {url}]"),
+            RichTextSegment::Link {
+                url,
+                title: Some(title),
+            } => write!(f, "[Link: {title} {url}]"),
         }
     }
 }
@@ -303,6 +348,44 @@ impl RichText {
         self.with(RichTextSegment::Image(reference.into()))
     }
 
+    /// Adds a mention of everyone in the chat.
+    pub fn at_all(self) -> Self {
+        self.with(RichTextSegment::AtAll)
+    }
+
+    /// Adds a reply to the message with the given ID.
+    pub fn reply(self, message_id: impl Into<String>) -> Self {
+        self.with(RichTextSegment::Reply(message_id.into()))
+    }
+
+    /// Adds a platform emoji / sticker.
+    pub fn face(self, id: impl Into<String>) -> Self {
+        self.with(RichTextSegment::Face(id.into()))
+    }
+
+    /// Adds an audio segment.
+    pub fn audio(self, reference: impl Into<String>) -> Self {
+        self.with(RichTextSegment::Audio(reference.into()))
+    }
+
+    /// Adds a video segment.
+    pub fn video(self, reference: impl Into<String>) -> Self {
+        self.with(RichTextSegment::Video(reference.into()))
+    }
+
+    /// Adds a file attachment.
+    pub fn file(self, reference: impl Into<String>) -> Self {
+        self.with(RichTextSegment::File(reference.into()))
+    }
+
+    /// Adds a link share.
+    pub fn link(self, url: impl Into<String>, title: Option<impl Into<String>>) -> Self {
+        self.with(RichTextSegment::Link {
+            url: url.into(),
+            title: title.map(Into::into),
+        })
+    }
+
     /// A convenience constructor for a simple message with optional at-mention.
     pub fn msg(text: impl Into<String>, at: Option<impl Into<String>>) -> Self {
         let mut msg = Self::new();
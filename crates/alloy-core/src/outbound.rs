@@ -0,0 +1,1044 @@
+//! Per-bot outbound call scheduling.
+//!
+//! An [`OutboundScheduler`] sits in front of a bot's API calls and decides
+//! when each one may go out:
+//!
+//! - **Rate limits** — token buckets for all calls of the bot, per action and
+//!   per conversation, with overrides for single groups.  A call waits until
+//!   every bucket that applies to it has a token.
+//! - **Ordering** — messages to the same conversation go out one at a time,
+//!   in the order they were made, whatever their [`CallPriority`]; calls to
+//!   different conversations overtake each other when one of them is
+//!   throttled, the conversation whose next call is most urgent first.
+//!   Only calls that post into a conversation are given one: a file upload
+//!   or a member lookup in a group neither waits for its messages nor
+//!   spends its budget.
+//! - **Backpressure** — at most `queue_capacity` calls wait per bot; what
+//!   happens to further ones is set by [`QueueOverflow`].  Calls scheduled
+//!   with [`acquire_with`](OutboundScheduler::acquire_with) give up when
+//!   their timeout runs out while waiting.
+//!
+//! The [`AdapterBridge`](crate::bridge::AdapterBridge) creates one scheduler
+//! per bot when configured with
+//! [`with_outbound`](crate::bridge::AdapterBridge::with_outbound) and offers
+//! it through [`Bot::attach_outbound`](crate::bot::Bot::attach_outbound).
+//! A bot that accepts it holds an [`OutboundPermit`] from
+//! [`acquire`](OutboundScheduler::acquire) for the duration of each call;
+//! any other bot is wrapped in a [`ScheduledBot`], which does so on its
+//! behalf.
+//!
+//! ```yaml
+//! outbound:
+//!   global: { rate: 5, burst: 10 }       # all calls of one bot
+//!   actions:
+//!     send_group_msg: { rate: 1, burst: 3 }
+//!   per_target: { rate: 0.5, burst: 2 }  # each group / private chat
+//!   groups:
+//!     "123456": { rate: 0.2, burst: 1 }
+//!   queue_capacity: 200
+//!   overflow: drop-oldest                # or reject (default), wait
+//! ```
+
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::{Notify, oneshot};
+use tokio::time::Instant;
+use tracing::{debug, trace, warn};
+
+use crate::bot::{Bot, BoxedBot};
+use crate::call::{CallOptions, CallPriority};
+use crate::error::{ApiError, ApiResult};
+use crate::event::Event;
+use crate::message::ErasedMessage;
+use crate::session::{SceneKind, Target};
+use crate::transport::ConnectionHandle;
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// Outbound limits applied to every bot of an adapter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutboundConfig {
+    /// Limit on all calls of a bot.
+    pub global: Option<RateLimit>,
+    /// Limits per action name, e.g. `send_group_msg`.
+    pub actions: HashMap<String, RateLimit>,
+    /// Limit per conversation (group, private chat or channel).
+    pub per_target: Option<RateLimit>,
+    /// Limits for single groups by ID, replacing `per_target` for them.
+    pub groups: HashMap<String, RateLimit>,
+    /// Calls that may wait per bot.
+    pub queue_capacity: usize,
+    /// What happens to a call made while the queue is full.
+    pub overflow: QueueOverflow,
+}
+
+impl Default for OutboundConfig {
+    fn default() -> Self {
+        Self {
+            global: None,
+            actions: HashMap::new(),
+            per_target: None,
+            groups: HashMap::new(),
+            queue_capacity: 1000,
+            overflow: QueueOverflow::default(),
+        }
+    }
+}
+
+impl OutboundConfig {
+    /// Returns `true` if any rate limit is set.
+    pub fn is_limited(&self) -> bool {
+        self.global.is_some()
+            || !self.actions.is_empty()
+            || self.per_target.is_some()
+            || !self.groups.is_empty()
+    }
+
+    /// Limits all calls of a bot.
+    pub fn with_global_limit(mut self, limit: RateLimit) -> Self {
+        self.global = Some(limit);
+        self
+    }
+
+    /// Limits calls of `action`.
+    pub fn with_action_limit(mut self, action: impl Into<String>, limit: RateLimit) -> Self {
+        self.actions.insert(action.into(), limit);
+        self
+    }
+
+    /// Limits calls to each conversation.
+    pub fn with_target_limit(mut self, limit: RateLimit) -> Self {
+        self.per_target = Some(limit);
+        self
+    }
+
+    /// Limits calls to the group `group_id`.
+    pub fn with_group_limit(mut self, group_id: impl Into<String>, limit: RateLimit) -> Self {
+        self.groups.insert(group_id.into(), limit);
+        self
+    }
+
+    /// Sets the queue capacity and overflow policy.
+    pub fn with_queue(mut self, capacity: usize, overflow: QueueOverflow) -> Self {
+        self.queue_capacity = capacity;
+        self.overflow = overflow;
+        self
+    }
+
+    /// Returns the conversation limit for `target`.
+    fn target_limit(&self, target: &Target) -> Option<RateLimit> {
+        let group = (target.scene == SceneKind::Group)
+            .then(|| self.groups.get(&target.id))
+            .flatten();
+        group.or(self.per_target.as_ref()).copied()
+    }
+}
+
+/// A token bucket: `burst` calls at once, refilled at `rate` calls per
+/// second.
+///
+/// Deserializing a limit whose rate is not a positive number fails, so a
+/// typo in the configuration cannot stall a bot's calls for good.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RateLimitSpec")]
+pub struct RateLimit {
+    rate: f64,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// Creates a limit of `rate` calls per second with bursts of `burst`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rate` is not positive and finite.
+    pub fn new(rate: f64, burst: u32) -> Self {
+        Self::try_new(rate, burst).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Creates a limit, or explains why `rate` is not usable.
+    pub fn try_new(rate: f64, burst: u32) -> Result<Self, String> {
+        if rate > 0.0 && rate.is_finite() {
+            Ok(Self { rate, burst })
+        } else {
+            Err(format!(
+                "invalid rate limit: `rate` must be a positive number of calls per second, got {rate}"
+            ))
+        }
+    }
+
+    /// Calls per second in the long run.
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Calls that may go out back to back.
+    pub fn burst(&self) -> u32 {
+        self.burst
+    }
+}
+
+/// Unchecked form of [`RateLimit`], as written in configuration.
+#[derive(Deserialize)]
+struct RateLimitSpec {
+    rate: f64,
+    burst: u32,
+}
+
+impl TryFrom<RateLimitSpec> for RateLimit {
+    type Error = String;
+
+    fn try_from(spec: RateLimitSpec) -> Result<Self, Self::Error> {
+        Self::try_new(spec.rate, spec.burst)
+    }
+}
+
+/// What happens to a call made while a bot's outbound queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum QueueOverflow {
+    /// Fail the new call with [`ApiError::QueueFull`].
+    #[default]
+    Reject,
+    /// Fail the longest-waiting call with [`ApiError::QueueFull`] and queue
+    /// the new one.
+    DropOldest,
+    /// Wait until there is room.
+    ///
+    /// The wait counts towards the call's timeout when it is scheduled with
+    /// [`OutboundScheduler::acquire_with`]; with
+    /// [`acquire`](OutboundScheduler::acquire) it is unbounded.
+    Wait,
+}
+
+// =============================================================================
+// Scheduler
+// =============================================================================
+
+/// Counters of an [`OutboundScheduler`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutboundStats {
+    /// Calls currently waiting.
+    pub queued: usize,
+    /// Calls that could not go out immediately.
+    pub throttled: u64,
+    /// Calls rejected because the queue was full.
+    pub rejected: u64,
+    /// Waiting calls dropped to make room.
+    pub dropped: u64,
+}
+
+/// Schedules the outbound calls of one bot; see the [module docs](self).
+pub struct OutboundScheduler {
+    bot_id: String,
+    config: OutboundConfig,
+    state: Mutex<State>,
+    /// Signalled whenever calls are granted, dropped or finish.
+    changed: Notify,
+}
+
+impl OutboundScheduler {
+    /// Creates a scheduler for the bot `bot_id`.
+    pub fn new(bot_id: impl Into<String>, config: OutboundConfig) -> Self {
+        let now = Instant::now();
+        let state = State {
+            global: config.global.map(|limit| TokenBucket::new(limit, now)),
+            actions: config
+                .actions
+                .iter()
+                .map(|(action, &limit)| (action.clone(), TokenBucket::new(limit, now)))
+                .collect(),
+            ..Default::default()
+        };
+        Self {
+            bot_id: bot_id.into(),
+            config,
+            state: Mutex::new(state),
+            changed: Notify::new(),
+        }
+    }
+
+    /// Waits until a call of `action` to `target` may go out.
+    ///
+    /// `target` is the conversation the call posts a message into, and
+    /// should be `None` for every other call, including ones that merely
+    /// name a group.  The call should be made while the returned permit is
+    /// held; the next call to the same conversation waits until it is
+    /// dropped.  Fails with
+    /// [`ApiError::QueueFull`] if the call is rejected or dropped by the
+    /// overflow policy.
+    pub async fn acquire(
+        self: &Arc<Self>,
+        action: &str,
+        target: Option<&Target>,
+        priority: CallPriority,
+    ) -> ApiResult<OutboundPermit> {
+        let lane = target.map(Target::to_string);
+        let mut rx = self.enqueue(action, target, lane.clone(), priority).await?;
+
+        match rx.try_recv() {
+            Ok(permit) => return Ok(permit),
+            Err(oneshot::error::TryRecvError::Closed) => return Err(ApiError::QueueFull),
+            Err(oneshot::error::TryRecvError::Empty) => {}
+        }
+        let queued_at = Instant::now();
+        {
+            let mut state = self.state.lock();
+            state.throttled += 1;
+            debug!(
+                bot_id = %self.bot_id,
+                action = %action,
+                target = lane.as_deref().unwrap_or("-"),
+                queued = state.queued,
+                "Outbound call throttled"
+            );
+        }
+
+        loop {
+            let changed = self.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+            let wake_at = self.state.lock().wake_at;
+
+            tokio::select! {
+                result = &mut rx => {
+                    // A closed channel means the call was dropped from the queue.
+                    let permit = result.map_err(|_| ApiError::QueueFull)?;
+                    trace!(bot_id = %self.bot_id, action = %action, waited = ?queued_at.elapsed(), "Outbound call released");
+                    return Ok(permit);
+                }
+                () = &mut changed => {}
+                () = sleep_until(wake_at) => self.dispatch(),
+            }
+        }
+    }
+
+    /// Waits until a call made with `options` may go out, as
+    /// [`acquire`](Self::acquire) does at `options.priority`.
+    ///
+    /// The wait counts towards `options.timeout`: the call fails with
+    /// [`ApiError::Timeout`] if it runs out first, whether the call is
+    /// throttled or waiting for room in a full queue.  Returns the permit
+    /// with the options to make the call with, their timeout reduced by the
+    /// time spent waiting.
+    pub async fn acquire_with(
+        self: &Arc<Self>,
+        action: &str,
+        target: Option<&Target>,
+        options: &CallOptions,
+    ) -> ApiResult<(OutboundPermit, CallOptions)> {
+        let Some(timeout) = options.timeout else {
+            let permit = self.acquire(action, target, options.priority).await?;
+            return Ok((permit, options.clone()));
+        };
+        let started = Instant::now();
+        let permit = tokio::time::timeout(timeout, self.acquire(action, target, options.priority))
+            .await
+            .map_err(|_| ApiError::Timeout)??;
+        let options = CallOptions {
+            timeout: Some(timeout.saturating_sub(started.elapsed())),
+            ..options.clone()
+        };
+        Ok((permit, options))
+    }
+
+    /// Returns the current counters.
+    pub fn stats(&self) -> OutboundStats {
+        let state = self.state.lock();
+        OutboundStats {
+            queued: state.queued,
+            throttled: state.throttled,
+            rejected: state.rejected,
+            dropped: state.dropped,
+        }
+    }
+
+    /// Queues a call, applying the overflow policy, and returns the
+    /// receiver its permit is sent to when it may go out.
+    ///
+    /// The permit travels through the channel so that a caller giving up
+    /// after its call was let out still releases the conversation: the
+    /// unread permit is dropped with the receiver.
+    async fn enqueue(
+        self: &Arc<Self>,
+        action: &str,
+        target: Option<&Target>,
+        lane: Option<String>,
+        priority: CallPriority,
+    ) -> ApiResult<oneshot::Receiver<OutboundPermit>> {
+        let capacity = self.config.queue_capacity.max(1);
+        loop {
+            let changed = self.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+
+            let rx = {
+                let mut state = self.state.lock();
+                let mut room = state.queued < capacity;
+                if !room {
+                    match self.config.overflow {
+                        QueueOverflow::Reject => {
+                            state.rejected += 1;
+                            warn!(bot_id = %self.bot_id, action = %action, queued = state.queued, "Outbound queue full, rejecting call");
+                            return Err(ApiError::QueueFull);
+                        }
+                        QueueOverflow::DropOldest => {
+                            if let Some(dropped) = state.drop_oldest() {
+                                warn!(bot_id = %self.bot_id, action = %dropped, queued = state.queued, "Outbound queue full, dropped oldest call");
+                            }
+                            room = true;
+                        }
+                        QueueOverflow::Wait => {}
+                    }
+                }
+                room.then(|| {
+                    let now = Instant::now();
+                    let (tx, rx) = oneshot::channel();
+                    let seq = state.next_seq;
+                    state.next_seq += 1;
+                    state.queued += 1;
+                    let target_limit = target.and_then(|target| self.config.target_limit(target));
+                    state
+                        .lanes
+                        .entry(lane.clone())
+                        .or_insert_with(|| Lane::new(target_limit, now))
+                        .jobs
+                        .push_back(Job {
+                            action: action.to_string(),
+                            priority,
+                            seq,
+                            tx,
+                        });
+                    state.dispatch(now, self);
+                    rx
+                })
+            };
+
+            match rx {
+                Some(rx) => {
+                    self.changed.notify_waiters();
+                    return Ok(rx);
+                }
+                None => changed.await,
+            }
+        }
+    }
+
+    /// Lets out every call that may go now, and wakes the waiters.
+    fn dispatch(self: &Arc<Self>) {
+        self.state.lock().dispatch(Instant::now(), self);
+        self.changed.notify_waiters();
+    }
+}
+
+/// Permission for one call, held while the call is in progress.
+pub struct OutboundPermit {
+    scheduler: Arc<OutboundScheduler>,
+    /// The conversation the call goes to, if any.
+    lane: Option<String>,
+}
+
+impl Drop for OutboundPermit {
+    fn drop(&mut self) {
+        if self.lane.is_none() {
+            return;
+        }
+        if let Some(lane) = self.scheduler.state.lock().lanes.get_mut(&self.lane) {
+            lane.busy = false;
+        }
+        self.scheduler.dispatch();
+    }
+}
+
+impl std::fmt::Debug for OutboundPermit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OutboundPermit")
+            .field("bot_id", &self.scheduler.bot_id)
+            .field("lane", &self.lane)
+            .finish()
+    }
+}
+
+// =============================================================================
+// Scheduled bot
+// =============================================================================
+
+/// A bot whose calls pass through an [`OutboundScheduler`].
+///
+/// The bridge wraps every bot that declines a scheduler in
+/// [`Bot::attach_outbound`] in one, so limits apply whether or not its
+/// adapter knows about them.  Raw calls are scheduled by action name and the
+/// conversation reported by [`Bot::call_target`]; `send`, `send_message` and
+/// `send_to` by their method name and the conversation they post into.
+/// Calls the inner bot makes on its own behalf are not seen, so each of
+/// these holds a single permit however many API calls it takes.
+///
+/// Everything else is forwarded, [`as_any`](Bot::as_any) included, so
+/// downcasts see the inner bot.
+pub struct ScheduledBot {
+    inner: BoxedBot,
+    scheduler: Arc<OutboundScheduler>,
+}
+
+impl ScheduledBot {
+    /// Wraps `inner` so that its calls are let out by `scheduler`.
+    pub fn new(inner: BoxedBot, scheduler: Arc<OutboundScheduler>) -> Self {
+        Self { inner, scheduler }
+    }
+
+    /// Returns the scheduler the calls pass through.
+    pub fn scheduler(&self) -> &Arc<OutboundScheduler> {
+        &self.scheduler
+    }
+
+    /// Runs `call` while holding a permit, with what is left of `options`
+    /// after waiting for it.
+    async fn scheduled<T, F, Fut>(
+        &self,
+        action: &str,
+        target: Option<Target>,
+        options: &CallOptions,
+        call: F,
+    ) -> ApiResult<T>
+    where
+        F: FnOnce(CallOptions) -> Fut,
+        Fut: Future<Output = ApiResult<T>>,
+    {
+        let (_permit, options) = self
+            .scheduler
+            .acquire_with(action, target.as_ref(), options)
+            .await?;
+        call(options).await
+    }
+}
+
+#[async_trait]
+impl Bot for ScheduledBot {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    async fn call_api(&self, action: &str, params: Value) -> ApiResult<Value> {
+        let target = self.inner.call_target(action, &params);
+        self.scheduled(action, target, &CallOptions::default(), |_| {
+            self.inner.call_api(action, params)
+        })
+        .await
+    }
+
+    async fn call_api_with(
+        &self,
+        action: &str,
+        params: Value,
+        options: &CallOptions,
+    ) -> ApiResult<Value> {
+        let target = self.inner.call_target(action, &params);
+        self.scheduled(action, target, options, |options| async move {
+            self.inner.call_api_with(action, params, &options).await
+        })
+        .await
+    }
+
+    async fn send(&self, event: &dyn Event, message: &str) -> ApiResult<String> {
+        let target = event.session().map(|session| session.target());
+        self.scheduled("send", target, &CallOptions::default(), |_| {
+            self.inner.send(event, message)
+        })
+        .await
+    }
+
+    async fn send_message(
+        &self,
+        event: &dyn Event,
+        message: &dyn ErasedMessage,
+    ) -> ApiResult<String> {
+        let target = event.session().map(|session| session.target());
+        self.scheduled("send_message", target, &CallOptions::default(), |_| {
+            self.inner.send_message(event, message)
+        })
+        .await
+    }
+
+    async fn send_to(&self, target: &Target, message: &dyn ErasedMessage) -> ApiResult<String> {
+        self.scheduled(
+            "send_to",
+            Some(target.clone()),
+            &CallOptions::default(),
+            |_| self.inner.send_to(target, message),
+        )
+        .await
+    }
+
+    fn call_target(&self, action: &str, params: &Value) -> Option<Target> {
+        self.inner.call_target(action, params)
+    }
+
+    fn as_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self.inner.clone().as_any()
+    }
+
+    fn attach_connection(&self, connection: ConnectionHandle) -> bool {
+        self.inner.attach_connection(connection)
+    }
+
+    async fn detach_connection(&self, connection: &ConnectionHandle) {
+        self.inner.detach_connection(connection).await;
+    }
+
+    async fn on_disconnect(&self) {
+        self.inner.on_disconnect().await;
+    }
+}
+
+// =============================================================================
+// Internals
+// =============================================================================
+
+#[derive(Default)]
+struct State {
+    /// Waiting calls by conversation; `None` holds calls without one.
+    lanes: HashMap<Option<String>, Lane>,
+    global: Option<TokenBucket>,
+    actions: HashMap<String, TokenBucket>,
+    queued: usize,
+    next_seq: u64,
+    /// When the first throttled call may go out.
+    wake_at: Option<Instant>,
+    throttled: u64,
+    rejected: u64,
+    dropped: u64,
+}
+
+/// The calls waiting for one conversation.
+struct Lane {
+    jobs: VecDeque<Job>,
+    /// A call to the conversation is in progress.  Never set for calls
+    /// without a conversation, which do not need ordering.
+    busy: bool,
+    bucket: Option<TokenBucket>,
+}
+
+impl Lane {
+    fn new(limit: Option<RateLimit>, now: Instant) -> Self {
+        Self {
+            jobs: VecDeque::new(),
+            busy: false,
+            bucket: limit.map(|limit| TokenBucket::new(limit, now)),
+        }
+    }
+}
+
+struct Job {
+    action: String,
+    priority: CallPriority,
+    /// Arrival order.
+    seq: u64,
+    tx: oneshot::Sender<OutboundPermit>,
+}
+
+impl State {
+    /// Lets out calls until none may go, the conversation whose next call
+    /// has the highest priority first, then the oldest; records when the
+    /// next one may.
+    ///
+    /// Runs under the scheduler's lock, so a permit that cannot be delivered
+    /// is disarmed rather than dropped, which would lock it again.
+    fn dispatch(&mut self, now: Instant, scheduler: &Arc<OutboundScheduler>) {
+        loop {
+            let mut best: Option<(CallPriority, std::cmp::Reverse<u64>, Option<String>)> = None;
+            let mut wake_at: Option<Instant> = None;
+
+            for (key, lane) in &mut self.lanes {
+                // Calls abandoned by their caller.
+                let waiting = lane.jobs.len();
+                lane.jobs.retain(|job| !job.tx.is_closed());
+                self.queued -= waiting - lane.jobs.len();
+                if lane.busy {
+                    continue;
+                }
+                // Always the oldest, so that messages to a conversation keep
+                // their order.
+                let Some(job) = lane.jobs.front() else {
+                    continue;
+                };
+
+                let wait = [
+                    self.global.as_mut(),
+                    self.actions.get_mut(&job.action),
+                    lane.bucket.as_mut(),
+                ]
+                .into_iter()
+                .flatten()
+                .map(|bucket| bucket.wait(now))
+                .max()
+                .unwrap_or_default();
+
+                if wait.is_zero() {
+                    let rank = (job.priority, std::cmp::Reverse(job.seq), key.clone());
+                    if best.as_ref().is_none_or(|best| rank > *best) {
+                        best = Some(rank);
+                    }
+                } else if let Some(at) = now.checked_add(wait) {
+                    wake_at = Some(wake_at.map_or(at, |w| w.min(at)));
+                }
+            }
+
+            let Some((_, _, key)) = best else {
+                self.wake_at = wake_at;
+                self.lanes.retain(|key, lane| {
+                    key.is_none()
+                        || !lane.jobs.is_empty()
+                        || lane.busy
+                        || lane.bucket.as_mut().is_some_and(|b| !b.is_full(now))
+                });
+                return;
+            };
+
+            let lane = self.lanes.get_mut(&key).expect("picked lane exists");
+            let job = lane.jobs.pop_front().expect("picked lane has a job");
+            self.queued -= 1;
+            for bucket in [
+                self.global.as_mut(),
+                self.actions.get_mut(&job.action),
+                lane.bucket.as_mut(),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                bucket.take();
+            }
+            let permit = OutboundPermit {
+                scheduler: scheduler.clone(),
+                lane: key.clone(),
+            };
+            match job.tx.send(permit) {
+                // Calls without a conversation may run side by side.
+                Ok(()) => lane.busy = key.is_some(),
+                Err(mut permit) => permit.lane = None,
+            }
+        }
+    }
+
+    /// Fails the longest-waiting call; returns its action.
+    fn drop_oldest(&mut self) -> Option<String> {
+        let lane = self
+            .lanes
+            .values_mut()
+            .filter(|lane| !lane.jobs.is_empty())
+            .min_by_key(|lane| lane.jobs.front().map(|job| job.seq))?;
+        let job = lane.jobs.pop_front()?;
+        self.queued -= 1;
+        self.dropped += 1;
+        Some(job.action)
+    }
+}
+
+/// Token bucket state for one [`RateLimit`].
+struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    /// Tolerance for refill rounding.
+    const EPSILON: f64 = 1e-9;
+
+    fn new(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            limit,
+            tokens: f64::from(limit.burst.max(1)),
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        let capacity = f64::from(self.limit.burst.max(1));
+        self.tokens = (self.tokens + elapsed * self.limit.rate).min(capacity);
+        self.updated = now;
+    }
+
+    /// Time until a token is available.
+    fn wait(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        let missing = 1.0 - self.tokens;
+        if missing <= Self::EPSILON {
+            return Duration::ZERO;
+        }
+        Duration::try_from_secs_f64(missing / self.limit.rate).unwrap_or(Duration::MAX)
+    }
+
+    fn take(&mut self) {
+        self.tokens -= 1.0;
+    }
+
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens + Self::EPSILON >= f64::from(self.limit.burst.max(1))
+    }
+}
+
+/// Sleeps until `deadline`, or forever if there is none.
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduler(config: OutboundConfig) -> Arc<OutboundScheduler> {
+        Arc::new(OutboundScheduler::new("10001", config))
+    }
+
+    /// Starts a call to `group` in the background and lets it queue.
+    async fn spawn_send(
+        scheduler: &Arc<OutboundScheduler>,
+        group: &str,
+    ) -> tokio::task::JoinHandle<ApiResult<OutboundPermit>> {
+        let scheduler = scheduler.clone();
+        let group = Target::group(group);
+        let task = tokio::spawn(async move {
+            scheduler
+                .acquire("send_group_msg", Some(&group), CallPriority::Normal)
+                .await
+        });
+        tokio::task::yield_now().await;
+        task
+    }
+
+    #[test]
+    fn test_rate_limit_rejects_non_positive_rates() {
+        let limit: RateLimit = serde_json::from_str(r#"{"rate": 0.5, "burst": 2}"#).unwrap();
+        assert_eq!((limit.rate(), limit.burst()), (0.5, 2));
+
+        for rate in ["0", "-1"] {
+            let json = format!(r#"{{"rate": {rate}, "burst": 1}}"#);
+            let error = serde_json::from_str::<RateLimit>(&json).unwrap_err();
+            assert!(
+                error.to_string().contains("must be a positive number"),
+                "{error}"
+            );
+        }
+        assert!(RateLimit::try_new(f64::NAN, 1).is_err());
+        assert!(
+            serde_json::from_str::<OutboundConfig>(
+                r#"{"actions": {"send_group_msg": {"rate": 0, "burst": 1}}}"#
+            )
+            .is_err()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_delays_calls() {
+        let scheduler = scheduler(
+            OutboundConfig::default().with_action_limit("send_group_msg", RateLimit::new(20.0, 1)),
+        );
+        let started = Instant::now();
+        for _ in 0..3 {
+            scheduler
+                .acquire("send_group_msg", None, CallPriority::Normal)
+                .await
+                .unwrap();
+        }
+        let elapsed = started.elapsed();
+        assert!(
+            (Duration::from_millis(100)..Duration::from_millis(105)).contains(&elapsed),
+            "{elapsed:?}"
+        );
+        // Other actions are not limited.
+        scheduler
+            .acquire("get_status", None, CallPriority::Normal)
+            .await
+            .unwrap();
+        assert_eq!(scheduler.stats().throttled, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_abandoned_grant_releases_conversation() {
+        let scheduler = scheduler(OutboundConfig::default());
+        let group = Target::group("1");
+        let first = scheduler
+            .acquire("send_group_msg", Some(&group), CallPriority::Normal)
+            .await
+            .unwrap();
+
+        // Queue a second call, then let it out and give up on it before it
+        // is polled again, as a caller's timeout would.
+        let mut second =
+            Box::pin(scheduler.acquire("send_group_msg", Some(&group), CallPriority::Normal));
+        assert!(
+            tokio::time::timeout(Duration::ZERO, second.as_mut())
+                .await
+                .is_err()
+        );
+        drop(first);
+        assert_eq!(scheduler.stats().queued, 0);
+        drop(second);
+
+        tokio::time::timeout(
+            Duration::from_secs(1),
+            scheduler.acquire("send_group_msg", Some(&group), CallPriority::Normal),
+        )
+        .await
+        .expect("conversation was released")
+        .unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_priority_applies_between_conversations() {
+        let scheduler =
+            scheduler(OutboundConfig::default().with_global_limit(RateLimit::new(1.0, 1)));
+        let first = scheduler
+            .acquire("send_group_msg", None, CallPriority::Normal)
+            .await
+            .unwrap();
+        drop(first);
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut tasks = Vec::new();
+        for (name, group, priority) in [
+            ("1:normal", "1", CallPriority::Normal),
+            ("1:high", "1", CallPriority::High),
+            ("2:low", "2", CallPriority::Low),
+            ("3:normal", "3", CallPriority::Normal),
+        ] {
+            let scheduler = scheduler.clone();
+            let group = Target::group(group);
+            let order = order.clone();
+            tasks.push(tokio::spawn(async move {
+                let _permit = scheduler
+                    .acquire("send_group_msg", Some(&group), priority)
+                    .await
+                    .unwrap();
+                order.lock().push(name);
+            }));
+            // Queue them in this order.
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(scheduler.stats().queued, 4);
+
+        for task in tasks {
+            task.await.unwrap();
+        }
+        // The urgent message to group 1 waits for the one made before it,
+        // then goes ahead of the other conversations.
+        assert_eq!(*order.lock(), ["1:normal", "1:high", "3:normal", "2:low"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_acquire_with_counts_wait_towards_timeout() {
+        let scheduler = scheduler(
+            OutboundConfig::default().with_action_limit("send_group_msg", RateLimit::new(1.0, 1)),
+        );
+        let options = CallOptions::new().with_timeout(Duration::from_millis(1500));
+        let (_permit, remaining) = scheduler
+            .acquire_with("send_group_msg", None, &options)
+            .await
+            .unwrap();
+        assert_eq!(remaining.timeout, Some(Duration::from_millis(1500)));
+
+        let (_permit, remaining) = scheduler
+            .acquire_with("send_group_msg", None, &options)
+            .await
+            .unwrap();
+        assert_eq!(remaining.timeout, Some(Duration::from_millis(500)));
+
+        let short = CallOptions::new().with_timeout(Duration::from_millis(500));
+        let result = scheduler.acquire_with("send_group_msg", None, &short).await;
+        assert!(matches!(result, Err(ApiError::Timeout)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_overflow_reject() {
+        let scheduler = scheduler(OutboundConfig::default().with_queue(1, QueueOverflow::Reject));
+        let first = spawn_send(&scheduler, "1").await.await.unwrap().unwrap();
+        let second = spawn_send(&scheduler, "1").await;
+        assert_eq!(scheduler.stats().queued, 1);
+
+        // Other conversations are not held up, but the queue is full.
+        let third = spawn_send(&scheduler, "2").await;
+        assert!(matches!(third.await.unwrap(), Err(ApiError::QueueFull)));
+
+        drop(first);
+        second.await.unwrap().unwrap();
+        spawn_send(&scheduler, "2").await.await.unwrap().unwrap();
+        assert_eq!(
+            scheduler.stats(),
+            OutboundStats {
+                queued: 0,
+                throttled: 1,
+                rejected: 1,
+                dropped: 0,
+            }
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_overflow_drop_oldest() {
+        let scheduler =
+            scheduler(OutboundConfig::default().with_queue(1, QueueOverflow::DropOldest));
+        let first = spawn_send(&scheduler, "1").await.await.unwrap().unwrap();
+        let second = spawn_send(&scheduler, "1").await;
+        let third = spawn_send(&scheduler, "1").await;
+
+        assert!(matches!(second.await.unwrap(), Err(ApiError::QueueFull)));
+        assert_eq!(scheduler.stats().queued, 1);
+        drop(first);
+        third.await.unwrap().unwrap();
+        assert_eq!(scheduler.stats().dropped, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_overflow_wait() {
+        let scheduler = scheduler(OutboundConfig::default().with_queue(1, QueueOverflow::Wait));
+        let first = spawn_send(&scheduler, "1").await.await.unwrap().unwrap();
+        let second = spawn_send(&scheduler, "1").await;
+        let third = spawn_send(&scheduler, "1").await;
+        assert_eq!(scheduler.stats().queued, 1);
+        assert!(!third.is_finished());
+
+        // The second call leaves the queue, which lets the third one in.
+        drop(first);
+        let second = second.await.unwrap().unwrap();
+        tokio::task::yield_now().await;
+        assert_eq!(scheduler.stats().queued, 1);
+        assert!(!third.is_finished());
+
+        drop(second);
+        third.await.unwrap().unwrap();
+        let stats = scheduler.stats();
+        assert_eq!((stats.rejected, stats.dropped), (0, 0));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_overflow_wait_gives_up_at_timeout() {
+        let scheduler = scheduler(OutboundConfig::default().with_queue(1, QueueOverflow::Wait));
+        let _first = spawn_send(&scheduler, "1").await.await.unwrap().unwrap();
+        let _second = spawn_send(&scheduler, "1").await;
+
+        let options = CallOptions::new().with_timeout(Duration::from_secs(1));
+        let started = Instant::now();
+        let result = scheduler
+            .acquire_with("send_group_msg", Some(&Target::group("2")), &options)
+            .await;
+        assert!(matches!(result, Err(ApiError::Timeout)));
+        assert_eq!(started.elapsed(), Duration::from_secs(1));
+        assert_eq!(scheduler.stats().queued, 1);
+    }
+}
@@ -0,0 +1,412 @@
+//! Recording and replaying inbound event streams.
+//!
+//! An [`EventRecorder`] attached to an [`AdapterBridge`] appends every raw
+//! inbound frame to a JSONL file, one [`RecordedFrame`] per line:
+//!
+//! ```text
+//! {"bot_id":"10001","adapter":"onebot","timestamp_ms":1700000000000,"data":"{\"post_type\":…}"}
+//! ```
+//!
+//! A [`Replayer`] feeds such a recording back through
+//! [`Adapter::parse_frame`] and [`Dispatcher::dispatch`], in order, against
+//! bots whose outgoing API calls are captured instead of sent:
+//!
+//! ```rust,ignore
+//! let frames = read_recording("bug-1234.jsonl")?;
+//! let calls = Replayer::new(Arc::new(OneBotAdapter::default()), plugin_manager)
+//!     .replay(frames)
+//!     .await;
+//! assert_eq!(calls[0].body["action"], "send_group_msg");
+//! ```
+//!
+//! [`AdapterBridge`]: crate::bridge::AdapterBridge
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+use crate::adapter::Adapter;
+use crate::bot::BoxedBot;
+use crate::bridge::Dispatcher;
+use crate::transport::{ConnectionHandle, Frame, PostJsonFn};
+
+// =============================================================================
+// RecordedFrame
+// =============================================================================
+
+/// One raw inbound frame, as received by [`ConnectionHandler::on_message`].
+///
+/// [`ConnectionHandler::on_message`]: crate::transport::ConnectionHandler::on_message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    /// The bot the frame arrived for.
+    pub bot_id: String,
+    /// Name of the adapter that owns the bot.
+    pub adapter: String,
+    /// Arrival time in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    /// Raw frame bytes.
+    ///
+    /// Stored as a JSON string when valid UTF-8 (the common case) and as a
+    /// byte array otherwise.
+    #[serde(with = "frame_bytes")]
+    pub data: Vec<u8>,
+    /// The frame arrived as a WebSocket binary frame.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub binary: bool,
+}
+
+impl RecordedFrame {
+    /// Creates a frame stamped with the current time.
+    pub fn new(adapter: impl Into<String>, bot_id: impl Into<String>, data: &[u8]) -> Self {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        Self {
+            bot_id: bot_id.into(),
+            adapter: adapter.into(),
+            timestamp_ms,
+            data: data.to_vec(),
+            binary: false,
+        }
+    }
+
+    /// Rebuilds the frame as it was received.
+    ///
+    /// Data not marked binary is a text frame if it is valid UTF-8, so that
+    /// recordings of raw HTTP bodies replay as they were parsed live.
+    pub fn frame(&self) -> Frame {
+        if self.binary {
+            Frame::Binary(self.data.clone())
+        } else {
+            Frame::from_payload(self.data.clone())
+        }
+    }
+}
+
+mod frame_bytes {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(data: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        match std::str::from_utf8(data) {
+            Ok(text) => s.serialize_str(text),
+            Err(_) => s.serialize_bytes(data),
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Text(String),
+        Bytes(Vec<u8>),
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        Ok(match Repr::deserialize(d)? {
+            Repr::Text(text) => text.into_bytes(),
+            Repr::Bytes(bytes) => bytes,
+        })
+    }
+}
+
+/// Reads every frame of a JSONL recording.
+///
+/// Blank lines are skipped; a malformed line is an
+/// [`InvalidData`](io::ErrorKind::InvalidData) error naming its line number.
+pub fn read_recording(path: impl AsRef<Path>) -> io::Result<Vec<RecordedFrame>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut frames = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let frame = serde_json::from_str(&line).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}: {e}", index + 1),
+            )
+        })?;
+        frames.push(frame);
+    }
+    Ok(frames)
+}
+
+// =============================================================================
+// EventRecorder
+// =============================================================================
+
+/// Frames waiting for the recorder's writer thread before new ones are
+/// dropped.
+const RECORDER_QUEUE_CAPACITY: usize = 4096;
+
+/// Appends raw inbound frames to a JSONL file.
+///
+/// One recorder may be shared by several bridges; each line carries the
+/// adapter name.  Frames are handed to a writer thread, so recording never
+/// blocks the async runtime on disk I/O; the thread flushes as soon as it
+/// has caught up, so that a recording survives a crash.  Write errors are
+/// logged and otherwise ignored, and frames arriving while the disk lags
+/// far behind are dropped — a failing recorder never interrupts event
+/// handling.  Dropping the recorder waits for the queued frames to be
+/// written.
+pub struct EventRecorder {
+    tx: Option<SyncSender<RecordedFrame>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl EventRecorder {
+    /// Opens `path` for appending, creating it and its parent directories if
+    /// needed.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let (tx, rx) = mpsc::sync_channel(RECORDER_QUEUE_CAPACITY);
+        let writer = thread::Builder::new()
+            .name("alloy-recorder".to_string())
+            .spawn(move || write_frames(rx, file))?;
+        Ok(Self {
+            tx: Some(tx),
+            writer: Some(writer),
+        })
+    }
+
+    /// Appends one raw payload, e.g. an HTTP request body.
+    pub fn record(&self, adapter: &str, bot_id: &str, data: &[u8]) {
+        self.write(RecordedFrame::new(adapter, bot_id, data));
+    }
+
+    /// Appends one WebSocket-style frame; close frames are not recorded.
+    pub fn record_frame(&self, adapter: &str, bot_id: &str, frame: &Frame) {
+        if frame.is_close() {
+            return;
+        }
+        let mut recorded = RecordedFrame::new(adapter, bot_id, frame.payload());
+        recorded.binary = frame.is_binary();
+        self.write(recorded);
+    }
+
+    fn write(&self, frame: RecordedFrame) {
+        let Some(tx) = &self.tx else {
+            return;
+        };
+        match tx.try_send(frame) {
+            Ok(()) => {}
+            Err(TrySendError::Full(frame)) => {
+                warn!(bot_id = %frame.bot_id, "Recorder is falling behind, dropping frame");
+            }
+            Err(TrySendError::Disconnected(frame)) => {
+                warn!(bot_id = %frame.bot_id, "Recorder writer has stopped, dropping frame");
+            }
+        }
+    }
+}
+
+impl Drop for EventRecorder {
+    fn drop(&mut self) {
+        // Closing the channel ends the writer once the queue is written.
+        drop(self.tx.take());
+        if let Some(writer) = self.writer.take()
+            && writer.join().is_err()
+        {
+            warn!("Recorder writer panicked");
+        }
+    }
+}
+
+/// Body of the recorder's writer thread.
+fn write_frames(rx: Receiver<RecordedFrame>, file: File) {
+    let mut file = BufWriter::new(file);
+    while let Ok(frame) = rx.recv() {
+        let mut pending = Some(frame);
+        while let Some(frame) = pending {
+            write_frame(&mut file, &frame);
+            pending = rx.try_recv().ok();
+        }
+        if let Err(e) = file.flush() {
+            warn!(error = %e, "Failed to flush recording");
+        }
+    }
+}
+
+fn write_frame(file: &mut impl Write, frame: &RecordedFrame) {
+    let bot_id = frame.bot_id.as_str();
+    let mut line = match serde_json::to_vec(frame) {
+        Ok(line) => line,
+        Err(e) => {
+            warn!(bot_id = %bot_id, error = %e, "Failed to serialize recorded frame");
+            return;
+        }
+    };
+    line.push(b'\n');
+    if let Err(e) = file.write_all(&line) {
+        warn!(bot_id = %bot_id, error = %e, "Failed to write recorded frame");
+    }
+}
+
+// =============================================================================
+// Replayer
+// =============================================================================
+
+/// An outgoing API request captured during replay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedCall {
+    /// The bot that issued the call.
+    pub bot_id: String,
+    /// The request body exactly as the adapter would have sent it.
+    ///
+    /// The shape is adapter-specific; OneBot uses
+    /// `{"action": "...", "params": {...}}`.
+    pub body: Value,
+}
+
+type ResponseFn = Arc<dyn Fn(&Value) -> Value + Send + Sync>;
+
+/// Feeds a recording back through an adapter and a dispatcher.
+///
+/// Bots are created through [`Adapter::create_bot`] on an HTTP-client
+/// connection whose POST function captures the request body and answers
+/// with a stub response (`null` unless set with
+/// [`with_response`](Self::with_response)).  Each frame's dispatch is
+/// awaited before the next one is parsed; with a dispatcher that awaits its
+/// handlers, such as the framework's `PluginManager`, the captured calls come
+/// back in a deterministic order.
+pub struct Replayer {
+    adapter: Arc<dyn Adapter>,
+    dispatcher: Arc<dyn Dispatcher>,
+    response: ResponseFn,
+}
+
+impl Replayer {
+    /// Creates a replayer for `adapter` that dispatches into `dispatcher`.
+    pub fn new(adapter: Arc<dyn Adapter>, dispatcher: Arc<dyn Dispatcher>) -> Self {
+        Self {
+            adapter,
+            dispatcher,
+            response: Arc::new(|_| Value::Null),
+        }
+    }
+
+    /// Sets the response returned for every captured call.
+    ///
+    /// The closure receives the request body.  Use it to make calls succeed
+    /// when handlers depend on the result, e.g. returning
+    /// `{"retcode": 0, "data": {"message_id": 1}}` for OneBot.
+    pub fn with_response(
+        mut self,
+        response: impl Fn(&Value) -> Value + Send + Sync + 'static,
+    ) -> Self {
+        self.response = Arc::new(response);
+        self
+    }
+
+    /// Replays `frames` in order and returns every API call the handlers made.
+    ///
+    /// Frames the adapter cannot parse are skipped, as they would be live.
+    pub async fn replay(
+        &self,
+        frames: impl IntoIterator<Item = RecordedFrame>,
+    ) -> Vec<CapturedCall> {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut bots: HashMap<String, BoxedBot> = HashMap::new();
+
+        for frame in frames {
+            let bot = bots
+                .entry(frame.bot_id.clone())
+                .or_insert_with(|| self.stub_bot(&frame.bot_id, calls.clone()))
+                .clone();
+            let Some(event) = self.adapter.parse_frame(&bot, &frame.frame()).await else {
+                continue;
+            };
+            self.dispatcher.dispatch(event, bot).await;
+        }
+
+        std::mem::take(&mut *calls.lock())
+    }
+
+    /// Reads a recording with [`read_recording`] and replays it.
+    pub async fn replay_file(&self, path: impl AsRef<Path>) -> io::Result<Vec<CapturedCall>> {
+        Ok(self.replay(read_recording(path)?).await)
+    }
+
+    fn stub_bot(&self, bot_id: &str, calls: Arc<Mutex<Vec<CapturedCall>>>) -> BoxedBot {
+        let id = bot_id.to_string();
+        let response = self.response.clone();
+        let post_json: PostJsonFn = Arc::new(move |body, _timeout| {
+            let reply = response(&body);
+            calls.lock().push(CapturedCall {
+                bot_id: id.clone(),
+                body,
+            });
+            Box::pin(async move { Ok(reply) })
+        });
+        let connection =
+            ConnectionHandle::new_http_client(bot_id, post_json, CancellationToken::new());
+        self.adapter.create_bot(bot_id, connection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_data_round_trip() {
+        let text = RecordedFrame::new("onebot", "10001", br#"{"post_type":"message"}"#);
+        let line = serde_json::to_string(&text).unwrap();
+        assert!(line.contains(r#""data":"{\"post_type\":\"message\"}""#));
+        assert_eq!(serde_json::from_str::<RecordedFrame>(&line).unwrap(), text);
+
+        let binary = RecordedFrame::new("onebot", "10001", &[0xff, 0x00, 0x7f]);
+        let line = serde_json::to_string(&binary).unwrap();
+        assert!(line.contains(r#""data":[255,0,127]"#));
+        assert_eq!(
+            serde_json::from_str::<RecordedFrame>(&line).unwrap(),
+            binary
+        );
+
+        let mut marked = RecordedFrame::new("onebot", "10001", b"\x01\x02");
+        marked.binary = true;
+        let line = serde_json::to_string(&marked).unwrap();
+        assert!(line.contains(r#""binary":true"#));
+        let parsed = serde_json::from_str::<RecordedFrame>(&line).unwrap();
+        assert_eq!(parsed.frame(), Frame::Binary(vec![1, 2]));
+        assert!(!serde_json::to_string(&text).unwrap().contains("binary"));
+    }
+
+    #[test]
+    fn test_recorder_appends_jsonl() {
+        let path = std::env::temp_dir().join(format!(
+            "alloy-record-{}-{}.jsonl",
+            std::process::id(),
+            RecordedFrame::new("", "", b"").timestamp_ms
+        ));
+        let recorder = EventRecorder::open(&path).unwrap();
+        recorder.record("onebot", "1", b"first");
+        recorder.record("onebot", "2", b"second");
+        drop(recorder);
+
+        let frames = read_recording(&path).unwrap();
+        fs::remove_file(&path).ok();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].bot_id, "1");
+        assert_eq!(frames[1].data, b"second");
+    }
+}
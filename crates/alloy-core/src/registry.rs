@@ -0,0 +1,251 @@
+//! Runtime-wide registry of connected bots.
+//!
+//! Every [`AdapterBridge`](crate::bridge::AdapterBridge) keeps its own bot
+//! map for routing inbound frames.  A [`BotRegistry`] shared by all bridges
+//! mirrors those maps so that code without an incoming event — scheduled
+//! tasks, dashboards, cross-bot forwarding — can still find a bot:
+//!
+//! ```rust,ignore
+//! async fn broadcast(registry: Arc<BotRegistry>) {
+//!     for bot in registry.by_platform("onebot") {
+//!         bot.send_to(&Target::group("123456"), &RichText::new().text("hi"))
+//!             .await
+//!             .ok();
+//!     }
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use parking_lot::RwLock;
+use tokio::sync::broadcast;
+
+use crate::bot::BoxedBot;
+
+/// Capacity of the change channel; slower subscribers observe
+/// [`RecvError::Lagged`](broadcast::error::RecvError::Lagged).
+const CHANGE_CHANNEL_CAPACITY: usize = 64;
+
+/// A bot joining or leaving the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BotChange {
+    /// A bot was registered.
+    Joined {
+        /// The bot's ID.
+        bot_id: String,
+        /// Name of the adapter that owns the bot.
+        platform: String,
+    },
+    /// A bot was unregistered.
+    Left {
+        /// The bot's ID.
+        bot_id: String,
+        /// Name of the adapter that owned the bot.
+        platform: String,
+    },
+}
+
+/// `(platform, bot_id)`.
+type Key = (String, String);
+
+/// All bots currently connected, across every adapter.
+///
+/// Bots are keyed by platform and ID, so two adapters may each have a bot
+/// with the same ID (say, the same QQ account over OneBot and another
+/// protocol).  Registering a key that is already present replaces the
+/// previous bot.
+pub struct BotRegistry {
+    bots: RwLock<HashMap<Key, BoxedBot>>,
+    changes: broadcast::Sender<BotChange>,
+}
+
+impl BotRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        let (changes, _) = broadcast::channel(CHANGE_CHANNEL_CAPACITY);
+        Self {
+            bots: RwLock::new(HashMap::new()),
+            changes,
+        }
+    }
+
+    // ─── Lookup ──────────────────────────────────────────────────────────────
+
+    /// Returns the bot of `platform` (adapter name) with the given ID.
+    pub fn get(&self, platform: &str, bot_id: &str) -> Option<BoxedBot> {
+        self.bots
+            .read()
+            .get(&(platform.to_string(), bot_id.to_string()))
+            .cloned()
+    }
+
+    /// Returns the platforms (adapter names) that have a bot with the given
+    /// ID.
+    pub fn platforms_of(&self, bot_id: &str) -> Vec<String> {
+        self.bots
+            .read()
+            .keys()
+            .filter(|(_, id)| id == bot_id)
+            .map(|(platform, _)| platform.clone())
+            .collect()
+    }
+
+    /// Returns every bot of the given platform.
+    pub fn by_platform(&self, platform: &str) -> Vec<BoxedBot> {
+        self.bots
+            .read()
+            .iter()
+            .filter(|((p, _), _)| p == platform)
+            .map(|(_, bot)| bot.clone())
+            .collect()
+    }
+
+    /// Returns every registered bot.
+    pub fn bots(&self) -> Vec<BoxedBot> {
+        self.bots.read().values().cloned().collect()
+    }
+
+    /// Returns the IDs of every registered bot; an ID registered by several
+    /// platforms appears once for each.
+    pub fn ids(&self) -> Vec<String> {
+        self.bots.read().keys().map(|(_, id)| id.clone()).collect()
+    }
+
+    /// Returns the number of registered bots.
+    pub fn len(&self) -> usize {
+        self.bots.read().len()
+    }
+
+    /// Returns `true` if no bot is registered.
+    pub fn is_empty(&self) -> bool {
+        self.bots.read().is_empty()
+    }
+
+    /// Subscribes to bots joining and leaving.
+    ///
+    /// Only changes after the call are delivered; combine with
+    /// [`bots`](Self::bots) for the current state.
+    pub fn subscribe(&self) -> broadcast::Receiver<BotChange> {
+        self.changes.subscribe()
+    }
+
+    // ─── Mutation (bridges) ──────────────────────────────────────────────────
+
+    /// Adds a bot.  Called by the bridge that created it.
+    pub fn register(&self, platform: impl Into<String>, bot: BoxedBot) {
+        let platform = platform.into();
+        let bot_id = bot.id().to_string();
+        self.bots
+            .write()
+            .insert((platform.clone(), bot_id.clone()), bot);
+        // No subscribers is not an error.
+        let _ = self.changes.send(BotChange::Joined { bot_id, platform });
+    }
+
+    /// Removes a bot of `platform`, returning it if it was registered.
+    ///
+    /// A bot with the same ID registered by another platform is left in
+    /// place.
+    pub fn unregister(&self, platform: &str, bot_id: &str) -> Option<BoxedBot> {
+        let bot = self
+            .bots
+            .write()
+            .remove(&(platform.to_string(), bot_id.to_string()))?;
+        let _ = self.changes.send(BotChange::Left {
+            bot_id: bot_id.to_string(),
+            platform: platform.to_string(),
+        });
+        Some(bot)
+    }
+}
+
+impl Default for BotRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for BotRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BotRegistry")
+            .field("bots", &self.ids())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::bot::Bot;
+    use crate::error::ApiResult;
+    use crate::event::Event;
+    use crate::message::ErasedMessage;
+
+    struct TestBot(&'static str);
+
+    #[async_trait]
+    impl Bot for TestBot {
+        fn id(&self) -> &str {
+            self.0
+        }
+
+        async fn call_api(&self, _action: &str, _params: Value) -> ApiResult<Value> {
+            Ok(Value::Null)
+        }
+
+        async fn send(&self, _event: &dyn Event, _message: &str) -> ApiResult<String> {
+            Ok(String::new())
+        }
+
+        async fn send_message(
+            &self,
+            _event: &dyn Event,
+            _message: &dyn ErasedMessage,
+        ) -> ApiResult<String> {
+            Ok(String::new())
+        }
+
+        fn as_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+            self
+        }
+    }
+
+    #[test]
+    fn test_same_id_on_two_platforms() {
+        let registry = BotRegistry::new();
+        let first = Arc::new(TestBot("10001")) as BoxedBot;
+        let second = Arc::new(TestBot("10001")) as BoxedBot;
+        registry.register("first", first.clone());
+        registry.register("second", second.clone());
+
+        // Both bots are registered side by side.
+        assert_eq!(registry.len(), 2);
+        assert!(Arc::ptr_eq(
+            &registry.get("first", "10001").unwrap(),
+            &first
+        ));
+        assert!(Arc::ptr_eq(
+            &registry.get("second", "10001").unwrap(),
+            &second
+        ));
+        assert!(registry.get("third", "10001").is_none());
+        let mut platforms = registry.platforms_of("10001");
+        platforms.sort();
+        assert_eq!(platforms, ["first", "second"]);
+
+        // One platform's disconnect leaves the other's bot in place.
+        assert!(registry.unregister("first", "10001").is_some());
+        assert!(registry.unregister("first", "10001").is_none());
+        assert_eq!(registry.platforms_of("10001"), ["second"]);
+        assert!(Arc::ptr_eq(&registry.by_platform("second")[0], &second));
+
+        assert!(registry.unregister("second", "10001").is_some());
+        assert!(registry.is_empty());
+    }
+}
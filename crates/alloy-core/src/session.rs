@@ -0,0 +1,198 @@
+//! Platform-agnostic description of *who* triggered an event and *where*.
+//!
+//! A [`Session`] is the stable key for anything that needs to know the
+//! conversation an event belongs to — replies, cooldowns, permissions,
+//! logging — without adapter-specific downcasting or JSON parsing.
+//!
+//! ```text
+//! Session
+//! ├── platform   "onebot"
+//! ├── bot_id     "10001"
+//! ├── scene      Private | Group | Channel
+//! ├── scene_id   group / channel id, or the peer's user id for Private
+//! └── user_id    Some("12345")
+//! ```
+//!
+//! A [`Target`] is the "where" half on its own: the destination of a
+//! proactive message sent with [`Bot::send_to`](crate::Bot::send_to).
+//!
+//! Sessions are produced by [`Event::session`](crate::Event::session), which
+//! `#[derive(BotEvent)]` generates from the `#[event(bot_id)]`,
+//! `#[event(user_id)]`, `#[event(group_id)]` and `#[event(channel_id)]`
+//! field markers.
+
+use std::fmt;
+use std::sync::Arc;
+
+/// The kind of chat an event happened in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SceneKind {
+    /// One-to-one chat; the scene id is the peer's user id.
+    Private,
+    /// Group chat.
+    Group,
+    /// Channel (guild sub-channel, thread, …).
+    Channel,
+}
+
+impl SceneKind {
+    /// Returns the lowercase name of the scene kind.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Private => "private",
+            Self::Group => "group",
+            Self::Channel => "channel",
+        }
+    }
+}
+
+impl fmt::Display for SceneKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Identifies the bot, chat and user an event belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Session {
+    /// Platform name, as returned by [`Event::platform`](crate::Event::platform).
+    pub platform: &'static str,
+    /// ID of the bot that received the event.
+    pub bot_id: String,
+    /// Kind of chat.
+    pub scene: SceneKind,
+    /// Group / channel id, or the peer's user id for private chats.
+    pub scene_id: String,
+    /// The user that triggered the event, if any.
+    pub user_id: Option<String>,
+}
+
+impl Session {
+    /// Returns `true` for one-to-one chats.
+    pub fn is_private(&self) -> bool {
+        self.scene == SceneKind::Private
+    }
+
+    /// Returns `true` if `other` is in the same chat of the same bot.
+    pub fn same_scene(&self, other: &Session) -> bool {
+        self.platform == other.platform
+            && self.bot_id == other.bot_id
+            && self.scene == other.scene
+            && self.scene_id == other.scene_id
+    }
+
+    /// Returns the chat this session happened in, as a send target.
+    pub fn target(&self) -> Target {
+        Target::new(self.scene, self.scene_id.clone())
+    }
+}
+
+/// `platform:bot_id:scene:scene_id[:user_id]`, e.g. `onebot:10001:group:123:456`.
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            self.platform, self.bot_id, self.scene, self.scene_id
+        )?;
+        if let Some(user_id) = &self.user_id {
+            write!(f, ":{user_id}")?;
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Target
+// ============================================================================
+
+/// Destination of a message sent without an originating event.
+///
+/// ```rust,ignore
+/// bot.send_to(&Target::group("123456"), &msg).await?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Target {
+    /// Kind of chat.
+    pub scene: SceneKind,
+    /// User id for private chats, otherwise the group / channel id.
+    pub id: String,
+}
+
+impl Target {
+    /// Creates a target from a scene kind and id.
+    pub fn new(scene: SceneKind, id: impl Into<String>) -> Self {
+        Self {
+            scene,
+            id: id.into(),
+        }
+    }
+
+    /// A private chat with the given user.
+    pub fn user(id: impl Into<String>) -> Self {
+        Self::new(SceneKind::Private, id)
+    }
+
+    /// A group chat.
+    pub fn group(id: impl Into<String>) -> Self {
+        Self::new(SceneKind::Group, id)
+    }
+
+    /// A channel.
+    pub fn channel(id: impl Into<String>) -> Self {
+        Self::new(SceneKind::Channel, id)
+    }
+}
+
+/// `scene:id`, e.g. `group:123456`.
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.scene, self.id)
+    }
+}
+
+impl From<&Session> for Target {
+    fn from(session: &Session) -> Self {
+        session.target()
+    }
+}
+
+// ============================================================================
+// SessionId — field conversion used by the derive macro
+// ============================================================================
+
+/// Converts an event field into an id usable in a [`Session`].
+///
+/// Implemented for the integer and string types adapters typically use, and
+/// for `Option<T>` so that optional fields (e.g. a poke that may or may not
+/// happen in a group) simply produce no scene.
+#[doc(hidden)]
+pub trait SessionId {
+    fn session_id(&self) -> Option<String>;
+}
+
+macro_rules! impl_session_id_display {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl SessionId for $ty {
+                fn session_id(&self) -> Option<String> {
+                    Some(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_session_id_display!(i32, i64, u32, u64, String, str, Arc<str>);
+
+impl<T: SessionId + ?Sized> SessionId for &T {
+    fn session_id(&self) -> Option<String> {
+        (**self).session_id()
+    }
+}
+
+impl<T: SessionId> SessionId for Option<T> {
+    fn session_id(&self) -> Option<String> {
+        self.as_ref().and_then(SessionId::session_id)
+    }
+}
@@ -5,9 +5,36 @@
 //!
 //! # Overview
 //!
-//! Each capability is a plain function pointer (`fn(Args...) -> BoxFuture`).
-//! Because they carry no captured state, all parameters are passed explicitly.
-//! Adapters call them via `ctx.ws_client()(url, handler, config).await`.
+//! A capability is a marker type implementing [`Capability`]: it names the
+//! capability and fixes the signature of its implementation, a plain function
+//! pointer (`fn(Args...) -> BoxFuture`).  Because implementations carry no
+//! captured state, all parameters are passed explicitly.  Adapters look them
+//! up by type:
+//!
+//! ```rust,ignore
+//! if let Some(ws_connect) = ctx.transport().get::<WsClient>() {
+//!     let handle = ws_connect(config, ctx.clone().as_connection_handler()).await?;
+//! }
+//! ```
+//!
+//! Transport crates contribute implementations with
+//! `#[register_capability(WsClient)]`, which adds a [`CapabilityProvider`] to
+//! [`CAPABILITY_REGISTRY`].  New capabilities need no change here — define a
+//! marker type next to the transport:
+//!
+//! ```rust,ignore
+//! pub struct SseServer;
+//!
+//! impl Capability for SseServer {
+//!     const NAME: &'static str = "sse_server";
+//!     type Fn = fn(String, String, Arc<dyn ConnectionHandler>)
+//!         -> BoxFuture<'static, TransportResult<ListenerHandle>>;
+//! }
+//! ```
+//!
+//! When several providers are linked for one capability,
+//! [`TransportContext::collect`] picks the one named in its preferences and
+//! otherwise the first, with a warning.
 //!
 //! # Dynamic Bot Management
 //!
@@ -15,6 +42,8 @@
 //! - **Server transports**: New connections become bots, disconnections remove them
 //! - **Client transports**: Configured endpoints auto-reconnect on disconnect
 
+use std::any::Any;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -22,8 +51,10 @@ use futures::future::BoxFuture;
 use linkme::distributed_slice;
 use tracing::warn;
 
-use super::config::{HttpClientConfig, WsClientConfig};
-use super::connection::{ConnectionHandle, ConnectionInfo, ListenerHandle};
+use super::config::{HttpClientConfig, HttpServerConfig, WsClientConfig, WsServerConfig};
+use super::connection::{ConnectionHandle, ConnectionInfo, ListenerHandle, ResponseSlot};
+use super::frame::Frame;
+use super::loopback::LoopbackPeer;
 use crate::error::TransportResult;
 
 // =============================================================================
@@ -38,183 +69,373 @@ use crate::error::TransportResult;
 /// [`AdapterBridge`](crate::adapter::AdapterBridge) is the built-in implementation.
 #[async_trait]
 pub trait ConnectionHandler: Send + Sync {
+    /// Decide whether an incoming connection may proceed.
+    ///
+    /// Server transports call this before accepting a connection (for
+    /// WebSocket, before the upgrade) and reject it with `401 Unauthorized`
+    /// on error.  Accepts everything by default.
+    fn authenticate(&self, _conn_info: &ConnectionInfo) -> TransportResult<()> {
+        Ok(())
+    }
+
+    /// Check a request body before it is processed, e.g. against a
+    /// signature header.
+    ///
+    /// The HTTP server calls this with the raw body of every request and
+    /// rejects it with `401 Unauthorized` on error, before any bot is created
+    /// or event dispatched.  Accepts everything by default.
+    fn verify_request(&self, _conn_info: &ConnectionInfo, _body: &[u8]) -> TransportResult<()> {
+        Ok(())
+    }
+
     /// Extract a bot ID from connection metadata when a new connection arrives.
     fn get_bot_id(&self, conn_info: ConnectionInfo) -> TransportResult<String>;
 
     /// Create and register a bot for a new connection.
+    ///
+    /// If the bot already exists, the connection is attached to it instead.
     fn create_bot(&self, bot_id: &str, connection: ConnectionHandle);
 
-    /// Process incoming data from a connection.
-    async fn on_message(&self, bot_id: &str, data: &[u8]);
+    /// Process a frame received on a connection.
+    ///
+    /// Text and binary frames arrive exactly as the peer sent them.  A close
+    /// frame is delivered before the connection is torn down, so its code
+    /// can be inspected.
+    async fn on_message(&self, bot_id: &str, frame: &Frame);
+
+    /// Process a request that can be answered through `response`.
+    ///
+    /// The default ignores `response` and delegates to
+    /// [`on_message`](Self::on_message) with a text frame, or a binary one
+    /// if `data` is not UTF-8.
+    async fn on_request(&self, bot_id: &str, data: &[u8], response: ResponseSlot) {
+        drop(response);
+        self.on_message(bot_id, &Frame::from_payload(data.to_vec()))
+            .await;
+    }
 
     /// Called when a connection is closed.
-    async fn on_disconnect(&self, bot_id: &str);
+    ///
+    /// The bot is only removed once its last connection has closed.
+    async fn on_disconnect(&self, connection: &ConnectionHandle);
 }
 
 // =============================================================================
-// Capability Function Types
+// Capability Trait
 // =============================================================================
 
-/// Function pointer that starts a WebSocket server listener.
+/// A kind of transport functionality, identified by name and typed by the
+/// function pointer its providers implement.
+///
+/// Implemented by zero-sized marker types such as [`WsServer`].
+pub trait Capability: 'static {
+    /// Registry key, also used to choose a provider in configuration.
+    const NAME: &'static str;
+
+    /// Function pointer type of the implementation.
+    type Fn: Copy + Send + Sync + 'static;
+}
+
+/// Starts a WebSocket server listener.
+///
+/// Parameters: `(config, handler)`.
+pub struct WsServer;
+
+impl Capability for WsServer {
+    const NAME: &'static str = "ws_server";
+    type Fn = WsListenFn;
+}
+
+/// Opens a WebSocket client connection.
+///
+/// Parameters: `(config, handler)`.
+pub struct WsClient;
+
+impl Capability for WsClient {
+    const NAME: &'static str = "ws_client";
+    type Fn = WsConnectFn;
+}
+
+/// Starts an HTTP server listener.
+///
+/// Parameters: `(config, handler)`.
+pub struct HttpServer;
+
+impl Capability for HttpServer {
+    const NAME: &'static str = "http_server";
+    type Fn = HttpListenFn;
+}
+
+/// Registers an HTTP outbound API-client bot.
 ///
-/// Parameters: `(addr, path, handler)` — all owned to satisfy `'static` bounds.
+/// Parameters: `(bot_id, config, handler)`.
+pub struct HttpClient;
+
+impl Capability for HttpClient {
+    const NAME: &'static str = "http_client";
+    type Fn = HttpStartClientFn;
+}
+
+/// Connects a handler to an in-process peer instead of the network.
+///
+/// Parameters: `(conn_info, handler)`.  `conn_info` goes to
+/// [`ConnectionHandler::get_bot_id`] as if a real client had connected; the
+/// bot gets a WebSocket-style [`ConnectionHandle`] and the caller keeps the
+/// [`LoopbackPeer`].
+pub struct Loopback;
+
+impl Capability for Loopback {
+    const NAME: &'static str = "loopback";
+    type Fn = LoopbackConnectFn;
+}
+
+/// Function pointer type of [`WsServer`].
 pub type WsListenFn = fn(
-    String,
-    String,
+    WsServerConfig,
     Arc<dyn ConnectionHandler>,
 ) -> BoxFuture<'static, TransportResult<ListenerHandle>>;
 
-/// Function pointer that opens a WebSocket client connection.
-///
-/// Parameters: `(config, handler)`.
+/// Function pointer type of [`WsClient`].
 pub type WsConnectFn = fn(
     WsClientConfig,
     Arc<dyn ConnectionHandler>,
 ) -> BoxFuture<'static, TransportResult<ConnectionHandle>>;
 
-/// Function pointer that starts an HTTP server listener.
-///
-/// Parameters: `(addr, path, handler)`.
+/// Function pointer type of [`HttpServer`].
 pub type HttpListenFn = fn(
-    String,
-    String,
+    HttpServerConfig,
     Arc<dyn ConnectionHandler>,
 ) -> BoxFuture<'static, TransportResult<ListenerHandle>>;
 
-/// Function pointer that registers an HTTP outbound API-client bot.
-///
-/// Parameters: `(bot_id, config, handler)`.
+/// Function pointer type of [`HttpClient`].
 pub type HttpStartClientFn = fn(
     String,
     HttpClientConfig,
     Arc<dyn ConnectionHandler>,
 ) -> BoxFuture<'static, TransportResult<ConnectionHandle>>;
 
+/// Function pointer type of [`Loopback`].
+pub type LoopbackConnectFn =
+    fn(
+        ConnectionInfo,
+        Arc<dyn ConnectionHandler>,
+    ) -> BoxFuture<'static, TransportResult<(ConnectionHandle, LoopbackPeer)>>;
+
 // =============================================================================
-// Capability Registries (linkme distributed slices)
+// Capability Registry (linkme distributed slice)
 // =============================================================================
 
-/// Registry of WebSocket server listen function pointers.
-/// Each crate that provides a ws-server capability contributes one entry.
-#[distributed_slice]
-pub static WS_LISTEN_REGISTRY: [WsListenFn];
-
-/// Registry of WebSocket client connect function pointers.
-#[distributed_slice]
-pub static WS_CONNECT_REGISTRY: [WsConnectFn];
-
-/// Registry of HTTP server listen function pointers.
-#[distributed_slice]
-pub static HTTP_LISTEN_REGISTRY: [HttpListenFn];
+/// One linked implementation of a capability.
+///
+/// Emitted by `#[register_capability(...)]`; not usually written by hand.
+pub struct CapabilityProvider {
+    /// [`Capability::NAME`] of the implemented capability.
+    pub capability: &'static str,
+    /// Provider name, by default the implementing crate's package name.
+    pub provider: &'static str,
+    /// The implementation, a `<C as Capability>::Fn`.
+    pub implementation: &'static (dyn Any + Send + Sync),
+}
 
-/// Registry of HTTP client start function pointers.
+/// Every capability implementation linked into the binary.
 #[distributed_slice]
-pub static HTTP_START_CLIENT_REGISTRY: [HttpStartClientFn];
-
-// Will be defined as impl method for TransportContext
+pub static CAPABILITY_REGISTRY: [CapabilityProvider];
+
+/// Returns the names of all linked providers of `capability`, in link order.
+pub fn capability_providers(capability: &str) -> Vec<&'static str> {
+    CAPABILITY_REGISTRY
+        .iter()
+        .filter(|p| p.capability == capability)
+        .map(|p| p.provider)
+        .collect()
+}
 
 // =============================================================================
 // Transport Context
 // =============================================================================
 
+/// A capability implementation held by a [`TransportContext`].
+#[derive(Clone)]
+enum Slot {
+    Linked(&'static CapabilityProvider),
+    Custom(Arc<dyn Any + Send + Sync>),
+}
+
+impl Slot {
+    fn implementation(&self) -> &(dyn Any + Send + Sync) {
+        match self {
+            Slot::Linked(provider) => provider.implementation,
+            Slot::Custom(implementation) => implementation.as_ref(),
+        }
+    }
+}
+
 /// Context for adapter initialization.
 ///
-/// Provides access to available transport capabilities.
-#[derive(Copy, Clone)]
+/// Provides access to available transport capabilities, at most one
+/// implementation per capability.
+#[derive(Clone, Default)]
 pub struct TransportContext {
-    ws_server: Option<WsListenFn>,
-    ws_client: Option<WsConnectFn>,
-    http_server: Option<HttpListenFn>,
-    http_client: Option<HttpStartClientFn>,
+    capabilities: HashMap<&'static str, Slot>,
 }
 
 impl TransportContext {
     /// Creates a new empty context.
     pub fn new() -> Self {
-        Self {
-            ws_server: None,
-            ws_client: None,
-            http_server: None,
-            http_client: None,
-        }
+        Self::default()
     }
 
     /// Builds a [`TransportContext`] from all capability functions registered via
     /// `#[register_capability(...)]`.
     ///
     /// If multiple providers are registered for the same capability type a warning
-    /// is emitted and the **first** one wins.
+    /// is emitted and the **first** one wins; use [`collect`](Self::collect) to
+    /// choose.
     pub fn collect_all() -> Self {
-        fn load<T: Copy>(registry: &[T], name: &str) -> Option<T> {
-            match registry.len() {
-                0 => None,
-                1 => Some(registry[0]),
-                n => {
-                    warn!(
-                        count = n,
-                        capability = name,
-                        "Multiple capability providers registered, using first"
-                    );
-                    Some(registry[0])
-                }
-            }
+        Self::collect(&HashMap::new())
+    }
+
+    /// Like [`collect_all`](Self::collect_all), but where several providers
+    /// implement a capability, uses the one `preferences` maps its name to.
+    ///
+    /// A preference naming a provider that is not linked is logged and the
+    /// first provider is used instead.
+    pub fn collect(preferences: &HashMap<String, String>) -> Self {
+        let mut by_capability: HashMap<&'static str, Vec<&'static CapabilityProvider>> =
+            HashMap::new();
+        for provider in CAPABILITY_REGISTRY.iter() {
+            by_capability
+                .entry(provider.capability)
+                .or_default()
+                .push(provider);
         }
 
-        TransportContext {
-            ws_server: load(&WS_LISTEN_REGISTRY, "ws_server"),
-            ws_client: load(&WS_CONNECT_REGISTRY, "ws_client"),
-            http_server: load(&HTTP_LISTEN_REGISTRY, "http_server"),
-            http_client: load(&HTTP_START_CLIENT_REGISTRY, "http_client"),
+        for (capability, provider) in preferences {
+            if !by_capability.contains_key(capability.as_str()) {
+                warn!(
+                    capability = %capability,
+                    provider = %provider,
+                    "Provider preference for a capability with no providers"
+                );
+            }
         }
+
+        let capabilities = by_capability
+            .into_iter()
+            .map(|(capability, providers)| {
+                let chosen = match preferences.get(capability) {
+                    Some(wanted) => providers
+                        .iter()
+                        .find(|p| p.provider == wanted)
+                        .copied()
+                        .unwrap_or_else(|| {
+                            warn!(
+                                capability,
+                                provider = %wanted,
+                                available = ?providers.iter().map(|p| p.provider).collect::<Vec<_>>(),
+                                "Preferred capability provider not linked, using first"
+                            );
+                            providers[0]
+                        }),
+                    None => {
+                        if providers.len() > 1 {
+                            warn!(
+                                count = providers.len(),
+                                capability,
+                                "Multiple capability providers registered, using first"
+                            );
+                        }
+                        providers[0]
+                    }
+                };
+                (capability, Slot::Linked(chosen))
+            })
+            .collect();
+
+        Self { capabilities }
     }
 
-    /// Registers the WebSocket server capability.
-    pub fn with_ws_server(mut self, f: WsListenFn) -> Self {
-        self.ws_server = Some(f);
+    /// Registers (or replaces) the implementation of capability `C`.
+    pub fn with<C: Capability>(mut self, implementation: C::Fn) -> Self {
+        self.capabilities
+            .insert(C::NAME, Slot::Custom(Arc::new(implementation)));
         self
     }
 
-    /// Registers the WebSocket client capability.
-    pub fn with_ws_client(mut self, f: WsConnectFn) -> Self {
-        self.ws_client = Some(f);
-        self
+    /// Gets the implementation of capability `C` if available.
+    pub fn get<C: Capability>(&self) -> Option<C::Fn> {
+        let implementation = self.capabilities.get(C::NAME)?.implementation();
+        match implementation.downcast_ref::<C::Fn>() {
+            Some(f) => Some(*f),
+            None => {
+                warn!(
+                    capability = C::NAME,
+                    "Capability provider has a different signature than requested"
+                );
+                None
+            }
+        }
     }
 
-    /// Registers the HTTP server capability.
-    pub fn with_http_server(mut self, f: HttpListenFn) -> Self {
-        self.http_server = Some(f);
-        self
+    /// Returns `true` if a capability with the given name is available.
+    pub fn has(&self, capability: &str) -> bool {
+        self.capabilities.contains_key(capability)
     }
 
-    /// Registers the HTTP client capability.
-    pub fn with_http_client(mut self, f: HttpStartClientFn) -> Self {
-        self.http_client = Some(f);
-        self
+    /// Returns the names of all available capabilities.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.capabilities.keys().copied().collect()
     }
 
-    /// Gets the WebSocket server capability if available.
-    pub fn ws_server(&self) -> Option<WsListenFn> {
-        self.ws_server
+    /// Returns the provider chosen for a capability, or `None` if it is
+    /// missing or was set with [`with`](Self::with).
+    pub fn provider(&self, capability: &str) -> Option<&'static str> {
+        match self.capabilities.get(capability)? {
+            Slot::Linked(provider) => Some(provider.provider),
+            Slot::Custom(_) => None,
+        }
     }
+}
 
-    /// Gets the WebSocket client capability if available.
-    pub fn ws_client(&self) -> Option<WsConnectFn> {
-        self.ws_client
+impl std::fmt::Debug for TransportContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.capabilities.iter().map(|(name, slot)| match slot {
+                Slot::Linked(p) => (name, p.provider),
+                Slot::Custom(_) => (name, "<custom>"),
+            }))
+            .finish()
     }
+}
 
-    /// Gets the HTTP server capability if available.
-    pub fn http_server(&self) -> Option<HttpListenFn> {
-        self.http_server
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::ConnectionInfo;
+
+    struct Upper;
+
+    impl Capability for Upper {
+        const NAME: &'static str = "upper";
+        type Fn = fn(String) -> String;
     }
 
-    /// Gets the HTTP client capability if available.
-    pub fn http_client(&self) -> Option<HttpStartClientFn> {
-        self.http_client
+    /// Same name as [`Upper`], different signature.
+    struct Impostor;
+
+    impl Capability for Impostor {
+        const NAME: &'static str = "upper";
+        type Fn = fn(ConnectionInfo) -> String;
     }
-}
 
-impl Default for TransportContext {
-    fn default() -> Self {
-        Self::new()
+    #[test]
+    fn test_custom_capability() {
+        let ctx = TransportContext::new().with::<Upper>(|s| s.to_uppercase());
+
+        assert!(ctx.has("upper"));
+        assert_eq!(ctx.provider("upper"), None);
+        assert_eq!(ctx.get::<Upper>().unwrap()("hi".into()), "HI");
+        assert!(ctx.get::<Impostor>().is_none());
+        assert!(ctx.get::<WsServer>().is_none());
     }
 }
@@ -1,7 +1,11 @@
-//! Configuration types for transport clients.
+//! Configuration types for transport clients and servers.
 
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::Duration;
 
+use serde::{Deserialize, Serialize};
+
 // =============================================================================
 // WebSocket Client Config
 // =============================================================================
@@ -21,10 +25,34 @@ pub struct WsClientConfig {
     pub max_delay: Duration,
     /// Backoff multiplier.
     pub backoff_multiplier: f64,
-    /// Optional access token for authentication.
+    /// Fraction (0.0–1.0) of each backoff delay that is randomized, so
+    /// clients dropped together do not reconnect in lockstep.
+    pub jitter: f64,
+    /// Optional access token, sent as `Authorization: Bearer <token>` in the
+    /// handshake.
     pub access_token: Option<String>,
-    /// Heartbeat interval for WebSocket keep-alive.
+    /// Extra headers sent with every handshake (e.g. `X-Self-ID`,
+    /// `X-Client-Role`).
+    pub headers: HashMap<String, String>,
+    /// Interval between keep-alive pings (None = no pings).
+    ///
+    /// If nothing, not even a pong, arrives between two pings, the connection
+    /// is considered dead and closed (and reconnected if enabled).
     pub heartbeat_interval: Option<Duration>,
+    /// Connect through this Unix socket instead of TCP.
+    ///
+    /// `url` still supplies the request path and `Host` header.
+    pub unix_socket: Option<PathBuf>,
+    /// Certificate settings for `wss://` URLs.
+    pub tls: ClientTlsConfig,
+    /// Proxy to connect through; ignored with `unix_socket`.
+    pub proxy: ProxyConfig,
+    /// Time allowed for each connection attempt, from opening the socket to
+    /// the end of the WebSocket handshake.
+    pub connect_timeout: Duration,
+    /// Default timeout of API calls made over the connection (None = the
+    /// adapter's default).
+    pub call_timeout: Option<Duration>,
 }
 
 impl Default for WsClientConfig {
@@ -36,8 +64,15 @@ impl Default for WsClientConfig {
             initial_delay: Duration::from_secs(1),
             max_delay: Duration::from_secs(60),
             backoff_multiplier: 2.0,
+            jitter: 0.2,
             access_token: None,
+            headers: HashMap::new(),
             heartbeat_interval: Some(Duration::from_secs(30)),
+            unix_socket: None,
+            tls: ClientTlsConfig::default(),
+            proxy: ProxyConfig::default(),
+            connect_timeout: Duration::from_secs(10),
+            call_timeout: None,
         }
     }
 }
@@ -66,11 +101,332 @@ impl WsClientConfig {
         self
     }
 
+    /// Adds a header to the handshake request.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Sets the keep-alive ping interval; `None` disables pings.
+    pub fn with_heartbeat(mut self, interval: Option<Duration>) -> Self {
+        self.heartbeat_interval = interval;
+        self
+    }
+
     /// Sets the maximum retry count.
     pub fn with_max_retries(mut self, max: u32) -> Self {
         self.max_retries = Some(max);
         self
     }
+
+    /// Enables or disables reconnecting, waiting `initial_delay` before the
+    /// first attempt.
+    pub fn with_reconnect(mut self, enabled: bool, initial_delay: Duration) -> Self {
+        self.auto_reconnect = enabled;
+        self.initial_delay = initial_delay;
+        self
+    }
+
+    /// Connects through the Unix socket at `path`.
+    pub fn with_unix_socket(mut self, path: impl Into<PathBuf>) -> Self {
+        self.unix_socket = Some(path.into());
+        self
+    }
+
+    /// Sets the certificate settings used for `wss://` URLs.
+    pub fn with_tls(mut self, tls: ClientTlsConfig) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Sets the proxy to connect through.
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// Sets the time allowed for each connection attempt.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Sets the default timeout of API calls made over the connection.
+    pub fn with_call_timeout(mut self, timeout: Duration) -> Self {
+        self.call_timeout = Some(timeout);
+        self
+    }
+}
+
+// =============================================================================
+// WebSocket Server Config
+// =============================================================================
+
+/// Configuration for a WebSocket server listener.
+///
+/// The settings apply to every connection accepted on `path`.
+#[derive(Debug, Clone)]
+pub struct WsServerConfig {
+    /// Address to bind: `host:port` or `unix:<path>`.
+    pub addr: String,
+    /// Request path of the WebSocket endpoint.
+    pub path: String,
+    /// Serve TLS with these files; TCP addresses only.
+    pub tls: Option<ServerTlsConfig>,
+    /// Permissions of the socket file for a `unix:<path>` address (None =
+    /// `0o660`).
+    pub socket_mode: Option<u32>,
+    /// Interval between keep-alive pings to each client (None = no pings).
+    pub ping_interval: Option<Duration>,
+    /// How long a client may take to answer a ping.
+    ///
+    /// A client that sends nothing, not even a pong, within this time after
+    /// a ping is considered gone and disconnected.
+    pub pong_timeout: Duration,
+    /// Disconnect clients that send no text or binary frame for this long
+    /// (None = never).  Pings and pongs do not count.
+    pub idle_timeout: Option<Duration>,
+    /// Maximum concurrent connections on this path (None = unlimited).
+    pub max_connections: Option<usize>,
+    /// Maximum concurrent connections on this path from one IP address
+    /// (None = unlimited).  Unix socket clients are not counted.
+    pub max_connections_per_ip: Option<usize>,
+    /// Outgoing frames queued per connection while the client is slow to
+    /// read.
+    pub send_queue_capacity: usize,
+    /// What happens to a frame sent while the queue is full.
+    pub send_overflow: SendOverflow,
+    /// Default timeout of API calls made over accepted connections (None =
+    /// the adapter's default).
+    pub call_timeout: Option<Duration>,
+}
+
+impl Default for WsServerConfig {
+    fn default() -> Self {
+        Self {
+            addr: String::new(),
+            path: String::new(),
+            tls: None,
+            socket_mode: None,
+            ping_interval: Some(Duration::from_secs(30)),
+            pong_timeout: Duration::from_secs(10),
+            idle_timeout: None,
+            max_connections: None,
+            max_connections_per_ip: None,
+            send_queue_capacity: 256,
+            send_overflow: SendOverflow::default(),
+            call_timeout: None,
+        }
+    }
+}
+
+impl WsServerConfig {
+    /// Creates a config serving `path` on `addr` with default settings.
+    pub fn new(addr: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            addr: addr.into(),
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Serves TLS with the given certificate files.
+    pub fn with_tls(mut self, tls: ServerTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Sets the permissions of the socket file, e.g. `0o600`.
+    pub fn with_socket_mode(mut self, mode: u32) -> Self {
+        self.socket_mode = Some(mode);
+        self
+    }
+
+    /// Sets the ping interval (`None` disables pings) and the pong deadline.
+    pub fn with_ping(mut self, interval: Option<Duration>, pong_timeout: Duration) -> Self {
+        self.ping_interval = interval;
+        self.pong_timeout = pong_timeout;
+        self
+    }
+
+    /// Sets the idle timeout; `None` disables it.
+    pub fn with_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Limits the concurrent connections on this path.
+    pub fn with_max_connections(mut self, max: usize) -> Self {
+        self.max_connections = Some(max);
+        self
+    }
+
+    /// Limits the concurrent connections on this path per client IP.
+    pub fn with_max_connections_per_ip(mut self, max: usize) -> Self {
+        self.max_connections_per_ip = Some(max);
+        self
+    }
+
+    /// Sets the per-connection send queue size and its overflow policy.
+    pub fn with_send_queue(mut self, capacity: usize, overflow: SendOverflow) -> Self {
+        self.send_queue_capacity = capacity;
+        self.send_overflow = overflow;
+        self
+    }
+
+    /// Sets the default timeout of API calls made over accepted connections.
+    pub fn with_call_timeout(mut self, timeout: Duration) -> Self {
+        self.call_timeout = Some(timeout);
+        self
+    }
+}
+
+/// What a server connection does with an outgoing frame when its send queue
+/// is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SendOverflow {
+    /// The sender waits until the client catches up.
+    #[default]
+    Block,
+    /// The oldest queued frame is discarded to make room.
+    DropOldest,
+    /// The client is disconnected.
+    Disconnect,
+}
+
+// =============================================================================
+// HTTP Server Config
+// =============================================================================
+
+/// Configuration for an HTTP server listener.
+#[derive(Debug, Clone)]
+pub struct HttpServerConfig {
+    /// Address to bind, in the same forms as [`WsServerConfig::addr`].
+    pub addr: String,
+    /// Request path of the POST endpoint.
+    pub path: String,
+    /// Serve TLS with these files; TCP addresses only.
+    pub tls: Option<ServerTlsConfig>,
+    /// Permissions of the socket file for a `unix:<path>` address (None =
+    /// `0o660`).
+    pub socket_mode: Option<u32>,
+    /// How long a request waits for its event's handlers to produce a
+    /// response body before it is answered with `204 No Content`.
+    pub response_deadline: Duration,
+}
+
+impl Default for HttpServerConfig {
+    fn default() -> Self {
+        Self {
+            addr: String::new(),
+            path: String::new(),
+            tls: None,
+            socket_mode: None,
+            response_deadline: Duration::from_secs(5),
+        }
+    }
+}
+
+impl HttpServerConfig {
+    /// Creates a config serving `path` on `addr` with default settings.
+    pub fn new(addr: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            addr: addr.into(),
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Serves TLS with the given certificate files.
+    pub fn with_tls(mut self, tls: ServerTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Sets the permissions of the socket file, e.g. `0o600`.
+    pub fn with_socket_mode(mut self, mode: u32) -> Self {
+        self.socket_mode = Some(mode);
+        self
+    }
+
+    /// Sets how long requests wait for a response body.
+    pub fn with_response_deadline(mut self, deadline: Duration) -> Self {
+        self.response_deadline = deadline;
+        self
+    }
+}
+
+// =============================================================================
+// Server TLS Config
+// =============================================================================
+
+/// Certificate chain and private key a server listener serves TLS with.
+///
+/// Both files are PEM-encoded.  TLS settings belong to the bind address, so
+/// every listener sharing it must use the same files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerTlsConfig {
+    /// Certificate chain.
+    pub cert: PathBuf,
+    /// Private key of the certificate.
+    pub key: PathBuf,
+}
+
+impl ServerTlsConfig {
+    /// Creates a config from a certificate chain and its private key.
+    pub fn new(cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        Self {
+            cert: cert.into(),
+            key: key.into(),
+        }
+    }
+}
+
+// =============================================================================
+// Client TLS Config
+// =============================================================================
+
+/// Certificate settings for TLS client connections.
+///
+/// The default trusts the system's root certificates and presents no client
+/// certificate.  Files are PEM-encoded and read on every connection attempt,
+/// so replaced files take effect at the next reconnect.
+#[derive(Debug, Clone, Default)]
+pub struct ClientTlsConfig {
+    /// Extra CA certificates to trust, in addition to the system roots.
+    pub ca_file: Option<PathBuf>,
+    /// Client certificate chain, for servers requiring mutual TLS.
+    pub client_cert: Option<PathBuf>,
+    /// Private key of `client_cert`.
+    pub client_key: Option<PathBuf>,
+    /// Accept any server certificate.
+    ///
+    /// Only for testing against self-signed certificates: this removes all
+    /// protection against impersonation.
+    pub insecure_skip_verify: bool,
+}
+
+impl ClientTlsConfig {
+    /// Trusts the CA certificates in `path` (PEM) as well.
+    pub fn with_ca_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ca_file = Some(path.into());
+        self
+    }
+
+    /// Presents the certificate chain in `cert` with the private key in
+    /// `key` (both PEM).
+    pub fn with_client_cert(mut self, cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        self.client_cert = Some(cert.into());
+        self.client_key = Some(key.into());
+        self
+    }
+
+    /// Disables server certificate verification.
+    pub fn with_insecure_skip_verify(mut self, skip: bool) -> Self {
+        self.insecure_skip_verify = skip;
+        self
+    }
 }
 
 // =============================================================================
@@ -84,8 +440,15 @@ pub struct HttpClientConfig {
     pub api_url: String,
     /// Optional access token for authentication (used as Bearer token).
     pub access_token: Option<String>,
-    /// Request timeout duration.
+    /// Request timeout duration; also the connection's default API call
+    /// timeout.
     pub timeout: Duration,
+    /// Send requests through this Unix socket instead of TCP.
+    ///
+    /// `api_url` still supplies the request path and `Host` header.
+    pub unix_socket: Option<PathBuf>,
+    /// Proxy to send requests through; ignored with `unix_socket`.
+    pub proxy: ProxyConfig,
 }
 
 impl HttpClientConfig {
@@ -95,6 +458,8 @@ impl HttpClientConfig {
             api_url: api_url.into(),
             access_token: None,
             timeout: Duration::from_secs(30),
+            unix_socket: None,
+            proxy: ProxyConfig::default(),
         }
     }
 
@@ -109,6 +474,18 @@ impl HttpClientConfig {
         self.timeout = timeout;
         self
     }
+
+    /// Sends requests through the Unix socket at `path`.
+    pub fn with_unix_socket(mut self, path: impl Into<PathBuf>) -> Self {
+        self.unix_socket = Some(path.into());
+        self
+    }
+
+    /// Sets the proxy to send requests through.
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = proxy;
+        self
+    }
 }
 
 impl Default for HttpClientConfig {
@@ -116,3 +493,81 @@ impl Default for HttpClientConfig {
         Self::new("")
     }
 }
+
+// =============================================================================
+// Proxy Config
+// =============================================================================
+
+/// Proxy settings for outbound connections.
+///
+/// `url` selects the proxy by scheme: `http://host:port` tunnels with HTTP
+/// `CONNECT`, `socks5://host:port` goes through SOCKS5 with names resolved
+/// locally, and `socks5h://host:port` lets the proxy resolve them.
+///
+/// Without a `url`, the standard environment variables are honored by
+/// default: `HTTPS_PROXY` for `https://` and `wss://` targets, `HTTP_PROXY`
+/// for `http://` and `ws://`, `ALL_PROXY` for both, in upper or lower case.
+/// A variable naming an unsupported proxy (such as `https://`) is ignored
+/// with a warning.  Hosts listed in `no_proxy` (and, when reading the environment, in
+/// `NO_PROXY`) are always reached directly.
+#[derive(Debug, Clone)]
+pub struct ProxyConfig {
+    /// Proxy URL, possibly with `user:password@` credentials.
+    pub url: Option<String>,
+    /// User name for the proxy; overrides credentials in `url`.
+    pub username: Option<String>,
+    /// Password for the proxy.
+    pub password: Option<String>,
+    /// Hosts to reach directly: domain names (also matching their
+    /// subdomains), IP addresses, CIDR ranges, or `*` for all.
+    pub no_proxy: Vec<String>,
+    /// Fall back to the proxy environment variables when `url` is unset.
+    pub from_env: bool,
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            username: None,
+            password: None,
+            no_proxy: Vec::new(),
+            from_env: true,
+        }
+    }
+}
+
+impl ProxyConfig {
+    /// Connects through the proxy at `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: Some(url.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Connects directly, ignoring the environment.
+    pub fn direct() -> Self {
+        Self {
+            from_env: false,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the proxy credentials.
+    pub fn with_credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.username = Some(username.into());
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Adds a host, IP address or CIDR range to reach directly.
+    pub fn with_no_proxy(mut self, host: impl Into<String>) -> Self {
+        self.no_proxy.push(host.into());
+        self
+    }
+}
@@ -3,20 +3,28 @@
 use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
+use parking_lot::Mutex;
 use serde_json::Value;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot, watch};
 use tokio_util::sync::CancellationToken;
 
+use super::frame::Frame;
 use crate::error::TransportResult;
 
 /// Type-erased async function that performs an HTTP POST and returns JSON.
 ///
 /// The URL and any authentication (e.g. Bearer token) are captured when the
 /// closure is constructed by the transport layer.  Callers only supply the
-/// request body.
+/// request body and, optionally, a timeout replacing the client's default
+/// for this request.
 pub type PostJsonFn = Arc<
-    dyn Fn(Value) -> Pin<Box<dyn std::future::Future<Output = TransportResult<Value>> + Send>>
+    dyn Fn(
+            Value,
+            Option<Duration>,
+        ) -> Pin<Box<dyn std::future::Future<Output = TransportResult<Value>> + Send>>
         + Send
         + Sync,
 >;
@@ -28,7 +36,12 @@ pub struct ConnectionInfo {
     pub remote_addr: Option<String>,
     /// Connection protocol (ws, http, etc.).
     pub protocol: String,
-    /// Additional metadata.
+    /// Request path, for connections accepted by a server.
+    pub path: Option<String>,
+    /// Decoded query-string parameters of the request.
+    pub query: HashMap<String, String>,
+    /// Additional metadata (for server connections, the request headers
+    /// with lowercase names).
     pub metadata: HashMap<String, String>,
 }
 
@@ -38,6 +51,8 @@ impl ConnectionInfo {
         Self {
             remote_addr: None,
             protocol: protocol.into(),
+            path: None,
+            query: HashMap::new(),
             metadata: HashMap::new(),
         }
     }
@@ -48,6 +63,18 @@ impl ConnectionInfo {
         self
     }
 
+    /// Sets the request path.
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Sets the query-string parameters.
+    pub fn with_query(mut self, query: HashMap<String, String>) -> Self {
+        self.query = query;
+        self
+    }
+
     /// Adds metadata.
     pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.metadata.insert(key.into(), value.into());
@@ -103,8 +130,9 @@ impl Drop for ListenerHandle {
 pub enum ConnectionKind {
     /// WebSocket connection (outbound dial or inbound accept — identical after handshake).
     Ws {
-        /// Channel to the WS write loop; send serialised frames here.
-        message_tx: mpsc::Sender<Vec<u8>>,
+        /// Channel to the WS write loop.  Each [`Frame`] goes out as the
+        /// matching WebSocket frame; a close frame ends the connection.
+        message_tx: mpsc::Sender<Frame>,
     },
     /// HTTP outbound API client.
     ///
@@ -117,10 +145,11 @@ pub enum ConnectionKind {
     },
     /// HTTP inbound webhook server.
     ///
-    /// Events arrive via POST.  `message_tx` is an optional hook for future
-    /// SSE / response-channel delivery; it is not used for API calls.
+    /// Events arrive via POST and may be answered through the request's
+    /// [`ResponseSlot`].  Frames sent to `message_tx` are pushed to the bot's
+    /// Server-Sent Events subscribers, if any.
     HttpServer {
-        /// Outgoing message queue (reserved for future SSE / push mechanisms).
+        /// Outgoing message queue, streamed to SSE subscribers.
         message_tx: mpsc::Sender<Vec<u8>>,
     },
 }
@@ -135,22 +164,115 @@ impl std::fmt::Debug for ConnectionKind {
     }
 }
 
+// =============================================================================
+// ResponseSlot
+// =============================================================================
+
+/// Where to put the response to the request that delivered an event.
+///
+/// Request/response transports (the HTTP server) create one per request and
+/// hand it to [`ConnectionHandler::on_request`](super::ConnectionHandler::on_request);
+/// the adapter attaches it to the parsed event so handlers can answer in the
+/// response body.  The transport waits until a response is given, every clone
+/// of the slot is dropped, or its deadline passes.
+#[derive(Clone, Default)]
+pub struct ResponseSlot {
+    tx: Arc<Mutex<Option<oneshot::Sender<Vec<u8>>>>>,
+}
+
+impl ResponseSlot {
+    /// Creates a slot and the receiver the transport waits on.
+    pub fn channel() -> (Self, oneshot::Receiver<Vec<u8>>) {
+        let (tx, rx) = oneshot::channel();
+        let slot = Self {
+            tx: Arc::new(Mutex::new(Some(tx))),
+        };
+        (slot, rx)
+    }
+
+    /// Sets the response body (JSON).
+    ///
+    /// Returns `false` if a response was already given or the transport
+    /// stopped waiting.
+    pub fn respond(&self, body: Vec<u8>) -> bool {
+        match self.tx.lock().take() {
+            Some(tx) => tx.send(body).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Returns `true` while a response can still be given.
+    pub fn is_open(&self) -> bool {
+        self.tx.lock().as_ref().is_some_and(|tx| !tx.is_closed())
+    }
+}
+
+impl std::fmt::Debug for ResponseSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseSlot")
+            .field("open", &self.is_open())
+            .finish()
+    }
+}
+
+// =============================================================================
+// ConnectionStatus
+// =============================================================================
+
+/// Lifecycle state of a client connection that reconnects by itself.
+///
+/// Published through [`ConnectionHandle::watch_status`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    /// Connected and exchanging frames.
+    Connected,
+    /// The connection was lost; waiting `delay` before reconnect attempt
+    /// number `attempt` (starting at 1).
+    Backoff {
+        /// The upcoming attempt.
+        attempt: u32,
+        /// Time until the attempt, jitter included.
+        delay: Duration,
+    },
+    /// Reconnect attempt number `attempt` is in progress.
+    Reconnecting {
+        /// The running attempt.
+        attempt: u32,
+    },
+    /// Closed for good: reconnecting is disabled, the retry limit was
+    /// reached, or the connection was shut down.
+    GaveUp,
+}
+
 // =============================================================================
 // ConnectionHandle
 // =============================================================================
 
+static NEXT_CONN_ID: AtomicU64 = AtomicU64::new(1);
+
 /// Handle to an active bot connection.
 ///
 /// The `kind` field carries all transport-specific data; the handle itself
 /// only contains fields common to every connection.
+///
+/// A bot may be reachable over several connections at once (e.g. a reverse
+/// WebSocket and an HTTP API client), so `id` alone does not identify a
+/// connection; [`conn_id`](Self::conn_id) does.  Clones share the same
+/// `conn_id`.
 #[derive(Clone, Debug)]
 pub struct ConnectionHandle {
-    /// Unique identifier for this connection (bot ID).
+    /// ID of the bot this connection belongs to.
     pub id: String,
     /// Transport-specific data for this connection.
     pub kind: ConnectionKind,
+    /// Process-unique connection number.
+    conn_id: u64,
     /// Cancellation token for graceful shutdown.
     shutdown_token: CancellationToken,
+    /// Reconnect state, for transports that reconnect.
+    status: Option<watch::Receiver<ConnectionStatus>>,
+    /// Default API call timeout configured for the connection.
+    call_timeout: Option<Duration>,
 }
 
 impl ConnectionHandle {
@@ -164,14 +286,10 @@ impl ConnectionHandle {
     /// after the handshake, their behavior is identical.
     pub fn new_ws(
         id: impl Into<String>,
-        message_tx: mpsc::Sender<Vec<u8>>,
+        message_tx: mpsc::Sender<Frame>,
         shutdown_token: CancellationToken,
     ) -> Self {
-        Self {
-            id: id.into(),
-            kind: ConnectionKind::Ws { message_tx },
-            shutdown_token,
-        }
+        Self::with_kind(id.into(), ConnectionKind::Ws { message_tx }, shutdown_token)
     }
 
     /// Creates a handle for an HTTP outbound API client connection.
@@ -183,11 +301,11 @@ impl ConnectionHandle {
         post_json: PostJsonFn,
         shutdown_token: CancellationToken,
     ) -> Self {
-        Self {
-            id: id.into(),
-            kind: ConnectionKind::HttpClient { post_json },
+        Self::with_kind(
+            id.into(),
+            ConnectionKind::HttpClient { post_json },
             shutdown_token,
-        }
+        )
     }
 
     /// Creates a handle for an HTTP inbound webhook server connection.
@@ -196,13 +314,63 @@ impl ConnectionHandle {
         message_tx: mpsc::Sender<Vec<u8>>,
         shutdown_token: CancellationToken,
     ) -> Self {
+        Self::with_kind(
+            id.into(),
+            ConnectionKind::HttpServer { message_tx },
+            shutdown_token,
+        )
+    }
+
+    fn with_kind(id: String, kind: ConnectionKind, shutdown_token: CancellationToken) -> Self {
         Self {
-            id: id.into(),
-            kind: ConnectionKind::HttpServer { message_tx },
+            id,
+            kind,
+            conn_id: NEXT_CONN_ID.fetch_add(1, Ordering::Relaxed),
             shutdown_token,
+            status: None,
+            call_timeout: None,
         }
     }
 
+    /// Publishes the connection's reconnect state through `status`.
+    ///
+    /// Used by transports that reconnect by themselves.
+    pub fn with_status(mut self, status: watch::Receiver<ConnectionStatus>) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Sets how long API calls over this connection wait for a response
+    /// unless a call says otherwise.
+    pub fn with_call_timeout(mut self, timeout: Duration) -> Self {
+        self.call_timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the configured default API call timeout, or `None` to leave
+    /// it to the adapter.
+    pub fn call_timeout(&self) -> Option<Duration> {
+        self.call_timeout
+    }
+
+    /// Returns the current reconnect state, or `None` if the transport does
+    /// not reconnect.
+    pub fn status(&self) -> Option<ConnectionStatus> {
+        self.status.as_ref().map(|rx| rx.borrow().clone())
+    }
+
+    /// Returns a receiver notified on every reconnect state change, or
+    /// `None` if the transport does not reconnect.
+    pub fn watch_status(&self) -> Option<watch::Receiver<ConnectionStatus>> {
+        self.status.clone()
+    }
+
+    /// Returns the number identifying this connection among all connections
+    /// of the process.
+    pub fn conn_id(&self) -> u64 {
+        self.conn_id
+    }
+
     /// Signals the transport loop to shut down this connection.
     pub fn close(self) {
         self.shutdown_token.cancel();
@@ -0,0 +1,111 @@
+//! Typed frames for message-based connections.
+//!
+//! WebSocket (and loopback) connections exchange [`Frame`]s rather than raw
+//! bytes, so the frame type survives the trip in both directions: the
+//! adapter decides whether it sends text or binary, and sees which one the
+//! peer sent.
+
+/// One message on a WebSocket-style connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+    /// UTF-8 text, e.g. JSON.
+    Text(String),
+    /// Raw bytes, e.g. compressed, msgpack or protobuf payloads.
+    Binary(Vec<u8>),
+    /// A close frame.
+    ///
+    /// Sending one closes the connection; receiving one means the peer is
+    /// closing it.
+    Close {
+        /// Status code (RFC 6455 §7.4).
+        code: u16,
+        /// Human-readable reason, possibly empty.
+        reason: String,
+    },
+}
+
+impl Frame {
+    /// Normal closure.
+    pub const CLOSE_NORMAL: u16 = 1000;
+    /// The peer closed without giving a status code.
+    pub const CLOSE_NO_STATUS: u16 = 1005;
+
+    /// Creates a close frame.
+    pub fn close(code: u16, reason: impl Into<String>) -> Self {
+        Self::Close {
+            code,
+            reason: reason.into(),
+        }
+    }
+
+    /// Wraps a payload of unknown type: text if it is valid UTF-8, binary
+    /// otherwise.
+    pub fn from_payload(data: Vec<u8>) -> Self {
+        match String::from_utf8(data) {
+            Ok(text) => Self::Text(text),
+            Err(e) => Self::Binary(e.into_bytes()),
+        }
+    }
+
+    /// Returns the payload of a text or binary frame; empty for close frames.
+    pub fn payload(&self) -> &[u8] {
+        match self {
+            Self::Text(text) => text.as_bytes(),
+            Self::Binary(data) => data,
+            Self::Close { .. } => &[],
+        }
+    }
+
+    /// Returns `true` for binary frames.
+    pub fn is_binary(&self) -> bool {
+        matches!(self, Self::Binary(_))
+    }
+
+    /// Returns `true` for close frames.
+    pub fn is_close(&self) -> bool {
+        matches!(self, Self::Close { .. })
+    }
+}
+
+impl From<String> for Frame {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&str> for Frame {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_owned())
+    }
+}
+
+impl From<Vec<u8>> for Frame {
+    fn from(data: Vec<u8>) -> Self {
+        Self::Binary(data)
+    }
+}
+
+impl From<&[u8]> for Frame {
+    fn from(data: &[u8]) -> Self {
+        Self::Binary(data.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_from_payload() {
+        assert_eq!(
+            Frame::from_payload(b"{}".to_vec()),
+            Frame::Text("{}".into())
+        );
+        assert_eq!(
+            Frame::from_payload(vec![0x80, 0x01]),
+            Frame::Binary(vec![0x80, 0x01])
+        );
+        assert_eq!(Frame::from("hi").payload(), b"hi");
+        assert!(Frame::close(Frame::CLOSE_NORMAL, "").payload().is_empty());
+    }
+}
@@ -0,0 +1,96 @@
+//! Test-side endpoint of an in-process loopback connection.
+//!
+//! The [`Loopback`](super::Loopback) capability connects a
+//! [`ConnectionHandler`](super::ConnectionHandler) to a peer living in the same
+//! process.  The handler side is an ordinary WebSocket-style
+//! [`ConnectionHandle`](super::ConnectionHandle); the other side is a
+//! [`LoopbackPeer`], which plays the protocol implementation: it pushes frames
+//! to the bot and reads the frames the bot sent.
+
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use super::frame::Frame;
+use crate::error::{TransportError, TransportResult};
+
+/// The peer end of a loopback connection.
+///
+/// Dropping the peer (or calling [`close`](Self::close)) closes the
+/// connection, which disconnects the bot.
+#[derive(Debug)]
+pub struct LoopbackPeer {
+    /// Frames for the handler.
+    inbound_tx: mpsc::Sender<Frame>,
+    /// Frames the bot sent.
+    outbound_rx: mpsc::Receiver<Frame>,
+    shutdown_token: CancellationToken,
+}
+
+impl LoopbackPeer {
+    /// Creates a peer from its halves of the connection.
+    ///
+    /// Called by loopback transport implementations.
+    pub fn new(
+        inbound_tx: mpsc::Sender<Frame>,
+        outbound_rx: mpsc::Receiver<Frame>,
+        shutdown_token: CancellationToken,
+    ) -> Self {
+        Self {
+            inbound_tx,
+            outbound_rx,
+            shutdown_token,
+        }
+    }
+
+    /// Delivers a frame to the bot, as if it had arrived over the network.
+    ///
+    /// Strings are sent as text frames and byte vectors as binary frames.
+    pub async fn send(&self, frame: impl Into<Frame>) -> TransportResult<()> {
+        if self.shutdown_token.is_cancelled() {
+            return Err(Self::closed());
+        }
+        self.inbound_tx
+            .send(frame.into())
+            .await
+            .map_err(|_| Self::closed())
+    }
+
+    /// Waits for the next frame sent by the bot.
+    ///
+    /// Returns `None` once the connection is closed and every frame sent
+    /// before that has been read.
+    pub async fn recv(&mut self) -> Option<Frame> {
+        tokio::select! {
+            biased;
+            frame = self.outbound_rx.recv() => frame,
+            _ = self.shutdown_token.cancelled() => self.outbound_rx.try_recv().ok(),
+        }
+    }
+
+    /// Returns the next frame sent by the bot, if one is already queued.
+    pub fn try_recv(&mut self) -> Option<Frame> {
+        self.outbound_rx.try_recv().ok()
+    }
+
+    /// Returns `true` once either side has closed the connection.
+    pub fn is_closed(&self) -> bool {
+        self.shutdown_token.is_cancelled() || self.inbound_tx.is_closed()
+    }
+
+    /// Closes the connection.
+    pub fn close(self) {
+        self.shutdown_token.cancel();
+    }
+
+    fn closed() -> TransportError {
+        TransportError::ConnectionClosed {
+            reason: "loopback connection closed".into(),
+        }
+    }
+}
+
+impl Drop for LoopbackPeer {
+    fn drop(&mut self) {
+        self.shutdown_token.cancel();
+    }
+}
@@ -6,14 +6,22 @@
 pub mod capability;
 pub mod config;
 pub mod connection;
+pub mod frame;
+pub mod loopback;
 
 // Re-export commonly used types
 pub use capability::{
-    ConnectionHandler, HTTP_LISTEN_REGISTRY, HTTP_START_CLIENT_REGISTRY, HttpListenFn,
-    HttpStartClientFn, TransportContext, WS_CONNECT_REGISTRY, WS_LISTEN_REGISTRY, WsConnectFn,
-    WsListenFn,
+    CAPABILITY_REGISTRY, Capability, CapabilityProvider, ConnectionHandler, HttpClient,
+    HttpListenFn, HttpServer, HttpStartClientFn, Loopback, LoopbackConnectFn, TransportContext,
+    WsClient, WsConnectFn, WsListenFn, WsServer, capability_providers,
+};
+pub use config::{
+    ClientTlsConfig, HttpClientConfig, HttpServerConfig, ProxyConfig, SendOverflow,
+    ServerTlsConfig, WsClientConfig, WsServerConfig,
 };
-pub use config::{HttpClientConfig, WsClientConfig};
 pub use connection::{
-    ConnectionHandle, ConnectionInfo, ConnectionKind, ListenerHandle, PostJsonFn,
+    ConnectionHandle, ConnectionInfo, ConnectionKind, ConnectionStatus, ListenerHandle, PostJsonFn,
+    ResponseSlot,
 };
+pub use frame::Frame;
+pub use loopback::LoopbackPeer;
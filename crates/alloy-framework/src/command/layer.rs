@@ -11,7 +11,7 @@ use tower_layer::{Identity, Stack};
 
 use crate::context::AlloyContext;
 use crate::error::EventSkipped;
-use crate::handler::{FromCtxFn, HandlerResponse, HandlerService, ServiceBuilderExt};
+use crate::handler::{FromCtxFn, HandlerResponse, HandlerService, Prioritized, ServiceBuilderExt};
 use alloy_core::EventType;
 
 use super::CURRENT_REGISTRY;
@@ -127,6 +127,17 @@ where
         ServiceBuilder::new().layer(self)
     }
 
+    /// Convert to a [`ServiceBuilder`] tagged with a dispatch priority.
+    ///
+    /// Shorthand for `.build().priority(priority)`; see
+    /// [`ServiceBuilderExt::priority`].
+    pub fn priority(
+        self,
+        priority: i32,
+    ) -> Prioritized<ServiceBuilder<Stack<CommandLayer<T>, Identity>>> {
+        self.build().priority(priority)
+    }
+
     /// Wrap a handler function with this command layer.
     ///
     /// This is equivalent to `.build().handler(handler)` but more concise.
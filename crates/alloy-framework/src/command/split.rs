@@ -60,6 +60,8 @@ pub fn shell_split(input: &str) -> Vec<String> {
 /// - **`Image`** and **`At`** segments are replaced by unique placeholder
 ///   tokens (`\x00IMG_0`, `\x00AT_0`, etc.) that each become a single
 ///   argument.
+/// - **`Link`** segments become their URL as a single argument.
+/// - All other segments (replies, faces, media, …) are skipped.
 ///
 /// Returns the argument list together with a [`HandleRegistry`] that maps
 /// placeholders back to their original values.
@@ -91,6 +93,8 @@ pub fn rich_text_shell_split(segments: &[RichTextSegment]) -> (Vec<String>, Hand
                 registry.ats.insert(placeholder.clone(), user_id.clone());
                 args.push(placeholder);
             }
+            RichTextSegment::Link { url, .. } => args.push(url.clone()),
+            _ => {}
         }
     }
 
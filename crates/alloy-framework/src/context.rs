@@ -14,19 +14,23 @@
 //! - [`AlloyContext`] — the full context handed to handlers, combining an
 //!   `Arc<BaseContext>` with a `PluginContext`.  Calling
 //!   [`stop_propagation`](AlloyContext::stop_propagation) on any plugin's
-//!   `AlloyContext` writes through to the shared base, stopping the chain
-//!   for all subsequent plugins. Each plugin's state is completely isolated.
+//!   `AlloyContext` halts that plugin's own handler chain and writes through
+//!   to the shared base, so no lower-priority plugin receives the event.
+//!   Each plugin's state is completely isolated.
 
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use serde_json::Value;
+use tokio::sync::Notify;
 
-use alloy_core::{BoxedBot, BoxedEvent};
+use alloy_core::{BotRegistry, BoxedBot, BoxedEvent};
 
-use crate::error::{ExtractError, ExtractResult};
+use crate::conversation::{ConversationHub, ConversationSession, SessionKey};
+use crate::error::{ConversationError, ExtractError, ExtractResult};
 
 /// Type alias for the heterogeneous service map values stored in the global registry.
 ///
@@ -44,8 +48,8 @@ pub type ServiceArc = Arc<dyn Any + Send + Sync>;
 /// One `BaseContext` is created per incoming event and wrapped in an `Arc`
 /// that is cloned into every [`AlloyContext`] for that event.  This means:
 ///
-/// - Stopping propagation in one plugin is immediately visible to the dispatch
-///   loop and to all subsequent plugins.
+/// - Stopping propagation in one plugin is visible to the dispatch loop, which
+///   skips every lower-priority tier.
 /// - The event and bot are accessed without copying.
 /// - Each plugin has its own isolated state through [`PluginContext`].
 pub struct BaseContext {
@@ -53,15 +57,26 @@ pub struct BaseContext {
     bot: BoxedBot,
     /// Cleared by any handler that calls [`AlloyContext::stop_propagation`].
     is_propagating: AtomicBool,
+    /// Open conversations, shared with the dispatcher.
+    conversations: Arc<ConversationHub>,
+    /// All connected bots, across adapters.
+    bots: Arc<BotRegistry>,
 }
 
 impl BaseContext {
     /// Creates a new shared event context.
-    pub(crate) fn new(event: BoxedEvent, bot: BoxedBot) -> Self {
+    pub(crate) fn new(
+        event: BoxedEvent,
+        bot: BoxedBot,
+        conversations: Arc<ConversationHub>,
+        bots: Arc<BotRegistry>,
+    ) -> Self {
         Self {
             event,
             bot,
             is_propagating: AtomicBool::new(true),
+            conversations,
+            bots,
         }
     }
 
@@ -111,6 +126,11 @@ pub struct PluginContext {
     /// Per-plugin isolated state storage for this event dispatch.
     /// Each plugin gets its own independent state that is not shared.
     state: Mutex<HashMap<TypeId, Box<dyn Any + Send + Sync>>>,
+    /// Set when a handler of *this* plugin stops propagation.
+    halted: AtomicBool,
+    /// Notified when a handler of this plugin starts waiting in a
+    /// conversation, releasing the plugin's priority tier.
+    suspended: Arc<Notify>,
 }
 
 impl PluginContext {
@@ -125,6 +145,8 @@ impl PluginContext {
             config,
             services,
             state: Mutex::new(HashMap::new()),
+            halted: AtomicBool::new(false),
+            suspended: Arc::new(Notify::new()),
         }
     }
 }
@@ -141,7 +163,8 @@ impl PluginContext {
 /// - **Isolated state**: Via `set_state`, `get_state`, etc. — each plugin's
 ///   state is completely isolated and not visible to other plugins.
 /// - **Shared propagation**: Calling [`stop_propagation`](Self::stop_propagation)
-///   prevents subsequent plugins from running.
+///   ends this plugin's handler chain and prevents lower-priority plugins
+///   from running.
 /// - **Shared event/bot**: Access to the event and bot without copying.
 ///
 /// # Example
@@ -150,7 +173,7 @@ impl PluginContext {
 /// async fn handle(ctx: Arc<AlloyContext>) {
 ///     println!("event: {:?}", ctx.event());
 ///     ctx.set_state("my_data".to_string());  // isolated to this plugin
-///     ctx.stop_propagation();                // no further plugins will run
+///     ctx.stop_propagation();                // lower-priority plugins will not run
 ///     ctx.bot().send(...).await.ok();
 /// }
 /// ```
@@ -183,6 +206,12 @@ impl AlloyContext {
         self.base.bot.clone()
     }
 
+    /// Returns the registry of every connected bot, not just the one that
+    /// received this event.
+    pub fn bot_registry(&self) -> &Arc<BotRegistry> {
+        &self.base.bots
+    }
+
     /// Looks up a service by its trait-object type.
     ///
     /// Returns `None` if the service of type `T` was not declared by this
@@ -201,19 +230,61 @@ impl AlloyContext {
             .ok_or(ExtractError::ServiceNotFound(std::any::type_name::<T>()))
     }
 
-    /// Stops propagation of this event to subsequent plugins.
+    /// Stops propagation of this event to lower-priority handlers and plugins.
     ///
-    /// Writes through to the shared base context; the dispatch loop checks
-    /// `is_propagating()` before handing the event to each next plugin.
+    /// The current plugin's remaining handlers are skipped unless they share
+    /// the calling handler's priority.  The flag is also written through to
+    /// the shared base context; the dispatch loop checks it after each
+    /// priority tier, so plugins sharing the current plugin's priority still
+    /// finish but lower tiers never start.
     pub fn stop_propagation(&self) {
+        self.plugin.halted.store(true, Ordering::SeqCst);
         self.base.stop_propagation();
     }
 
+    /// Returns `true` once a handler of the current plugin stopped propagation.
+    pub(crate) fn is_halted(&self) -> bool {
+        self.plugin.halted.load(Ordering::SeqCst)
+    }
+
     /// Returns `true` if the event is still propagating.
     pub fn is_propagating(&self) -> bool {
         self.base.is_propagating()
     }
 
+    /// Suspends the handler until the next message matching `session` arrives.
+    ///
+    /// The session is resolved relative to the current event; see
+    /// [`conversation`](crate::conversation) for matching rules, cancellation
+    /// words, and the per-user limit.
+    ///
+    /// Waiting releases the plugin's priority tier: lower-priority plugins
+    /// receive the current event right away, so
+    /// [`stop_propagation`](Self::stop_propagation) only holds them back if
+    /// it is called before the wait.
+    pub async fn wait_for(
+        &self,
+        session: ConversationSession,
+        timeout: Duration,
+    ) -> Result<BoxedEvent, ConversationError> {
+        let origin = SessionKey::new(self.event(), self.bot());
+        self.base
+            .conversations
+            .wait(origin, session, timeout, &self.plugin.suspended)
+            .await
+    }
+
+    pub(crate) fn conversations(&self) -> &Arc<ConversationHub> {
+        &self.base.conversations
+    }
+
+    /// Returns the signal given when a handler of this plugin starts waiting
+    /// in a conversation; the dispatch loop stops waiting for the plugin
+    /// once it fires.
+    pub(crate) fn suspended(&self) -> &Arc<Notify> {
+        &self.plugin.suspended
+    }
+
     /// Stores a value in this plugin's isolated state map.
     ///
     /// Each plugin has its own isolated state that is not visible to other plugins.
@@ -0,0 +1,429 @@
+//! Multi-step conversations: suspend a handler until the user replies.
+//!
+//! A handler normally lives for exactly one dispatch.  The conversation system
+//! lets it ask a follow-up question and await the next matching message:
+//!
+//! ```rust,ignore
+//! async fn signup(ctx: Arc<AlloyContext>, prompt: Prompt) -> anyhow::Result<String> {
+//!     let name = prompt.ask("What's your name?").await?;
+//!     let age = ctx
+//!         .wait_for(ConversationSession::current(), Duration::from_secs(30))
+//!         .await?;
+//!     Ok(format!("{} ({})", name.get_plain_text(), age.get_plain_text()))
+//! }
+//! ```
+//!
+//! # Delivery
+//!
+//! Every incoming **message** event is first offered to the open
+//! conversations, oldest first.  If one of them matches, the event is handed
+//! to the waiting future and is **not** dispatched to plugins.
+//!
+//! | [`ConversationSession`] | Matches messages from … |
+//! |-------------|-------------------------|
+//! | [`current`](ConversationSession::current) | the same user in the same chat |
+//! | [`user`](ConversationSession::user) | the same user in any chat |
+//! | [`scene`](ConversationSession::scene) | anyone in the same chat |
+//!
+//! # Limits
+//!
+//! Configured through [`ConversationConfig`]:
+//!
+//! - **Timeouts** — waits end with [`ConversationError::Timeout`].
+//! - **Cancellation words** — a reply equal to one of them (case-insensitive,
+//!   surrounding whitespace ignored) ends the wait with
+//!   [`ConversationError::Cancelled`].
+//! - **Per-user cap** — opening more than `max_per_user` concurrent
+//!   conversations fails with [`ConversationError::TooManyConversations`].
+//!
+//! # Dispatch order
+//!
+//! Plugins receive an event tier by tier, in descending priority.  A handler
+//! that starts waiting releases its plugin's tier: lower-priority plugins
+//! receive the *original* event at once instead of after the conversation
+//! ends.  Call [`stop_propagation`](AlloyContext::stop_propagation) *before*
+//! waiting to keep the event from them; calling it afterwards no longer has
+//! an effect on that event.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Notify, oneshot};
+use tracing::debug;
+
+use alloy_core::{BoxedBot, BoxedEvent, EventType, SceneKind};
+
+use crate::context::AlloyContext;
+use crate::error::{ConversationError, ExtractResult};
+use crate::extractor::FromContext;
+
+// =============================================================================
+// Configuration
+// =============================================================================
+
+/// Conversation limits, read from the `conversation` section of the config.
+///
+/// ```toml
+/// [conversation]
+/// max_per_user = 1
+/// default_timeout_secs = 60
+/// cancel_words = ["cancel", "取消"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConversationConfig {
+    /// Maximum number of concurrently open conversations per user and bot.
+    pub max_per_user: usize,
+    /// Timeout used by [`Prompt::wait`] and [`Prompt::ask`].
+    pub default_timeout_secs: u64,
+    /// Replies that abort the conversation instead of answering it.
+    pub cancel_words: Vec<String>,
+}
+
+impl Default for ConversationConfig {
+    fn default() -> Self {
+        Self {
+            max_per_user: 1,
+            default_timeout_secs: 60,
+            cancel_words: vec!["cancel".to_string()],
+        }
+    }
+}
+
+impl ConversationConfig {
+    /// Returns the default timeout as a [`Duration`].
+    pub fn default_timeout(&self) -> Duration {
+        Duration::from_secs(self.default_timeout_secs)
+    }
+
+    fn is_cancel_word(&self, text: &str) -> bool {
+        let text = text.trim();
+        self.cancel_words
+            .iter()
+            .any(|w| w.trim().eq_ignore_ascii_case(text))
+    }
+}
+
+// =============================================================================
+// Session scope
+// =============================================================================
+
+/// Selects which follow-up messages a waiting handler accepts, relative to
+/// the event that started the conversation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversationSession {
+    /// Same user, same chat.
+    Current,
+    /// Same user, any chat.
+    User,
+    /// Any user, same chat.
+    Scene,
+}
+
+impl ConversationSession {
+    /// Same user, same chat — the usual choice for question/answer flows.
+    pub fn current() -> Self {
+        Self::Current
+    }
+
+    /// Same user, any chat.
+    pub fn user() -> Self {
+        Self::User
+    }
+
+    /// Any user, same chat.
+    pub fn scene() -> Self {
+        Self::Scene
+    }
+}
+
+/// Who sent an event and where, as far as the conversation system can tell.
+///
+/// Built from the event's core [`Session`](alloy_core::Session) when the
+/// adapter provides one; otherwise only the user is known and every such
+/// event counts as the same scene.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SessionKey {
+    platform: &'static str,
+    bot_id: String,
+    user_id: Option<String>,
+    scene: Option<(SceneKind, String)>,
+}
+
+impl SessionKey {
+    pub(crate) fn new(event: &BoxedEvent, bot: &BoxedBot) -> Self {
+        match event.session() {
+            Some(session) => Self {
+                platform: session.platform,
+                bot_id: session.bot_id,
+                user_id: session.user_id,
+                scene: Some((session.scene, session.scene_id)),
+            },
+            None => Self {
+                platform: event.platform(),
+                bot_id: bot.id().to_string(),
+                user_id: event.get_user_id(),
+                scene: None,
+            },
+        }
+    }
+
+    fn same_user(&self, other: &Self) -> bool {
+        self.user_id.is_some() && self.user_id == other.user_id
+    }
+
+    fn same_scene(&self, other: &Self) -> bool {
+        self.scene == other.scene
+    }
+
+    fn matches(&self, scope: ConversationSession, other: &Self) -> bool {
+        if self.platform != other.platform || self.bot_id != other.bot_id {
+            return false;
+        }
+        match scope {
+            ConversationSession::Current => self.same_user(other) && self.same_scene(other),
+            ConversationSession::User => self.same_user(other),
+            ConversationSession::Scene => self.same_scene(other),
+        }
+    }
+}
+
+// =============================================================================
+// ConversationHub
+// =============================================================================
+
+type ReplySender = oneshot::Sender<Result<BoxedEvent, ConversationError>>;
+
+struct Waiter {
+    id: u64,
+    scope: ConversationSession,
+    origin: SessionKey,
+    reply_tx: ReplySender,
+}
+
+/// Registry of open conversations, owned by the
+/// [`PluginManager`](crate::manager::PluginManager).
+pub(crate) struct ConversationHub {
+    config: ConversationConfig,
+    next_id: AtomicU64,
+    /// Open conversations in the order they were opened.
+    waiters: Mutex<Vec<Waiter>>,
+}
+
+impl ConversationHub {
+    pub(crate) fn new(config: ConversationConfig) -> Self {
+        Self {
+            config,
+            next_id: AtomicU64::new(0),
+            waiters: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn config(&self) -> &ConversationConfig {
+        &self.config
+    }
+
+    /// Offers `event` to the open conversations.
+    ///
+    /// Returns `true` when a conversation consumed the event, in which case
+    /// it must not be dispatched to plugins.
+    pub(crate) fn try_deliver(&self, event: &BoxedEvent, bot: &BoxedBot) -> bool {
+        if event.event_type() != EventType::Message {
+            return false;
+        }
+
+        let mut waiters = self.waiters.lock();
+        if waiters.is_empty() {
+            return false;
+        }
+
+        let key = SessionKey::new(event, bot);
+        let Some(pos) = waiters
+            .iter()
+            .position(|w| !w.reply_tx.is_closed() && w.origin.matches(w.scope, &key))
+        else {
+            return false;
+        };
+        let waiter = waiters.remove(pos);
+        drop(waiters);
+
+        let reply = if self.config.is_cancel_word(&event.get_plain_text()) {
+            Err(ConversationError::Cancelled)
+        } else {
+            Ok(event.clone())
+        };
+        debug!(
+            conversation = waiter.id,
+            cancelled = reply.is_err(),
+            "Delivering message to open conversation"
+        );
+        waiter.reply_tx.send(reply).is_ok()
+    }
+
+    /// Opens a conversation and waits for the first matching message.
+    ///
+    /// `suspended` is notified once the conversation is open, so that the
+    /// dispatch loop can move on to the next tier.
+    pub(crate) async fn wait(
+        &self,
+        origin: SessionKey,
+        scope: ConversationSession,
+        timeout: Duration,
+        suspended: &Notify,
+    ) -> Result<BoxedEvent, ConversationError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        let id = {
+            let mut waiters = self.waiters.lock();
+            let limit = self.config.max_per_user;
+            let open = waiters
+                .iter()
+                .filter(|w| w.origin.matches(ConversationSession::User, &origin))
+                .count();
+            if open >= limit {
+                return Err(ConversationError::TooManyConversations { limit });
+            }
+            let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+            waiters.push(Waiter {
+                id,
+                scope,
+                origin,
+                reply_tx,
+            });
+            id
+        };
+
+        // Removes the waiter on timeout or when the handler future is dropped.
+        let _guard = WaiterGuard { hub: self, id };
+        suspended.notify_one();
+
+        match tokio::time::timeout(timeout, reply_rx).await {
+            Ok(Ok(reply)) => reply,
+            Ok(Err(_)) => Err(ConversationError::Closed),
+            Err(_) => Err(ConversationError::Timeout),
+        }
+    }
+
+    fn remove(&self, id: u64) {
+        self.waiters.lock().retain(|w| w.id != id);
+    }
+}
+
+struct WaiterGuard<'a> {
+    hub: &'a ConversationHub,
+    id: u64,
+}
+
+impl Drop for WaiterGuard<'_> {
+    fn drop(&mut self) {
+        self.hub.remove(self.id);
+    }
+}
+
+// =============================================================================
+// Prompt extractor
+// =============================================================================
+
+/// Extractor for asking follow-up questions inside a handler.
+///
+/// Bound to the event that triggered the handler; every wait uses
+/// [`ConversationSession::current`] and the configured default timeout unless stated
+/// otherwise.
+///
+/// ```rust,ignore
+/// async fn report(prompt: Prompt) -> anyhow::Result<String> {
+///     let what = prompt.ask("What happened?").await?;
+///     let when = prompt.ask("When?").await?;
+///     Ok(format!("Filed: {} at {}", what.get_plain_text(), when.get_plain_text()))
+/// }
+/// ```
+pub struct Prompt {
+    hub: Arc<ConversationHub>,
+    origin: SessionKey,
+    event: BoxedEvent,
+    bot: BoxedBot,
+    suspended: Arc<Notify>,
+}
+
+impl Prompt {
+    /// Sends `question` as a reply and waits for the answer.
+    pub async fn ask(&self, question: &str) -> Result<BoxedEvent, ConversationError> {
+        self.bot
+            .send(self.event.as_ref(), question)
+            .await
+            .map_err(|e| ConversationError::SendFailed(e.to_string()))?;
+        self.wait().await
+    }
+
+    /// Waits for the next message in the current session.
+    pub async fn wait(&self) -> Result<BoxedEvent, ConversationError> {
+        self.wait_timeout(self.hub.config().default_timeout()).await
+    }
+
+    /// Waits for the next message in the current session, up to `timeout`.
+    pub async fn wait_timeout(&self, timeout: Duration) -> Result<BoxedEvent, ConversationError> {
+        self.wait_in(ConversationSession::Current, timeout).await
+    }
+
+    /// Waits for the next message matching `scope`, up to `timeout`.
+    pub async fn wait_in(
+        &self,
+        scope: ConversationSession,
+        timeout: Duration,
+    ) -> Result<BoxedEvent, ConversationError> {
+        self.hub
+            .wait(self.origin.clone(), scope, timeout, &self.suspended)
+            .await
+    }
+}
+
+#[async_trait]
+impl FromContext for Prompt {
+    async fn from_context(ctx: &AlloyContext) -> ExtractResult<Self> {
+        Ok(Prompt {
+            hub: ctx.conversations().clone(),
+            origin: SessionKey::new(ctx.event(), ctx.bot()),
+            event: ctx.event().clone(),
+            bot: ctx.bot_arc(),
+            suspended: ctx.suspended().clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_words_ignore_case_and_whitespace() {
+        let config = ConversationConfig {
+            cancel_words: vec!["Cancel".into(), "取消".into()],
+            ..Default::default()
+        };
+        assert!(config.is_cancel_word("  cancel "));
+        assert!(config.is_cancel_word("取消"));
+        assert!(!config.is_cancel_word("cancel please"));
+    }
+
+    #[test]
+    fn test_session_key_scopes() {
+        let key = |user: &str, group: Option<&str>| SessionKey {
+            platform: "test",
+            bot_id: "bot".into(),
+            user_id: Some(user.into()),
+            scene: group.map(|id| (SceneKind::Group, id.into())),
+        };
+        let origin = key("alice", Some("g1"));
+
+        assert!(origin.matches(ConversationSession::Current, &key("alice", Some("g1"))));
+        assert!(!origin.matches(ConversationSession::Current, &key("alice", Some("g2"))));
+        assert!(!origin.matches(ConversationSession::Current, &key("bob", Some("g1"))));
+
+        assert!(origin.matches(ConversationSession::User, &key("alice", None)));
+        assert!(origin.matches(ConversationSession::Scene, &key("bob", Some("g1"))));
+        assert!(!origin.matches(ConversationSession::Scene, &key("bob", None)));
+    }
+}
@@ -53,3 +53,32 @@ impl ExtractError {
 
 /// Result type for extraction operations.
 pub type ExtractResult<T> = Result<T, ExtractError>;
+
+/// Errors that end a conversation started with
+/// [`AlloyContext::wait_for`](crate::context::AlloyContext::wait_for) or a
+/// [`Prompt`](crate::conversation::Prompt).
+#[derive(Debug, Clone, Error)]
+pub enum ConversationError {
+    /// No matching message arrived in time.
+    #[error("timed out waiting for a reply")]
+    Timeout,
+
+    /// The user answered with a cancellation word.
+    #[error("conversation cancelled by the user")]
+    Cancelled,
+
+    /// The user already has the maximum number of open conversations.
+    #[error("too many open conversations for this user (limit {limit})")]
+    TooManyConversations {
+        /// Configured per-user limit.
+        limit: usize,
+    },
+
+    /// The question could not be sent.
+    #[error("failed to send prompt: {0}")]
+    SendFailed(String),
+
+    /// The conversation registry went away while waiting.
+    #[error("conversation closed")]
+    Closed,
+}
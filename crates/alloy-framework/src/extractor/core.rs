@@ -1,8 +1,10 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 
 use crate::context::AlloyContext;
 use crate::error::ExtractResult;
-use alloy_core::{BoxedBot, BoxedEvent};
+use alloy_core::{BotRegistry, BoxedBot, BoxedEvent};
 
 /// A trait for types that can be extracted from an [`AlloyContext`].
 ///
@@ -79,3 +81,21 @@ impl FromContext for BoxedBot {
         Ok(ctx.bot_arc())
     }
 }
+
+/// Implementation for extracting the runtime-wide [`BotRegistry`].
+///
+/// Lets a handler reach bots other than the one that received the event:
+///
+/// ```rust,ignore
+/// async fn relay(event: Event<MessageEvent>, bots: Arc<BotRegistry>) {
+///     if let Some(other) = bots.get("onebot", "20002") {
+///         other.send_to(&Target::group("123"), &event.get_message().clone()).await.ok();
+///     }
+/// }
+/// ```
+#[async_trait]
+impl FromContext for Arc<BotRegistry> {
+    async fn from_context(ctx: &AlloyContext) -> ExtractResult<Self> {
+        Ok(ctx.bot_registry().clone())
+    }
+}
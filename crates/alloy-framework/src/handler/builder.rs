@@ -137,6 +137,95 @@ impl AsyncPredicate<Arc<AlloyContext>> for AsyncEventPredicate {
     }
 }
 
+// ============================================================================
+// Prioritized
+// ============================================================================
+
+/// A handler service (or a builder for one) tagged with a dispatch priority.
+///
+/// Produced by [`ServiceBuilderExt::priority`].  Handlers inside a plugin run
+/// in descending priority order; handlers with equal priority keep their
+/// declaration order.  Untagged handlers have priority `0`.
+///
+/// `Prioritized` deliberately does **not** implement [`Service`] itself — it is
+/// unwrapped by [`define_plugin!`] when the handler list is assembled.
+///
+/// ```rust,ignore
+/// define_plugin! {
+///     name: "admin",
+///     handlers: [
+///         on_message().priority(10).block().handler(admin_guard),
+///         on_message().handler(log_everything),
+///     ],
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Prioritized<S> {
+    priority: i32,
+    inner: S,
+}
+
+impl<S> Prioritized<S> {
+    /// Tags `inner` with the given priority.
+    pub fn new(priority: i32, inner: S) -> Self {
+        Self { priority, inner }
+    }
+
+    /// Returns the attached priority.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Unwraps the tagged value.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<L> Prioritized<ServiceBuilder<L>> {
+    /// Finishes the builder with `handler`, keeping the priority tag.
+    pub fn handler<F, R, T>(self, handler: F) -> Prioritized<L::Service>
+    where
+        F: FromCtxFn<R, T>,
+        R: HandlerResponse,
+        L: Layer<HandlerService<F, R, T>>,
+    {
+        Prioritized::new(self.priority, self.inner.handler(handler))
+    }
+
+    /// Stacks an arbitrary layer, keeping the priority tag.
+    pub fn layer<T>(self, layer: T) -> Prioritized<ServiceBuilder<Stack<T, L>>> {
+        Prioritized::new(self.priority, self.inner.layer(layer))
+    }
+
+    /// See [`ServiceBuilderExt::rule_sync`].
+    pub fn rule_sync<F>(
+        self,
+        predicate: F,
+    ) -> Prioritized<ServiceBuilder<Stack<FilterLayer<EventPredicate>, L>>>
+    where
+        F: Fn(&AlloyContext) -> bool + Send + Sync + 'static,
+    {
+        Prioritized::new(self.priority, self.inner.rule_sync(predicate))
+    }
+
+    /// See [`ServiceBuilderExt::rule`].
+    pub fn rule<F, T>(
+        self,
+        predicate: F,
+    ) -> Prioritized<ServiceBuilder<Stack<AsyncFilterLayer<AsyncEventPredicate>, L>>>
+    where
+        F: FromCtxFn<bool, T>,
+    {
+        Prioritized::new(self.priority, self.inner.rule(predicate))
+    }
+
+    /// See [`ServiceBuilderExt::block`].
+    pub fn block(self) -> Prioritized<ServiceBuilder<Stack<BlockLayer, L>>> {
+        Prioritized::new(self.priority, self.inner.block())
+    }
+}
+
 /// Extension trait for [`tower::ServiceBuilder`] that adds convenience methods
 /// for building handler services with filtering and other cross-cutting concerns.
 ///
@@ -173,6 +262,12 @@ pub trait ServiceBuilderExt<L> {
     /// further handlers from processing the event. The current handler executes normally and
     /// completes successfully. If the inner service returns an error, it passes through unchanged.
     fn block(self) -> ServiceBuilder<Stack<BlockLayer, L>>;
+
+    /// Tags the handler built from this builder with a dispatch priority.
+    ///
+    /// Higher values run first within the plugin; the default is `0`.  The
+    /// remaining builder methods stay available on the returned [`Prioritized`].
+    fn priority(self, priority: i32) -> Prioritized<ServiceBuilder<L>>;
 }
 
 impl<L> ServiceBuilderExt<L> for ServiceBuilder<L> {
@@ -211,4 +306,8 @@ impl<L> ServiceBuilderExt<L> for ServiceBuilder<L> {
     fn block(self) -> ServiceBuilder<Stack<BlockLayer, L>> {
         self.layer(BlockLayer)
     }
+
+    fn priority(self, priority: i32) -> Prioritized<ServiceBuilder<L>> {
+        Prioritized::new(priority, self)
+    }
 }
@@ -52,7 +52,7 @@ pub mod builder;
 pub mod service;
 pub mod traits;
 
-pub use builder::{BlockLayer, BlockService, EventPredicate, ServiceBuilderExt};
+pub use builder::{BlockLayer, BlockService, EventPredicate, Prioritized, ServiceBuilderExt};
 pub use service::{HandlerResponse, HandlerService};
 pub use traits::FromCtxFn;
 
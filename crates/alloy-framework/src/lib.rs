@@ -14,6 +14,7 @@
 //! abstractions that aren't strictly necessary for the runtime.
 
 pub mod context;
+pub mod conversation;
 pub mod error;
 pub mod extractor;
 pub mod handler;
@@ -16,9 +16,30 @@
 //!   only the services it declared (via `provides` or `depends_on`) as part
 //!   of its own [`PluginContext`](crate::context::PluginContext).
 //! - Implements [`Dispatcher`]: on each incoming event it invokes all **active**
-//!   plugins **sequentially** in registration order, sharing a single
-//!   [`BaseContext`](crate::context::BaseContext).  Any plugin may call
-//!   `stop_propagation` to short-circuit the remaining plugins.
+//!   plugins tier by tier, sharing a single
+//!   [`BaseContext`](crate::context::BaseContext).  See below.
+//!
+//! # Dispatch order
+//!
+//! Active plugins are grouped into **tiers** by
+//! [`PluginMetadata::priority`](crate::plugin::PluginMetadata::priority)
+//! (higher first).  Tiers run one after another; the plugins inside a tier
+//! are started in registration order and run **concurrently**.
+//!
+//! ```text
+//! priority 10:  [admin]            ── stop_propagation? ──► done
+//!                  │ (tier finished, still propagating)
+//! priority  0:  [echo] [logger]    (concurrent, registration order)
+//!                  │
+//! priority -5:  [fallback]
+//! ```
+//!
+//! Calling `stop_propagation` only prevents **lower** tiers from running;
+//! plugins sharing the caller's priority always receive the event.
+//!
+//! Before any tier runs, message events are offered to open
+//! [conversations](crate::conversation); an event consumed by a waiting
+//! handler is not dispatched to plugins at all.
 //!
 //! # Example
 //!
@@ -33,18 +54,21 @@
 //! ```
 
 use std::any::TypeId;
+use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use async_trait::async_trait;
-use futures::future;
+use futures::future::{self, Either};
 use parking_lot::RwLock;
 use serde_json::{Map, Value};
-use tracing::{error, info, span, warn};
+use tracing::{debug, error, info, span, warn};
 
 use crate::context::{AlloyContext, BaseContext, PluginContext, ServiceArc};
+use crate::conversation::{ConversationConfig, ConversationHub};
 use crate::plugin::{ALLOY_PLUGIN_API_VERSION, Plugin, PluginDescriptor, PluginLoadContext};
-use alloy_core::{BoxedBot, BoxedEvent, Dispatcher};
+use alloy_core::{BotRegistry, BoxedBot, BoxedEvent, Dispatcher};
 
 // =============================================================================
 // Topological sort utility
@@ -193,6 +217,8 @@ pub enum PluginLoadState {
 struct PluginEntry {
     plugin: Arc<Plugin>,
     state: PluginLoadState,
+    /// Monotonic registration sequence number, used to break priority ties.
+    seq: u64,
 }
 
 // =============================================================================
@@ -224,6 +250,12 @@ pub struct PluginManager {
     plugin_configs: HashMap<String, Arc<Value>>,
     /// Managed exclusively by [`load_all`] / [`unload_all`].
     services: RwLock<HashMap<String, (TypeId, ServiceArc)>>,
+    /// Next value handed out as [`PluginEntry::seq`].
+    next_seq: AtomicU64,
+    /// Handlers waiting for a follow-up message.
+    conversations: Arc<ConversationHub>,
+    /// Runtime-wide bot registry, handed to handlers and `on_load` hooks.
+    bot_registry: Arc<BotRegistry>,
 }
 
 impl PluginManager {
@@ -236,9 +268,32 @@ impl PluginManager {
                 .map(|(k, v)| (k, Arc::new(v)))
                 .collect(),
             services: RwLock::new(HashMap::new()),
+            next_seq: AtomicU64::new(0),
+            conversations: Arc::new(ConversationHub::new(ConversationConfig::default())),
+            bot_registry: Arc::new(BotRegistry::new()),
         }
     }
 
+    /// Replaces the conversation limits (timeouts, cancellation words,
+    /// per-user cap).  Must be called before any event is dispatched.
+    pub fn with_conversation_config(mut self, config: ConversationConfig) -> Self {
+        self.conversations = Arc::new(ConversationHub::new(config));
+        self
+    }
+
+    /// Shares `registry` with plugins instead of a private, empty one.
+    ///
+    /// The runtime passes the registry its adapter bridges populate.
+    pub fn with_bot_registry(mut self, registry: Arc<BotRegistry>) -> Self {
+        self.bot_registry = registry;
+        self
+    }
+
+    /// Returns the bot registry exposed to plugins.
+    pub fn bot_registry(&self) -> &Arc<BotRegistry> {
+        &self.bot_registry
+    }
+
     // ─── Plugin registration ─────────────────────────────────────────────────
 
     /// Registers a plugin from a [`PluginDescriptor`].
@@ -247,6 +302,9 @@ impl PluginManager {
     /// [`PluginLoadState::Registered`].  It is **not** loaded until
     /// [`load_all`](Self::load_all) is called.
     ///
+    /// Registration order breaks ties between plugins of equal priority;
+    /// re-registering a name moves the plugin to the end of that order.
+    ///
     /// Logs a warning when the API version does not match, but continues —
     /// hard rejection can be enforced by callers if needed.
     pub fn register_plugin(&self, desc: &PluginDescriptor) {
@@ -268,14 +326,16 @@ impl PluginManager {
         }
         let instance = desc.instantiate();
         let name = instance.name().to_string();
+        let priority = instance.priority();
         self.plugins.write().insert(
             name.clone(),
             PluginEntry {
                 plugin: Arc::new(instance),
                 state: PluginLoadState::Registered,
+                seq: self.next_seq.fetch_add(1, Ordering::Relaxed),
             },
         );
-        info!(plugin = %name, "Plugin registered");
+        info!(plugin = %name, priority, "Plugin registered");
     }
 
     /// Removes the first plugin whose name matches `name`.
@@ -373,7 +433,7 @@ impl PluginManager {
         }
 
         let config = self.get_plugin_config(name);
-        let ctx = Arc::new(PluginLoadContext::new(config));
+        let ctx = Arc::new(PluginLoadContext::new(config, self.bot_registry.clone()));
 
         // ── 2. Initialise services in parallel ───────────────────────────
         let all_services = future::join_all(plugin.service_factories().iter().map(|entry| {
@@ -564,6 +624,46 @@ impl PluginManager {
     }
 }
 
+// =============================================================================
+// Priority tiers
+// =============================================================================
+
+impl PluginManager {
+    /// Snapshots the active plugins, grouped into priority tiers.
+    ///
+    /// Tiers are ordered by descending priority; plugins inside a tier are
+    /// ordered by registration sequence.
+    fn active_tiers(&self) -> Vec<Vec<(Arc<Plugin>, Arc<Value>)>> {
+        let mut active: Vec<(i32, u64, Arc<Plugin>, Arc<Value>)> = {
+            let plugins = self.plugins.read();
+            plugins
+                .iter()
+                .filter(|(_, e)| e.state == PluginLoadState::Active)
+                .map(|(name, e)| {
+                    (
+                        e.plugin.priority(),
+                        e.seq,
+                        e.plugin.clone(),
+                        self.get_plugin_config(name),
+                    )
+                })
+                .collect()
+        };
+        active.sort_by_key(|(priority, seq, _, _)| (Reverse(*priority), *seq));
+
+        let mut tiers: Vec<Vec<(Arc<Plugin>, Arc<Value>)>> = Vec::new();
+        let mut current_priority = None;
+        for (priority, _, plugin, config) in active {
+            if current_priority != Some(priority) {
+                current_priority = Some(priority);
+                tiers.push(Vec::new());
+            }
+            tiers.last_mut().unwrap().push((plugin, config));
+        }
+        tiers
+    }
+}
+
 // =============================================================================
 // Dispatcher impl
 // =============================================================================
@@ -576,76 +676,174 @@ impl Dispatcher for PluginManager {
     /// plugin.  Each plugin runs in its own isolated async task, allowing panics
     /// in one plugin to be contained without affecting others.
     ///
-    /// Plugins are executed sequentially in registration order. If any plugin calls
-    /// [`AlloyContext::stop_propagation`], the loop exits immediately and
-    /// subsequent plugins are skipped. Panics within a plugin are caught and logged,
-    /// but do not halt the dispatch process.
+    /// Plugins are executed tier by tier in descending priority; the plugins of
+    /// one tier run concurrently.  If any plugin calls
+    /// [`AlloyContext::stop_propagation`], the current tier still completes but
+    /// all lower tiers are skipped. Panics within a plugin are caught and
+    /// logged, but do not halt the dispatch process.
+    ///
+    /// A plugin whose handler starts waiting in a conversation no longer
+    /// holds up its tier: the next tier starts while the handler runs on.
+    ///
+    /// Message events that answer an open conversation are delivered to the
+    /// waiting handler instead and never reach the plugins.
     async fn dispatch(&self, event: BoxedEvent, bot: BoxedBot) {
         let event_name = event.event_name();
 
+        // Replies to open conversations never reach the plugins.
+        if self.conversations.try_deliver(&event, &bot) {
+            return;
+        }
+
         // Snapshot the global service map once for this dispatch cycle.
         // Each plugin will receive a filtered subset of this snapshot.
         let all_services = self.services.read().clone();
-        let base = Arc::new(BaseContext::new(event, bot));
-
-        // Snapshot active plugins — brief read lock.
-        let active_plugins: Vec<(Arc<Plugin>, Arc<Value>)> = {
-            let plugins = self.plugins.read();
-            plugins
-                .iter()
-                .filter(|(_, e)| e.state == PluginLoadState::Active)
-                .map(|(name, e)| (e.plugin.clone(), self.get_plugin_config(name)))
-                .collect()
-        };
-
-        // Dispatch sequentially in isolated tasks; stop early if propagation is halted.
-        for (plugin, config) in active_plugins {
+        let base = Arc::new(BaseContext::new(
+            event,
+            bot,
+            self.conversations.clone(),
+            self.bot_registry.clone(),
+        ));
+
+        for tier in self.active_tiers() {
             if !base.is_propagating() {
                 break;
             }
 
-            let declared: HashSet<&str> = plugin
-                .depends_on()
-                .iter()
-                .copied()
-                .chain(plugin.provides().iter().copied())
-                .collect();
-            let plugin_services = {
-                all_services
+            // Spawn every plugin of the tier in an isolated task (in
+            // registration order) to catch panics, then wait for all of them.
+            let tasks = tier.into_iter().map(|(plugin, config)| {
+                let declared: HashSet<&str> = plugin
+                    .depends_on()
+                    .iter()
+                    .copied()
+                    .chain(plugin.provides().iter().copied())
+                    .collect();
+                let plugin_services = all_services
                     .iter()
                     .filter(|(id, _)| declared.contains(id.as_str()))
                     .map(|(_, entry)| entry.clone())
-                    .collect()
-            };
-            let ctx = Arc::new(AlloyContext::new(
-                base.clone(),
-                PluginContext::new(plugin.name(), config, plugin_services),
-            ));
-
-            let plugin_clone = plugin.clone();
-
-            // Spawn each plugin in an isolated async task to catch panics and
-            // provide independent execution context.
-            let task_handle = tokio::spawn(async move {
-                let span = span!(
-                    tracing::Level::DEBUG,
-                    "dispatch",
-                    event_name = %event_name,
-                    plugin = %plugin_clone.name()
-                );
-                let _enter = span.enter();
-
-                plugin_clone.dispatch_event(ctx).await;
+                    .collect();
+                let ctx = Arc::new(AlloyContext::new(
+                    base.clone(),
+                    PluginContext::new(plugin.name(), config, plugin_services),
+                ));
+                let suspended = ctx.suspended().clone();
+
+                let plugin_clone = plugin.clone();
+                let task_handle = tokio::spawn(async move {
+                    let span = span!(
+                        tracing::Level::DEBUG,
+                        "dispatch",
+                        event_name = %event_name,
+                        plugin = %plugin_clone.name()
+                    );
+                    let _enter = span.enter();
+
+                    plugin_clone.dispatch_event(ctx).await;
+                });
+
+                async move {
+                    let suspended = std::pin::pin!(suspended.notified());
+                    match future::select(task_handle, suspended).await {
+                        Either::Left((Err(e), _)) => {
+                            error!(
+                                plugin = %plugin.name(),
+                                "Plugin task error: {}",
+                                e
+                            );
+                        }
+                        Either::Left((Ok(()), _)) => {}
+                        // The handler waits in a conversation and runs on in
+                        // the background.
+                        Either::Right(_) => {
+                            debug!(plugin = %plugin.name(), "Plugin suspended, releasing its tier");
+                        }
+                    }
+                }
             });
+            future::join_all(tasks.collect::<Vec<_>>()).await;
+        }
+    }
+}
 
-            // Wait for the task and handle any panics
-            if let Err(e) = task_handle.await {
-                error!(
-                    plugin = %plugin.name(),
-                    "Plugin task error: {}",
-                    e
-                );
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::{PluginMetadata, PluginType};
+
+    const fn meta(priority: i32) -> PluginMetadata {
+        PluginMetadata {
+            version: "0.0.0",
+            plugin_type: PluginType::Runtime,
+            desc: "",
+            full_desc: None,
+            priority,
+        }
+    }
+
+    macro_rules! test_plugin {
+        ($name:literal, $priority:expr) => {{
+            fn create() -> Plugin {
+                Plugin::__new($name, vec![], vec![], vec![], None, None, meta($priority))
+            }
+            PluginDescriptor {
+                api_version: ALLOY_PLUGIN_API_VERSION,
+                name: $name,
+                provides: &[],
+                depends_on: &[],
+                create,
+                metadata: meta($priority),
             }
+        }};
+    }
+
+    fn tier_names(manager: &PluginManager) -> Vec<Vec<String>> {
+        manager
+            .active_tiers()
+            .into_iter()
+            .map(|tier| tier.iter().map(|(p, _)| p.name().to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_active_tiers_ordered_by_priority_then_registration() {
+        let manager = PluginManager::new(HashMap::new());
+        let descriptors = [
+            test_plugin!("chatty_a", 0),
+            test_plugin!("fallback", -5),
+            test_plugin!("admin", 10),
+            test_plugin!("chatty_b", 0),
+            test_plugin!("chatty_c", 0),
+        ];
+        for desc in &descriptors {
+            manager.register_plugin(desc);
+            manager.set_plugin_state(desc.name, PluginLoadState::Active);
         }
+
+        assert_eq!(
+            tier_names(&manager),
+            vec![
+                vec!["admin".to_string()],
+                vec![
+                    "chatty_a".to_string(),
+                    "chatty_b".to_string(),
+                    "chatty_c".to_string()
+                ],
+                vec!["fallback".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_active_tiers_skip_inactive_plugins() {
+        let manager = PluginManager::new(HashMap::new());
+        let active = test_plugin!("active", 0);
+        let registered = test_plugin!("registered", 0);
+        manager.register_plugin(&active);
+        manager.register_plugin(&registered);
+        manager.set_plugin_state("active", PluginLoadState::Active);
+
+        assert_eq!(tier_names(&manager), vec![vec!["active".to_string()]]);
     }
 }
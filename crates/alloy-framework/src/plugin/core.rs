@@ -7,15 +7,19 @@ use tower::util::BoxCloneSyncService;
 use tower::{BoxError, Service};
 use tracing::{debug, error};
 
+use alloy_core::BotRegistry;
+
 use crate::context::{AlloyContext, ServiceArc};
 use crate::error::EventSkipped;
+use crate::handler::Prioritized;
 
 // ─── PluginLoadContext ────────────────────────────────────────────────────────
 
 /// Context passed to a plugin's `on_load` hook.
 ///
 /// Provides access to the plugin's configuration section from `alloy.yaml`
-/// (or an empty JSON object when the section is absent).
+/// (or an empty JSON object when the section is absent) and to the
+/// [`BotRegistry`], e.g. for background tasks that send without an event.
 ///
 /// # Example
 ///
@@ -33,11 +37,25 @@ use crate::error::EventSkipped;
 pub struct PluginLoadContext {
     /// Raw JSON value for this plugin's config section.
     plugin_config: Arc<serde_json::Value>,
+    /// All connected bots, across adapters.
+    bots: Arc<BotRegistry>,
 }
 
 impl PluginLoadContext {
-    pub(crate) fn new(plugin_config: Arc<serde_json::Value>) -> Self {
-        Self { plugin_config }
+    pub(crate) fn new(plugin_config: Arc<serde_json::Value>, bots: Arc<BotRegistry>) -> Self {
+        Self {
+            plugin_config,
+            bots,
+        }
+    }
+
+    /// Returns the runtime-wide bot registry.
+    ///
+    /// Bots usually connect after plugins load, so long-lived tasks should
+    /// look bots up when they need them or [`subscribe`](BotRegistry::subscribe)
+    /// to changes.
+    pub fn bot_registry(&self) -> &Arc<BotRegistry> {
+        &self.bots
     }
 
     /// Deserialise the plugin config section into `T`.
@@ -100,6 +118,7 @@ pub enum PluginType {
 /// | `plugin_type` | auto-inferred from whether `provides` is non-empty |
 /// | `desc` | `CARGO_PKG_DESCRIPTION` of the defining crate, or `""` |
 /// | `full_desc` | `None` |
+/// | `priority` | `0` |
 ///
 /// # Overriding via `define_plugin!`
 ///
@@ -111,6 +130,7 @@ pub enum PluginType {
 ///         plugin_type: runtime,
 ///         desc:        "Short description.",
 ///         full_desc:   "Even longer description.",
+///         priority:    10,
 ///     },
 ///     handlers: [...],
 /// };
@@ -129,6 +149,12 @@ pub struct PluginMetadata {
     pub desc: &'static str,
     /// Optional long-form description.
     pub full_desc: Option<&'static str>,
+    /// Dispatch priority — higher values receive events first.
+    ///
+    /// Plugins sharing a priority form one tier and run concurrently; a
+    /// plugin that calls `stop_propagation` only prevents lower tiers from
+    /// running.  See [`PluginManager`](crate::manager::PluginManager).
+    pub priority: i32,
 }
 
 // ─── ServiceEntry ─────────────────────────────────────────────────────────────
@@ -152,6 +178,39 @@ pub struct ServiceEntry {
 
 type BoxedHandlerService = BoxCloneSyncService<Arc<AlloyContext>, (), BoxError>;
 
+// ─── IntoPluginHandler ────────────────────────────────────────────────────────
+
+/// Conversion from a handler-list entry of [`define_plugin!`] into a boxed
+/// handler service plus its priority.
+///
+/// Implemented for every suitable tower service (priority `0`) and for
+/// [`Prioritized`] services produced by
+/// [`ServiceBuilderExt::priority`](crate::handler::ServiceBuilderExt::priority).
+pub trait IntoPluginHandler {
+    /// Returns `(priority, service)`.
+    fn into_plugin_handler(self) -> (i32, BoxedHandlerService);
+}
+
+impl<S> IntoPluginHandler for S
+where
+    S: Service<Arc<AlloyContext>, Response = (), Error = BoxError> + Clone + Send + Sync + 'static,
+    S::Future: Send + 'static,
+{
+    fn into_plugin_handler(self) -> (i32, BoxedHandlerService) {
+        (0, BoxCloneSyncService::new(self))
+    }
+}
+
+impl<S> IntoPluginHandler for Prioritized<S>
+where
+    S: Service<Arc<AlloyContext>, Response = (), Error = BoxError> + Clone + Send + Sync + 'static,
+    S::Future: Send + 'static,
+{
+    fn into_plugin_handler(self) -> (i32, BoxedHandlerService) {
+        (self.priority(), BoxCloneSyncService::new(self.into_inner()))
+    }
+}
+
 // ─── Plugin ───────────────────────────────────────────────────────────────────
 
 /// A live plugin instance bundling handlers, lifecycle hooks, and configuration.
@@ -166,7 +225,9 @@ pub struct Plugin {
     name: Cow<'static, str>,
     /// Service IDs required before this plugin loads.
     depends_on: Vec<&'static str>,
-    handlers: Vec<BoxedHandlerService>,
+    /// Handler chain with the handlers' priorities, already sorted by
+    /// descending priority.
+    handlers: Vec<(i32, BoxedHandlerService)>,
 
     /// Service factories generated by the [`define_plugin!`] macro.
     ///
@@ -240,17 +301,33 @@ impl Plugin {
         }
     }
 
+    /// Returns the plugin's dispatch priority (see [`PluginMetadata::priority`]).
+    pub fn priority(&self) -> i32 {
+        self.metadata.priority
+    }
+
     /// Called for every incoming event.  Runs the handler chain.
     ///
     /// The runtime injects the plugin's raw config JSON into the context
     /// **before** calling this method, so handlers can use [`PluginConfig<T>`].
+    ///
+    /// Handlers run one after another in priority order.  Once a handler in
+    /// this plugin stops propagation, the handlers with a lower priority are
+    /// skipped, while those sharing its priority still run; a concurrently
+    /// running plugin of the same tier stopping propagation does not affect
+    /// this chain.
     pub(crate) async fn dispatch_event(&self, ctx: Arc<AlloyContext>) {
-        for mut svc in self.handlers.iter().cloned() {
-            if !ctx.is_propagating() {
+        let mut halted_at = None;
+        for (priority, svc) in &self.handlers {
+            if halted_at.is_some_and(|halted| *priority < halted) {
                 debug!(plugin = %self.name, "Propagation stopped, halting handler chain");
                 break;
             }
-            if let Err(e) = svc.call(ctx.clone()).await
+            let result = svc.clone().call(ctx.clone()).await;
+            if halted_at.is_none() && ctx.is_halted() {
+                halted_at = Some(*priority);
+            }
+            if let Err(e) = result
                 && !e.is::<EventSkipped>()
             {
                 error!(
@@ -274,16 +351,20 @@ impl Plugin {
 
 impl Plugin {
     /// Creates a `Plugin` directly.  Only called by the [`define_plugin!`] macro.
+    ///
+    /// `handlers` is stably sorted by descending priority, so equal-priority
+    /// handlers keep their declaration order.
     #[doc(hidden)]
     pub fn __new(
         name: &'static str,
         depends_on: Vec<&'static str>,
-        handlers: Vec<BoxedHandlerService>,
+        mut handlers: Vec<(i32, BoxedHandlerService)>,
         service_factories: Vec<ServiceEntry>,
         on_load_fn: Option<OnLoadFn>,
         on_unload_fn: Option<OnUnloadFn>,
         metadata: PluginMetadata,
     ) -> Self {
+        handlers.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
         Plugin {
             name: Cow::Borrowed(name),
             depends_on,
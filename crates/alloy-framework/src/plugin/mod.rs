@@ -78,11 +78,8 @@ pub mod registry;
 
 // ─── Re-exports from submodules ──────────────────────────────────────────────
 pub use core::{
-    OnLoadFn, OnUnloadFn, Plugin, PluginLoadContext, PluginMetadata, PluginType, ServiceEntry,
+    IntoPluginHandler, OnLoadFn, OnUnloadFn, Plugin, PluginLoadContext, PluginMetadata, PluginType,
+    ServiceEntry,
 };
 pub use descriptor::{ALLOY_PLUGIN_API_VERSION, PluginDescriptor};
 pub use registry::{ServiceInit, ServiceMeta};
-
-// ─── Macro-internal re-export (needed by define_plugin! at call sites) ───────
-#[doc(hidden)]
-pub use tower::util::BoxCloneSyncService as __BoxCloneSyncService;
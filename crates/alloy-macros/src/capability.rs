@@ -1,86 +1,103 @@
 use proc_macro::TokenStream;
 use proc_macro2::Span;
-use quote::quote;
-use syn::{Ident, ItemFn, parse_macro_input};
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::{FnArg, Ident, ItemFn, LitStr, Path, Token, parse_macro_input};
 
-/// Implementation of `#[register_capability(cap_type)]` attribute macro.
+/// Arguments of `#[register_capability(CapType, provider = "name")]`.
+struct CapabilityArgs {
+    capability: Path,
+    provider: Option<LitStr>,
+}
+
+impl Parse for CapabilityArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let capability: Path = input.parse()?;
+        let mut provider = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let key: Ident = input.parse()?;
+            if key != "provider" {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("unknown argument `{key}`, expected `provider = \"...\"`"),
+                ));
+            }
+            input.parse::<Token![=]>()?;
+            provider = Some(input.parse()?);
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(Self {
+            capability,
+            provider,
+        })
+    }
+}
+
+/// Implementation of `#[register_capability(CapType)]` attribute macro.
 ///
-/// Leaves the decorated `async fn` unchanged and appends a
-/// `#[::alloy_core::linkme::distributed_slice]` static that wires the function
-/// into the corresponding capability registry in `alloy-core`.
+/// Leaves the decorated function unchanged and appends two statics: the
+/// function coerced to `<CapType as Capability>::Fn` (boxing the future of an
+/// `async fn`), and a `#[::alloy_core::linkme::distributed_slice]` entry that
+/// adds it to `alloy_core::CAPABILITY_REGISTRY`.
 pub fn register_capability(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let cap_type = parse_macro_input!(attr as Ident);
+    let CapabilityArgs {
+        capability,
+        provider,
+    } = parse_macro_input!(attr as CapabilityArgs);
     let func = parse_macro_input!(item as ItemFn);
 
     let fn_name = &func.sig.ident;
     let fn_name_upper = fn_name.to_string().to_uppercase();
-    let static_name = Ident::new(
+    let fn_static = Ident::new(
+        &format!("_CAPABILITY_FN_{fn_name_upper}"),
+        Span::call_site(),
+    );
+    let register_static = Ident::new(
         &format!("_CAPABILITY_REGISTER_{fn_name_upper}"),
         Span::call_site(),
     );
 
-    let cap_str = cap_type.to_string();
-
-    let (slice, fn_ty, arg_decls, arg_names) = match cap_str.as_str() {
-        "ws_client" => (
-            quote!(::alloy_core::WS_CONNECT_REGISTRY),
-            quote!(::alloy_core::WsConnectFn),
-            quote!(
-                config: ::alloy_core::WsClientConfig,
-                handler: ::std::sync::Arc<dyn ::alloy_core::ConnectionHandler>
-            ),
-            quote!(config, handler),
-        ),
-        "ws_server" => (
-            quote!(::alloy_core::WS_LISTEN_REGISTRY),
-            quote!(::alloy_core::WsListenFn),
-            quote!(
-                addr: ::std::string::String,
-                path: ::std::string::String,
-                handler: ::std::sync::Arc<dyn ::alloy_core::ConnectionHandler>
-            ),
-            quote!(addr, path, handler),
-        ),
-        "http_client" => (
-            quote!(::alloy_core::HTTP_START_CLIENT_REGISTRY),
-            quote!(::alloy_core::HttpStartClientFn),
-            quote!(
-                bot_id: ::std::string::String,
-                config: ::alloy_core::HttpClientConfig,
-                handler: ::std::sync::Arc<dyn ::alloy_core::ConnectionHandler>
-            ),
-            quote!(bot_id, config, handler),
-        ),
-        "http_server" => (
-            quote!(::alloy_core::HTTP_LISTEN_REGISTRY),
-            quote!(::alloy_core::HttpListenFn),
-            quote!(
-                addr: ::std::string::String,
-                path: ::std::string::String,
-                handler: ::std::sync::Arc<dyn ::alloy_core::ConnectionHandler>
-            ),
-            quote!(addr, path, handler),
-        ),
-        other => {
-            return syn::Error::new(
-                cap_type.span(),
-                format!(
-                    "unknown capability type `{other}`, \
-                     expected one of: ws_client, ws_server, http_client, http_server"
-                ),
-            )
-            .into_compile_error()
-            .into();
+    let mut arg_types = Vec::new();
+    for input in &func.sig.inputs {
+        match input {
+            FnArg::Typed(arg) => arg_types.push(&arg.ty),
+            FnArg::Receiver(receiver) => {
+                return syn::Error::new_spanned(
+                    receiver,
+                    "capability implementations must be free functions",
+                )
+                .into_compile_error()
+                .into();
+            }
         }
+    }
+    let arg_names: Vec<_> = (0..arg_types.len())
+        .map(|i| format_ident!("arg{i}"))
+        .collect();
+
+    let cap_fn = quote!(<#capability as ::alloy_core::Capability>::Fn);
+    let implementation = if func.sig.asyncness.is_some() {
+        quote!(|#(#arg_names: #arg_types),*| ::futures::FutureExt::boxed(#fn_name(#(#arg_names),*)))
+    } else {
+        quote!(#fn_name)
+    };
+    let provider = match provider {
+        Some(name) => quote!(#name),
+        None => quote!(::core::env!("CARGO_PKG_NAME")),
     };
 
     quote! {
         #func
 
-        #[::alloy_core::linkme::distributed_slice(#slice)]
+        static #fn_static: #cap_fn = #implementation;
+
+        #[::alloy_core::linkme::distributed_slice(::alloy_core::CAPABILITY_REGISTRY)]
         #[linkme(crate = ::alloy_core::linkme)]
-        static #static_name: #fn_ty =
-            |#arg_decls| ::futures::FutureExt::boxed(#fn_name(#arg_names));
+        static #register_static: ::alloy_core::CapabilityProvider = ::alloy_core::CapabilityProvider {
+            capability: <#capability as ::alloy_core::Capability>::NAME,
+            provider: #provider,
+            implementation: &#fn_static,
+        };
     }
     .into()
 }
@@ -33,9 +33,24 @@
 //! |-----|-------------|
 //! | `parent` | Marks this field as the parent (type is auto-detected) |
 //! | `raw_json` | Field that stores `Option<Arc<str>>` of raw JSON |
-//! | `bot_id` | Field that stores `Option<Arc<str>>` of bot ID |
+//! | `bot_id` | Field whose `to_string()` is returned by `Event::get_bot_id()` |
 //! | `message` | Field of type `Message<Segment>`, used for `Event::get_message()` |
 //! | `user_id` | Field whose `to_string()` is returned by `Event::get_user_id()` |
+//! | `group_id` | Group id; makes `Event::session()` a group scene |
+//! | `channel_id` | Channel id; makes `Event::session()` a channel scene |
+//!
+//! # Sessions
+//!
+//! `Event::session()` is generated for every struct that marks a `user_id`,
+//! `group_id` or `channel_id` field; other child events delegate to their
+//! parent.  The scene is picked from the first available source:
+//!
+//! 1. this struct's `channel_id`, then `group_id` (`Option` fields that are
+//!    `None` are skipped);
+//! 2. the parent's group or channel scene (child events only);
+//! 3. a private scene keyed by the user id.
+//!
+//! The bot id always comes from the `#[event(bot_id)]` field of the root.
 
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -66,6 +81,18 @@ struct FieldAttrs {
     is_raw_json: bool,
     is_message: bool,
     is_user_id: bool,
+    is_bot_id: bool,
+    is_group_id: bool,
+    is_channel_id: bool,
+}
+
+/// Fields contributing to `Event::session()` / `Event::get_bot_id()`.
+#[derive(Default)]
+struct SessionFields {
+    bot_id: Option<Ident>,
+    user_id: Option<Ident>,
+    group_id: Option<Ident>,
+    channel_id: Option<Ident>,
 }
 
 // ============================================================================
@@ -164,6 +191,12 @@ fn parse_field_attrs(attrs: &[Attribute]) -> syn::Result<FieldAttrs> {
                 result.is_message = true;
             } else if meta.path.is_ident("user_id") {
                 result.is_user_id = true;
+            } else if meta.path.is_ident("bot_id") {
+                result.is_bot_id = true;
+            } else if meta.path.is_ident("group_id") {
+                result.is_group_id = true;
+            } else if meta.path.is_ident("channel_id") {
+                result.is_channel_id = true;
             }
             Ok(())
         })?;
@@ -185,7 +218,7 @@ fn generate_struct_impl(
     let mut parent_field: Option<(Ident, Type)> = None;
     let mut raw_json_field: Option<Ident> = None;
     let mut message_field: Option<(Ident, Type)> = None;
-    let mut user_id_field: Option<Ident> = None;
+    let mut session_fields = SessionFields::default();
 
     if let Fields::Named(named) = fields {
         for f in &named.named {
@@ -201,7 +234,16 @@ fn generate_struct_impl(
                 message_field = Some((ident.clone(), f.ty.clone()));
             }
             if fa.is_user_id {
-                user_id_field = Some(ident.clone());
+                session_fields.user_id = Some(ident.clone());
+            }
+            if fa.is_bot_id {
+                session_fields.bot_id = Some(ident.clone());
+            }
+            if fa.is_group_id {
+                session_fields.group_id = Some(ident.clone());
+            }
+            if fa.is_channel_id {
+                session_fields.channel_id = Some(ident.clone());
             }
         }
     }
@@ -223,7 +265,7 @@ fn generate_struct_impl(
                 segment_type,
                 raw_json_field,
                 message_field,
-                user_id_field,
+                session_fields,
             )
         }
         EventKind::Child {
@@ -243,7 +285,7 @@ fn generate_struct_impl(
                 &pf_ident,
                 &pf_ty,
                 message_field,
-                user_id_field,
+                session_fields,
             ))
         }
     }
@@ -259,7 +301,7 @@ fn generate_root_event(
     segment_type_str: &str,
     raw_json_field: Option<Ident>,
     message_field: Option<(Ident, Type)>,
-    user_id_field: Option<Ident>,
+    session_fields: SessionFields,
 ) -> syn::Result<TokenStream> {
     let platform_lit = syn::LitStr::new(platform, name.span());
     let seg_ty: Type = syn::parse_str(segment_type_str)?;
@@ -292,7 +334,7 @@ fn generate_root_event(
         };
     }
 
-    let get_user_id_impl = if let Some(uid) = user_id_field {
+    let get_user_id_impl = if let Some(uid) = &session_fields.user_id {
         quote! {
             fn get_user_id(&self) -> Option<String> {
                 Some(self.#uid.to_string())
@@ -302,6 +344,18 @@ fn generate_root_event(
         quote! {}
     };
 
+    let get_bot_id_impl = if let Some(bid) = &session_fields.bot_id {
+        quote! {
+            fn get_bot_id(&self) -> Option<String> {
+                Some(self.#bid.to_string())
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let session_impl = generate_session_impl(&session_fields, None);
+
     let downgrade_any_impl = quote! {
         fn downgrade_any(&self, type_id: ::std::any::TypeId) -> Option<Box<dyn ::std::any::Any>> {
             // Root event: only matches self
@@ -330,6 +384,8 @@ fn generate_root_event(
             #downgrade_any_impl
             #raw_json_impl
             #get_user_id_impl
+            #get_bot_id_impl
+            #session_impl
             #segment_type_impl
             #get_message_impl
         }
@@ -351,7 +407,7 @@ fn generate_child_event(
     parent_field_ident: &Ident,
     parent_ty: &Type,
     message_field: Option<(Ident, Type)>,
-    user_id_field: Option<Ident>,
+    session_fields: SessionFields,
 ) -> TokenStream {
     // ── event_type ──
     let event_type_impl = match event_type {
@@ -452,7 +508,7 @@ fn generate_child_event(
     };
 
     // ── get_user_id ──
-    let get_user_id_impl = if let Some(uid) = user_id_field {
+    let get_user_id_impl = if let Some(uid) = &session_fields.user_id {
         quote! {
             fn get_user_id(&self) -> Option<String> {
                 Some(self.#uid.to_string())
@@ -466,6 +522,23 @@ fn generate_child_event(
         }
     };
 
+    // ── get_bot_id / session ──
+    let get_bot_id_impl = if let Some(bid) = &session_fields.bot_id {
+        quote! {
+            fn get_bot_id(&self) -> Option<String> {
+                Some(self.#bid.to_string())
+            }
+        }
+    } else {
+        quote! {
+            fn get_bot_id(&self) -> Option<String> {
+                <#parent_ty as ::alloy_core::Event>::get_bot_id(&self.#parent_field_ident)
+            }
+        }
+    };
+    let session_impl =
+        generate_session_impl(&session_fields, Some((parent_field_ident, parent_ty)));
+
     // ── DowngradeAny ──
     let downgrade_any_impl = quote! {
         fn downgrade_any(&self, type_id: ::std::any::TypeId) -> Option<Box<dyn ::std::any::Any>> {
@@ -492,6 +565,8 @@ fn generate_child_event(
             #downgrade_any_impl
             #raw_json_impl
             #get_user_id_impl
+            #get_bot_id_impl
+            #session_impl
             #segment_type_impl
             #get_message_impl
         }
@@ -502,3 +577,65 @@ fn generate_child_event(
         #event_impl
     }
 }
+
+// ============================================================================
+// Session generation
+// ============================================================================
+
+/// Generates `Event::session()`; see the module docs for the scene rules.
+///
+/// `parent` is `Some` for child events, which fall back to the parent's
+/// group/channel scene and delegate entirely when they mark no scene fields.
+fn generate_session_impl(fields: &SessionFields, parent: Option<(&Ident, &Type)>) -> TokenStream {
+    let declares_scene =
+        fields.user_id.is_some() || fields.group_id.is_some() || fields.channel_id.is_some();
+
+    if !declares_scene {
+        return match parent {
+            Some((pf, pty)) => quote! {
+                fn session(&self) -> Option<::alloy_core::Session> {
+                    <#pty as ::alloy_core::Event>::session(&self.#pf)
+                }
+            },
+            None => quote! {},
+        };
+    }
+
+    let channel_scene = fields.channel_id.iter().map(|f| {
+        quote! {
+            .or_else(|| ::alloy_core::session::SessionId::session_id(&self.#f)
+                .map(|id| (::alloy_core::SceneKind::Channel, id)))
+        }
+    });
+    let group_scene = fields.group_id.iter().map(|f| {
+        quote! {
+            .or_else(|| ::alloy_core::session::SessionId::session_id(&self.#f)
+                .map(|id| (::alloy_core::SceneKind::Group, id)))
+        }
+    });
+    let parent_scene = parent.iter().map(|(pf, pty)| {
+        quote! {
+            .or_else(|| <#pty as ::alloy_core::Event>::session(&self.#pf)
+                .filter(|s| !s.is_private())
+                .map(|s| (s.scene, s.scene_id)))
+        }
+    });
+
+    quote! {
+        fn session(&self) -> Option<::alloy_core::Session> {
+            let user_id = ::alloy_core::Event::get_user_id(self);
+            let (scene, scene_id) = ::std::option::Option::None
+                #( #channel_scene )*
+                #( #group_scene )*
+                #( #parent_scene )*
+                .or_else(|| user_id.clone().map(|id| (::alloy_core::SceneKind::Private, id)))?;
+            Some(::alloy_core::Session {
+                platform: ::alloy_core::Event::platform(self),
+                bot_id: ::alloy_core::Event::get_bot_id(self)?,
+                scene,
+                scene_id,
+                user_id,
+            })
+        }
+    }
+}
@@ -74,8 +74,10 @@ use syn::{DeriveInput, parse_macro_input};
 ///
 /// - `parent` — Marks the parent field (type auto-detected)
 /// - `raw_json` — `Option<Arc<str>>` field providing raw JSON
-/// - `bot_id` — `Option<Arc<str>>` field providing bot ID
+/// - `bot_id` — Field providing the bot ID (`get_bot_id()`)
 /// - `message` — Field implementing `Message` trait, used for `get_message()`
+/// - `user_id` — Field providing the user ID (`get_user_id()`)
+/// - `group_id` / `channel_id` — Scene fields used to build `session()`
 #[proc_macro_derive(BotEvent, attributes(event, root_event))]
 pub fn derive_bot_event(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -86,41 +88,42 @@ pub fn derive_bot_event(input: TokenStream) -> TokenStream {
     }
 }
 
-/// Registers an async function as a transport capability implementation.
+/// Registers a function as a transport capability implementation.
 ///
 /// # Usage
 ///
-/// Apply this attribute macro to an async function matching one of the four capability types:
+/// The argument is a type implementing `alloy_core::Capability`; the
+/// function's signature must match the capability's `Fn` type, with an
+/// `async fn` standing in for a function returning a `BoxFuture`:
 ///
 /// ```rust,ignore
-/// #[alloy_macros::register_capability(ws_client)]
+/// #[alloy_macros::register_capability(WsClient)]
 /// pub async fn ws_connect(
-///     url: String,
+///     config: WsClientConfig,
 ///     handler: Arc<dyn ConnectionHandler>,
-///     config: ClientConfig,
 /// ) -> TransportResult<ConnectionHandle> { ... }
 ///
-/// #[alloy_macros::register_capability(ws_server)]
+/// #[alloy_macros::register_capability(WsServer, provider = "my-ws")]
 /// pub async fn ws_listen(
-///     addr: String,
-///     path: String,
+///     config: WsServerConfig,
 ///     handler: Arc<dyn ConnectionHandler>,
 /// ) -> TransportResult<ListenerHandle> { ... }
 ///
-/// #[alloy_macros::register_capability(http_client)]
-/// pub async fn http_start_client(...) -> TransportResult<ConnectionHandle> { ... }
-///
-/// #[alloy_macros::register_capability(http_server)]
-/// pub async fn http_listen(...) -> TransportResult<ListenerHandle> { ... }
+/// // Capabilities defined outside alloy-core work the same way.
+/// #[alloy_macros::register_capability(crate::sse::SseServer)]
+/// pub async fn sse_listen(...) -> TransportResult<ListenerHandle> { ... }
 /// ```
 ///
 /// The macro leaves the decorated function unchanged and emits a `#[linkme::distributed_slice]`
 /// static that wires the function into the capability registry in `alloy-core`.
 ///
-/// The runtime calls [`TransportContext::collect_all()`] once at startup to
-/// gather all registered capabilities.
+/// The runtime calls [`TransportContext::collect()`] once at startup to
+/// gather all registered capabilities.  The provider name defaults to the
+/// crate's package name and is what configuration uses to choose between
+/// several providers of one capability.
 ///
-/// The attribute argument must be one of: `ws_client`, `ws_server`, `http_client`, `http_server`.
+/// The expansion refers to `::futures` and `::alloy_core`, which the calling
+/// crate must depend on.
 #[proc_macro_attribute]
 pub fn register_capability(attr: TokenStream, item: TokenStream) -> TokenStream {
     capability::register_capability(attr, item)
@@ -149,6 +152,7 @@ pub fn register_capability(attr: TokenStream, item: TokenStream) -> TokenStream
 ///     handlers: [
 ///         on_message().handler(my_handler),
 ///         on_command::<MyCmd>("cmd").handler(cmd_handler),
+///         on_message().priority(5).handler(first_handler), // runs before the others
 ///     ],
 ///
 ///     on_load:   my_on_load_fn,    // async fn(Arc<PluginLoadContext>) -> Result<()>
@@ -159,6 +163,7 @@ pub fn register_capability(attr: TokenStream, item: TokenStream) -> TokenStream
 ///         desc:        "Short summary.",  // default: CARGO_PKG_DESCRIPTION
 ///         full_desc:   "Longer text.",    // overrides doc comment
 ///         plugin_type: service,           // `service` or `runtime`; auto-inferred
+///         priority:    10,                // default: 0; higher tiers dispatch first
 ///     },
 /// }
 /// ```
@@ -170,10 +175,10 @@ pub fn register_capability(attr: TokenStream, item: TokenStream) -> TokenStream
 /// | `name` | ✓ | Must be **first** (after optional docs). Display name and config-section key. |
 /// | `provides` | — | `{ Trait: ImplType, … }` — services injected into the registry |
 /// | `depends_on` | — | `[Trait, …]` — traits that must exist before loading |
-/// | `handlers` | — | `[expr, …]` — Tower handler services |
+/// | `handlers` | — | `[expr, …]` — Tower handler services, optionally `.priority(n)`-tagged |
 /// | `on_load` | — | `async fn(Arc<PluginLoadContext>) -> Result<()>` |
 /// | `on_unload` | — | `async fn()` |
-/// | `metadata` | — | `{ version, desc, full_desc, plugin_type }` |
+/// | `metadata` | — | `{ version, desc, full_desc, plugin_type, priority }` |
 ///
 /// [`PluginDescriptor`]: alloy_framework::plugin::PluginDescriptor
 #[proc_macro]
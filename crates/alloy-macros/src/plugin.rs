@@ -21,6 +21,7 @@ struct MetadataOpts {
     desc: Option<LitStr>,
     full_desc: Option<LitStr>,
     plugin_type: Option<Ident>, // "service" | "runtime"
+    priority: Option<Expr>,
 }
 
 /// Parsed content of the whole `define_plugin! { … }` invocation.
@@ -77,7 +78,7 @@ fn parse_handlers(input: ParseStream) -> Result<Vec<Expr>> {
     Ok(exprs.into_iter().collect())
 }
 
-/// Parse `{ version: "…", desc: "…", full_desc: "…", plugin_type: service|runtime }`.
+/// Parse `{ version: "…", desc: "…", full_desc: "…", plugin_type: service|runtime, priority: N }`.
 fn parse_metadata(input: ParseStream) -> Result<MetadataOpts> {
     let content;
     braced!(content in input);
@@ -96,11 +97,12 @@ fn parse_metadata(input: ParseStream) -> Result<MetadataOpts> {
             "desc" => opts.desc = Some(content.parse()?),
             "full_desc" => opts.full_desc = Some(content.parse()?),
             "plugin_type" => opts.plugin_type = Some(content.parse()?),
+            "priority" => opts.priority = Some(content.parse()?),
             other => {
                 return Err(syn::Error::new(
                     key.span(),
                     format!(
-                        "unknown metadata key `{other}`; expected version, desc, full_desc, or plugin_type"
+                        "unknown metadata key `{other}`; expected version, desc, full_desc, plugin_type, or priority"
                     ),
                 ));
             }
@@ -277,6 +279,12 @@ pub fn expand(input: DefinePluginInput) -> TokenStream {
         }
     };
 
+    let priority_tokens = if let Some(p) = &metadata.priority {
+        quote! { #p }
+    } else {
+        quote! { 0 }
+    };
+
     // ── ServiceEntry vec ──────────────────────────────────────────────────────
     let service_entries = provides.iter().map(|e| {
         let t = &e.trait_path;
@@ -309,7 +317,7 @@ pub fn expand(input: DefinePluginInput) -> TokenStream {
 
     // ── handler vec ───────────────────────────────────────────────────────────
     let handler_entries = handlers.iter().map(|h| {
-        quote! { #fw::plugin::__BoxCloneSyncService::new(#h) }
+        quote! { #fw::plugin::IntoPluginHandler::into_plugin_handler(#h) }
     });
 
     // ── on_load / on_unload closures ──────────────────────────────────────────
@@ -344,6 +352,7 @@ pub fn expand(input: DefinePluginInput) -> TokenStream {
                 plugin_type: #plugin_type_tokens,
                 desc:        #desc_tokens,
                 full_desc:   #full_desc_tokens,
+                priority:    #priority_tokens,
             };
 
             fn __alloy_plugin_create() -> #fw::plugin::Plugin {
@@ -20,4 +20,7 @@ pub mod loader;
 pub mod schema;
 
 pub use loader::{ConfigLoader, Profile};
-pub use schema::{AlloyConfig, LogFormat, LogLevel, LogOutput, LoggingConfig, SpanEventConfig};
+pub use schema::{
+    AlloyConfig, LogFormat, LogLevel, LogOutput, LoggingConfig, RecordingConfig, SpanEventConfig,
+    TransportConfig,
+};
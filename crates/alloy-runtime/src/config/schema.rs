@@ -15,8 +15,12 @@
 //!
 //! ```text
 //! AlloyConfig
-//! ├── logging: LoggingConfig       # Logging settings
-//! └── adapters: Map<String, Value> # Adapter-specific configs (dynamic)
+//! ├── logging: LoggingConfig            # Logging settings
+//! ├── conversation: ConversationConfig  # Multi-step conversation limits
+//! ├── recording: RecordingConfig        # Raw inbound frame recording
+//! ├── outbound: OutboundConfig          # Per-bot API rate limits and queue
+//! ├── adapters: Map<String, Value>      # Adapter-specific configs (dynamic)
+//! └── plugins: Map<String, Value>       # Plugin-specific configs (dynamic)
 //! ```
 //!
 //! # Example Configuration (YAML)
@@ -42,6 +46,8 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use alloy_core::OutboundConfig;
+use alloy_framework::conversation::ConversationConfig;
 use figment::value::Value;
 use serde::{Deserialize, Serialize};
 
@@ -59,6 +65,46 @@ pub struct AlloyConfig {
     /// Logging configuration.
     pub logging: LoggingConfig,
 
+    /// Limits for handlers waiting on follow-up messages.
+    ///
+    /// ```yaml
+    /// conversation:
+    ///   max_per_user: 1
+    ///   default_timeout_secs: 60
+    ///   cancel_words: ["cancel", "取消"]
+    /// ```
+    pub conversation: ConversationConfig,
+
+    /// Raw inbound frame recording, for replaying production issues.
+    ///
+    /// ```yaml
+    /// recording:
+    ///   path: ./recordings/events.jsonl
+    /// ```
+    pub recording: RecordingConfig,
+
+    /// Rate limits and queueing for the API calls of each bot; unlimited
+    /// unless a limit is set.
+    ///
+    /// ```yaml
+    /// outbound:
+    ///   actions:
+    ///     send_group_msg: { rate: 1, burst: 3 }
+    ///   per_target: { rate: 0.5, burst: 2 }
+    ///   queue_capacity: 200
+    ///   overflow: drop-oldest
+    /// ```
+    pub outbound: OutboundConfig,
+
+    /// Transport capability selection.
+    ///
+    /// ```yaml
+    /// transport:
+    ///   providers:
+    ///     ws_client: alloy-transport
+    /// ```
+    pub transport: TransportConfig,
+
     /// Adapter-specific configurations.
     ///
     /// Each adapter registers its own configuration schema.
@@ -85,6 +131,36 @@ pub struct AlloyConfig {
     pub plugins: HashMap<String, Value>,
 }
 
+// =============================================================================
+// Recording Configuration
+// =============================================================================
+
+/// Event recording configuration.
+///
+/// Recording is off unless `path` is set.  Every raw frame received by any
+/// adapter is appended to the file as one JSON line; see
+/// [`alloy_core::record`] for the format and for replaying it.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct RecordingConfig {
+    /// JSONL file to append frames to.
+    pub path: Option<PathBuf>,
+}
+
+// =============================================================================
+// Transport Configuration
+// =============================================================================
+
+/// Transport capability configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct TransportConfig {
+    /// Provider to use per capability name, for capabilities implemented by
+    /// more than one linked crate.  Unlisted capabilities use the first
+    /// provider found.
+    pub providers: HashMap<String, String>,
+}
+
 // =============================================================================
 // Logging Configuration
 // =============================================================================
@@ -29,12 +29,15 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use futures::future;
 use parking_lot::Mutex;
 use tokio::signal;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
 use crate::config::{AlloyConfig, ConfigLoader};
 use crate::error::{RuntimeError, RuntimeResult};
 use crate::logging;
-use alloy_core::{AdapterBridge, ConfigurableAdapter, TransportContext};
+use alloy_core::{
+    AdapterBridge, BotRegistry, ConfigurableAdapter, ConnectionStatus, EventRecorder,
+    TransportContext,
+};
 use alloy_framework::{manager::PluginManager, plugin::PluginDescriptor};
 
 /// The main Alloy runtime that orchestrates adapters, transports, and plugins.
@@ -77,6 +80,8 @@ pub struct AlloyRuntime {
     transport_context: TransportContext,
     /// Adapter bridges, created eagerly on registration.
     bridges: Mutex<HashMap<String, Arc<AdapterBridge>>>,
+    /// Raw frame recorder shared by all bridges, if recording is configured.
+    recorder: Option<Arc<EventRecorder>>,
     /// Whether the runtime is running.
     running: AtomicBool,
 }
@@ -123,7 +128,8 @@ impl AlloyRuntime {
 
         // Create transport context by collecting all capabilities registered via
         // `#[register_capability(...)]` across linked crates.
-        let transport_ctx = alloy_core::TransportContext::collect_all();
+        let transport_ctx = TransportContext::collect(&config.transport.providers);
+        debug!(capabilities = ?transport_ctx, "Transport capabilities collected");
 
         info!(
             log_level = %config.logging.level,
@@ -138,12 +144,31 @@ impl AlloyRuntime {
             .iter()
             .map(|(k, v)| (k.clone(), serde_json::to_value(v).unwrap_or_default()))
             .collect();
+        let plugin_manager = PluginManager::new(plugin_configs)
+            .with_conversation_config(config.conversation.clone());
+
+        let recorder =
+            config
+                .recording
+                .path
+                .as_ref()
+                .and_then(|path| match EventRecorder::open(path) {
+                    Ok(recorder) => {
+                        info!(path = %path.display(), "Recording inbound frames");
+                        Some(Arc::new(recorder))
+                    }
+                    Err(e) => {
+                        error!(path = %path.display(), error = %e, "Failed to open recording file");
+                        None
+                    }
+                });
 
         Self {
             config,
-            plugin_manager: Arc::new(PluginManager::new(plugin_configs)),
+            plugin_manager: Arc::new(plugin_manager),
             transport_context: transport_ctx,
             bridges: Mutex::new(HashMap::new()),
+            recorder,
             running: AtomicBool::new(false),
         }
     }
@@ -153,6 +178,33 @@ impl AlloyRuntime {
         &self.config
     }
 
+    /// Returns the registry of bots connected through any adapter.
+    ///
+    /// This is the same registry plugins see through
+    /// [`PluginLoadContext`](alloy_framework::plugin::PluginLoadContext) and
+    /// the `Arc<BotRegistry>` extractor.
+    pub fn bot_registry(&self) -> &Arc<BotRegistry> {
+        self.plugin_manager.bot_registry()
+    }
+
+    /// Returns the reconnect state of every self-reconnecting client
+    /// connection, as `(adapter, bot ID, status)`.
+    ///
+    /// Servers and plain HTTP clients report nothing here; their bots are
+    /// listed in the [`bot_registry`](Self::bot_registry) while connected.
+    pub fn connection_statuses(&self) -> Vec<(String, String, ConnectionStatus)> {
+        self.bridges
+            .lock()
+            .iter()
+            .flat_map(|(name, bridge)| {
+                bridge
+                    .connection_statuses()
+                    .into_iter()
+                    .map(move |(bot_id, status)| (name.clone(), bot_id, status))
+            })
+            .collect()
+    }
+
     /// Registers an adapter with the runtime.
     ///
     /// Configuration is loaded from `alloy.yaml` under the adapter's name key,
@@ -186,11 +238,20 @@ impl AlloyRuntime {
         };
 
         let adapter = Arc::new(A::from_config(config));
-        let bridge = Arc::new(AdapterBridge::new(
+        let mut bridge = AdapterBridge::new(
+            adapter_name,
             adapter,
             self.plugin_manager.clone(),
-            self.transport_context,
-        ));
+            self.transport_context.clone(),
+        )
+        .with_registry(self.bot_registry().clone());
+        if let Some(recorder) = &self.recorder {
+            bridge = bridge.with_recorder(recorder.clone());
+        }
+        if self.config.outbound.is_limited() {
+            bridge = bridge.with_outbound(self.config.outbound.clone());
+        }
+        let bridge = Arc::new(bridge);
 
         self.bridges.lock().insert(adapter_name.to_string(), bridge);
         info!(adapter = adapter_name, "Registered adapter");
@@ -0,0 +1,214 @@
+//! A [`Bot`] that records what handlers do instead of talking to a platform.
+
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde_json::Value;
+
+use alloy_core::{ApiResult, Bot, ErasedMessage, Event, RichText, Session, Target};
+
+/// Bot ID used by [`MockBot::default`] and the OneBot event builders.
+pub const DEFAULT_BOT_ID: &str = "10001";
+
+// =============================================================================
+// BotCall
+// =============================================================================
+
+/// One call a handler made on a [`MockBot`].
+#[derive(Debug, Clone)]
+pub enum BotCall {
+    /// [`Bot::call_api`].
+    CallApi {
+        /// API action name.
+        action: String,
+        /// Parameters as passed.
+        params: Value,
+    },
+    /// [`Bot::send`] with a plain string.
+    Send {
+        /// Session of the event being replied to.
+        session: Option<Session>,
+        /// Message text.
+        text: String,
+    },
+    /// [`Bot::send_message`] with a rich message.
+    SendMessage {
+        /// Session of the event being replied to.
+        session: Option<Session>,
+        /// The message, converted to its platform-agnostic form.
+        message: RichText,
+    },
+    /// [`Bot::send_to`] with an explicit target.
+    SendTo {
+        /// Where the message was sent.
+        target: Target,
+        /// The message, converted to its platform-agnostic form.
+        message: RichText,
+    },
+}
+
+impl BotCall {
+    /// Returns the rendered message for `send*` calls, `None` for API calls.
+    pub fn reply_text(&self) -> Option<String> {
+        match self {
+            BotCall::CallApi { .. } => None,
+            BotCall::Send { text, .. } => Some(text.clone()),
+            BotCall::SendMessage { message, .. } | BotCall::SendTo { message, .. } => {
+                Some(message.to_string())
+            }
+        }
+    }
+}
+
+// =============================================================================
+// MockBot
+// =============================================================================
+
+/// A bot that records every call and answers API calls from a script.
+///
+/// ```rust,ignore
+/// let bot = MockBot::new("10001");
+/// bot.respond("get_login_info", Ok(json!({ "user_id": 10001, "nickname": "bot" })));
+///
+/// // ... run a handler against `bot` ...
+///
+/// assert_eq!(bot.replies(), ["hello"]);
+/// ```
+///
+/// Scripted responses for an action are consumed in order and the last one
+/// repeats; unscripted actions return `Ok(Value::Null)`.  `send*` calls always
+/// succeed and return increasing message IDs.
+pub struct MockBot {
+    id: String,
+    calls: Mutex<Vec<BotCall>>,
+    responses: Mutex<HashMap<String, VecDeque<ApiResult<Value>>>>,
+    next_message_id: AtomicU64,
+}
+
+impl MockBot {
+    /// Creates a mock bot with the given ID.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            calls: Mutex::new(Vec::new()),
+            responses: Mutex::new(HashMap::new()),
+            next_message_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Appends a scripted response for `action`.
+    pub fn respond(&self, action: impl Into<String>, response: ApiResult<Value>) -> &Self {
+        self.responses
+            .lock()
+            .entry(action.into())
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    /// Returns every call recorded so far, in order.
+    pub fn calls(&self) -> Vec<BotCall> {
+        self.calls.lock().clone()
+    }
+
+    /// Returns the number of calls recorded so far.
+    pub fn call_count(&self) -> usize {
+        self.calls.lock().len()
+    }
+
+    /// Returns the `(action, params)` of every [`Bot::call_api`] call.
+    pub fn api_calls(&self) -> Vec<(String, Value)> {
+        self.calls
+            .lock()
+            .iter()
+            .filter_map(|call| match call {
+                BotCall::CallApi { action, params } => Some((action.clone(), params.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the rendered text of every message sent so far.
+    pub fn replies(&self) -> Vec<String> {
+        self.calls
+            .lock()
+            .iter()
+            .filter_map(BotCall::reply_text)
+            .collect()
+    }
+
+    /// Forgets all recorded calls.  Scripted responses are kept.
+    pub fn clear(&self) {
+        self.calls.lock().clear();
+    }
+
+    fn record(&self, call: BotCall) -> String {
+        self.calls.lock().push(call);
+        self.next_message_id
+            .fetch_add(1, Ordering::Relaxed)
+            .to_string()
+    }
+
+    fn scripted(&self, action: &str) -> ApiResult<Value> {
+        let mut responses = self.responses.lock();
+        match responses.get_mut(action) {
+            Some(queue) if queue.len() > 1 => queue.pop_front().unwrap(),
+            Some(queue) => queue.front().cloned().unwrap_or(Ok(Value::Null)),
+            None => Ok(Value::Null),
+        }
+    }
+}
+
+impl Default for MockBot {
+    fn default() -> Self {
+        Self::new(DEFAULT_BOT_ID)
+    }
+}
+
+#[async_trait]
+impl Bot for MockBot {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    async fn call_api(&self, action: &str, params: Value) -> ApiResult<Value> {
+        self.calls.lock().push(BotCall::CallApi {
+            action: action.to_string(),
+            params,
+        });
+        self.scripted(action)
+    }
+
+    async fn send(&self, event: &dyn Event, message: &str) -> ApiResult<String> {
+        Ok(self.record(BotCall::Send {
+            session: event.session(),
+            text: message.to_string(),
+        }))
+    }
+
+    async fn send_message(
+        &self,
+        event: &dyn Event,
+        message: &dyn ErasedMessage,
+    ) -> ApiResult<String> {
+        Ok(self.record(BotCall::SendMessage {
+            session: event.session(),
+            message: RichText::from_erased_message(message),
+        }))
+    }
+
+    async fn send_to(&self, target: &Target, message: &dyn ErasedMessage) -> ApiResult<String> {
+        Ok(self.record(BotCall::SendTo {
+            target: target.clone(),
+            message: RichText::from_erased_message(message),
+        }))
+    }
+
+    fn as_any(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self
+    }
+}
@@ -0,0 +1,33 @@
+//! # Alloy Test
+//!
+//! Test harness for Alloy plugins and handlers — no adapter, transport or
+//! runtime required.
+//!
+//! - [`MockBot`]: a [`Bot`](alloy_core::Bot) that records `call_api` / `send`
+//!   / `send_message` / `send_to` calls and answers API calls from a script
+//! - [`PluginTester`]: registers plugin descriptors with their configs into
+//!   a real [`PluginManager`](alloy_framework::manager::PluginManager),
+//!   dispatches events and asserts replies
+//! - [`onebot`]: builders for OneBot v11 events (feature `onebot`, on by
+//!   default)
+//!
+//! ```rust,ignore
+//! use alloy_test::{PluginTester, onebot};
+//!
+//! #[tokio::test]
+//! async fn echo_replies() {
+//!     let tester = PluginTester::builder().plugin(&ECHO_PLUGIN).build().await;
+//!
+//!     tester
+//!         .assert_reply(onebot::group_message(123, 456, "/echo hi"), "hi")
+//!         .await;
+//! }
+//! ```
+
+pub mod bot;
+#[cfg(feature = "onebot")]
+pub mod onebot;
+pub mod tester;
+
+pub use bot::{BotCall, DEFAULT_BOT_ID, MockBot};
+pub use tester::{MOCK_PLATFORM, PluginTester, PluginTesterBuilder};
@@ -0,0 +1,137 @@
+//! Builders for OneBot v11 events.
+//!
+//! Events are assembled as the JSON a OneBot implementation would post and
+//! then parsed by the adapter, so `raw_json`, the event hierarchy and
+//! [`Event::session`](alloy_core::Event::session) behave exactly as in
+//! production.
+//!
+//! ```rust,ignore
+//! let event = onebot::group_message(123, 456, "/echo hi")
+//!     .nickname("alice")
+//!     .role("admin")
+//!     .build();
+//! ```
+
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use serde_json::{Value, json};
+
+use alloy_adapter_onebot::model::event::parse_onebot_event;
+use alloy_core::BoxedEvent;
+
+use crate::bot::DEFAULT_BOT_ID;
+
+static NEXT_MESSAGE_ID: AtomicI32 = AtomicI32::new(1);
+
+/// Parses an arbitrary OneBot payload, e.g. a notice or request event.
+///
+/// # Panics
+///
+/// Panics if the adapter cannot parse `payload`.
+pub fn event(payload: Value) -> BoxedEvent {
+    parse_onebot_event(&payload.to_string())
+        .unwrap_or_else(|e| panic!("invalid OneBot event {payload}: {e}"))
+}
+
+/// Starts a private message from `user_id`.
+///
+/// `message` may contain CQ codes.
+pub fn private_message(user_id: i64, message: impl Into<String>) -> MessageEventBuilder {
+    MessageEventBuilder::new("private", "friend", user_id, message.into())
+}
+
+/// Starts a group message from `user_id` in `group_id`.
+///
+/// `message` may contain CQ codes.
+pub fn group_message(
+    group_id: i64,
+    user_id: i64,
+    message: impl Into<String>,
+) -> MessageEventBuilder {
+    let mut builder = MessageEventBuilder::new("group", "normal", user_id, message.into());
+    builder.payload["group_id"] = json!(group_id);
+    builder.payload["sender"]["role"] = json!("member");
+    builder
+}
+
+/// Builder for private and group message events.
+///
+/// Defaults: `self_id` is [`DEFAULT_BOT_ID`], `time` is 0, message IDs are
+/// unique within the process, and group senders are plain members.
+#[derive(Debug, Clone)]
+pub struct MessageEventBuilder {
+    payload: Value,
+}
+
+impl MessageEventBuilder {
+    fn new(message_type: &str, sub_type: &str, user_id: i64, message: String) -> Self {
+        let self_id: i64 = DEFAULT_BOT_ID.parse().unwrap();
+        Self {
+            payload: json!({
+                "time": 0,
+                "self_id": self_id,
+                "post_type": "message",
+                "message_type": message_type,
+                "sub_type": sub_type,
+                "message_id": NEXT_MESSAGE_ID.fetch_add(1, Ordering::Relaxed),
+                "user_id": user_id,
+                "message": message,
+                "raw_message": message,
+                "font": 0,
+                "sender": { "user_id": user_id },
+            }),
+        }
+    }
+
+    /// Sets the receiving bot's ID.
+    pub fn self_id(mut self, self_id: i64) -> Self {
+        self.payload["self_id"] = json!(self_id);
+        self
+    }
+
+    /// Sets the message ID.
+    pub fn message_id(mut self, message_id: i32) -> Self {
+        self.payload["message_id"] = json!(message_id);
+        self
+    }
+
+    /// Sets the event timestamp.
+    pub fn time(mut self, time: i64) -> Self {
+        self.payload["time"] = json!(time);
+        self
+    }
+
+    /// Sets the sender's nickname.
+    pub fn nickname(mut self, nickname: impl Into<String>) -> Self {
+        self.payload["sender"]["nickname"] = json!(nickname.into());
+        self
+    }
+
+    /// Sets the sender's group card.
+    pub fn card(mut self, card: impl Into<String>) -> Self {
+        self.payload["sender"]["card"] = json!(card.into());
+        self
+    }
+
+    /// Sets the sender's group role (`"owner"`, `"admin"` or `"member"`).
+    pub fn role(mut self, role: impl Into<String>) -> Self {
+        self.payload["sender"]["role"] = json!(role.into());
+        self
+    }
+
+    /// Returns the JSON payload that [`build`](Self::build) will parse.
+    pub fn payload(&self) -> &Value {
+        &self.payload
+    }
+
+    /// Parses the payload into an event.
+    pub fn build(self) -> BoxedEvent {
+        event(self.payload)
+    }
+}
+
+impl From<MessageEventBuilder> for BoxedEvent {
+    fn from(builder: MessageEventBuilder) -> Self {
+        builder.build()
+    }
+}
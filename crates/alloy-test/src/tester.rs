@@ -0,0 +1,462 @@
+//! Drive plugins through a real [`PluginManager`] without an adapter.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_json::Value;
+use tokio::task::JoinHandle;
+
+use alloy_core::{BoxedBot, BoxedEvent, Dispatcher};
+use alloy_framework::conversation::ConversationConfig;
+use alloy_framework::manager::{PluginLoadState, PluginManager};
+use alloy_framework::plugin::PluginDescriptor;
+
+use crate::bot::{BotCall, MockBot};
+
+/// Platform the tester's [`MockBot`] is registered under in the
+/// [`BotRegistry`](alloy_core::BotRegistry).
+pub const MOCK_PLATFORM: &str = "mock";
+
+/// Loads plugins into a [`PluginManager`] and dispatches events to them on
+/// behalf of a [`MockBot`].
+///
+/// ```rust,ignore
+/// let tester = PluginTester::builder()
+///     .plugin(&ECHO_PLUGIN)
+///     .config("echo", json!({ "prefix": "> " }))
+///     .build()
+///     .await;
+///
+/// tester
+///     .assert_reply(onebot::private_message(1, "/echo hi"), "> hi")
+///     .await;
+/// ```
+///
+/// [`dispatch`](Self::dispatch) returns when every handler has finished or
+/// started waiting for a follow-up message, so the calls it returns are
+/// complete up to that point.  A waiting handler goes on in the background
+/// once the follow-up is dispatched; wait for what it sends with
+/// [`until_calls`](Self::until_calls).
+pub struct PluginTester {
+    manager: Arc<PluginManager>,
+    bot: Arc<MockBot>,
+}
+
+impl PluginTester {
+    /// Starts building a tester.
+    pub fn builder() -> PluginTesterBuilder {
+        PluginTesterBuilder::default()
+    }
+
+    /// Returns the mock bot every event is dispatched with.
+    pub fn bot(&self) -> &Arc<MockBot> {
+        &self.bot
+    }
+
+    /// Returns the underlying plugin manager.
+    pub fn manager(&self) -> &Arc<PluginManager> {
+        &self.manager
+    }
+
+    /// Returns the load state of the named plugin.
+    pub fn plugin_state(&self, name: &str) -> Option<PluginLoadState> {
+        self.manager.plugin_states().remove(name)
+    }
+
+    /// Dispatches `event` and returns the bot calls made while handling it.
+    pub async fn dispatch(&self, event: impl Into<BoxedEvent>) -> Vec<BotCall> {
+        let start = self.bot.call_count();
+        self.manager
+            .dispatch(event.into(), self.bot.clone() as BoxedBot)
+            .await;
+        self.bot.calls().split_off(start)
+    }
+
+    /// Waits until the bot has made `count` calls in total, e.g. once a
+    /// handler that was waiting in a conversation has replied.
+    pub async fn until_calls(&self, count: usize) {
+        while self.bot.call_count() < count {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    /// Dispatches `event` in the background.
+    pub fn spawn_dispatch(&self, event: impl Into<BoxedEvent>) -> JoinHandle<()> {
+        let manager = self.manager.clone();
+        let bot = self.bot.clone() as BoxedBot;
+        let event = event.into();
+        tokio::spawn(async move { manager.dispatch(event, bot).await })
+    }
+
+    /// Dispatches `event` and returns the text of every message sent.
+    pub async fn replies(&self, event: impl Into<BoxedEvent>) -> Vec<String> {
+        self.dispatch(event)
+            .await
+            .iter()
+            .filter_map(BotCall::reply_text)
+            .collect()
+    }
+
+    /// Dispatches `event` and asserts that exactly `expected` was sent.
+    pub async fn assert_reply(&self, event: impl Into<BoxedEvent>, expected: &str) {
+        assert_eq!(self.replies(event).await, [expected]);
+    }
+
+    /// Dispatches `event` and asserts that nothing was sent.
+    pub async fn assert_no_reply(&self, event: impl Into<BoxedEvent>) {
+        let replies = self.replies(event).await;
+        assert!(replies.is_empty(), "expected no reply, got {replies:?}");
+    }
+}
+
+// =============================================================================
+// Builder
+// =============================================================================
+
+/// Builder for [`PluginTester`].
+#[derive(Default)]
+pub struct PluginTesterBuilder {
+    plugins: Vec<&'static PluginDescriptor>,
+    configs: HashMap<String, Value>,
+    conversation: Option<ConversationConfig>,
+    bot: Option<MockBot>,
+}
+
+impl PluginTesterBuilder {
+    /// Registers a plugin.
+    pub fn plugin(mut self, desc: &'static PluginDescriptor) -> Self {
+        self.plugins.push(desc);
+        self
+    }
+
+    /// Sets the configuration section of the named plugin, as it would appear
+    /// under `plugins.<name>` in the config file.
+    pub fn config(mut self, plugin: impl Into<String>, config: Value) -> Self {
+        self.configs.insert(plugin.into(), config);
+        self
+    }
+
+    /// Replaces the conversation limits.
+    pub fn conversation_config(mut self, config: ConversationConfig) -> Self {
+        self.conversation = Some(config);
+        self
+    }
+
+    /// Uses `bot` instead of [`MockBot::default`], e.g. to script responses
+    /// that `on_load` hooks depend on.
+    pub fn bot(mut self, bot: MockBot) -> Self {
+        self.bot = Some(bot);
+        self
+    }
+
+    /// Registers and loads every plugin.
+    pub async fn build(self) -> PluginTester {
+        let mut manager = PluginManager::new(self.configs);
+        if let Some(config) = self.conversation {
+            manager = manager.with_conversation_config(config);
+        }
+        for desc in self.plugins {
+            manager.register_plugin(desc);
+        }
+        let bot = Arc::new(self.bot.unwrap_or_default());
+        manager
+            .bot_registry()
+            .register(MOCK_PLATFORM, bot.clone() as BoxedBot);
+        manager.load_all().await;
+
+        PluginTester {
+            manager: Arc::new(manager),
+            bot,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "onebot"))]
+mod tests {
+    use std::time::Duration;
+
+    use alloy::prelude::*;
+    use alloy_adapter_onebot::MessageEvent;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+    use crate::DEFAULT_BOT_ID;
+    use crate::onebot;
+
+    #[derive(Deserialize, Default)]
+    struct EchoConfig {
+        #[serde(default)]
+        prefix: String,
+    }
+
+    async fn echo(event: Event<MessageEvent>, config: PluginConfig<EchoConfig>) -> String {
+        format!("{}{}", config.prefix, event.get_plain_text())
+    }
+
+    define_plugin! {
+        name: "echo",
+        handlers: [on_message().handler(echo)],
+    }
+
+    #[tokio::test]
+    async fn test_echo_plugin_replies_with_config() {
+        let tester = PluginTester::builder()
+            .plugin(&ECHO_PLUGIN)
+            .config("echo", json!({ "prefix": "> " }))
+            .build()
+            .await;
+        assert_eq!(tester.plugin_state("echo"), Some(PluginLoadState::Active));
+
+        let calls = tester.dispatch(onebot::group_message(1, 2, "hi")).await;
+        assert_eq!(calls.len(), 1);
+        let BotCall::Send { session, text } = &calls[0] else {
+            panic!("unexpected call {:?}", calls[0]);
+        };
+        assert_eq!(text, "> hi");
+        assert_eq!(session.as_ref().unwrap().scene_id, "1");
+
+        tester
+            .assert_reply(onebot::private_message(2, "again"), "> again")
+            .await;
+    }
+
+    async fn list_bots(bots: Arc<BotRegistry>) -> String {
+        bots.ids().join(",")
+    }
+
+    mod bots_plugin {
+        use super::*;
+
+        define_plugin! {
+            name: "bots",
+            handlers: [on_message().handler(list_bots)],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bot_registry_extractor() {
+        let tester = PluginTester::builder()
+            .plugin(&bots_plugin::BOTS_PLUGIN)
+            .build()
+            .await;
+        let registry = tester.manager().bot_registry();
+        assert_eq!(registry.platforms_of(DEFAULT_BOT_ID), [MOCK_PLATFORM]);
+
+        let mut changes = registry.subscribe();
+        registry.register("other", Arc::new(MockBot::new("20002")) as BoxedBot);
+        assert_eq!(
+            changes.recv().await.unwrap(),
+            BotChange::Joined {
+                bot_id: "20002".into(),
+                platform: "other".into()
+            }
+        );
+        assert_eq!(registry.by_platform("other").len(), 1);
+
+        let mut ids = tester.replies(onebot::private_message(1, "list")).await[0]
+            .split(',')
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, ["10001", "20002"]);
+
+        assert!(registry.unregister("other", "20002").is_some());
+        assert!(matches!(
+            changes.recv().await.unwrap(),
+            BotChange::Left { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_mock_bot_scripted_responses() {
+        let bot = MockBot::default();
+        bot.respond("get_status", Ok(json!(1)))
+            .respond("get_status", Ok(json!(2)));
+
+        assert_eq!(bot.call_api("get_status", json!({})).await.unwrap(), 1);
+        assert_eq!(bot.call_api("get_status", json!({})).await.unwrap(), 2);
+        assert_eq!(bot.call_api("get_status", json!({})).await.unwrap(), 2);
+        assert_eq!(bot.call_api("other", json!({})).await.unwrap(), Value::Null);
+        assert_eq!(bot.api_calls().len(), 4);
+    }
+
+    // =========================================================================
+    // Handler priority
+    // =========================================================================
+
+    async fn guard() -> String {
+        "guard".to_string()
+    }
+
+    async fn audit() -> String {
+        "audit".to_string()
+    }
+
+    async fn chatter() -> String {
+        "chatter".to_string()
+    }
+
+    mod guarded_plugin {
+        use super::*;
+
+        define_plugin! {
+            name: "guarded",
+            handlers: [
+                on_message().priority(5).block().handler(guard),
+                on_message().priority(5).handler(audit),
+                on_message().handler(chatter),
+            ],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stop_propagation_spares_equal_priority_handlers() {
+        let tester = PluginTester::builder()
+            .plugin(&guarded_plugin::GUARDED_PLUGIN)
+            .build()
+            .await;
+
+        assert_eq!(
+            tester.replies(onebot::private_message(1, "hi")).await,
+            ["guard", "audit"]
+        );
+    }
+
+    // =========================================================================
+    // Conversations
+    // =========================================================================
+
+    /// Asks for a name after `signup` and greets with the answer.
+    async fn signup(event: Event<MessageEvent>, prompt: Prompt) -> Option<String> {
+        if event.get_plain_text() != "signup" {
+            return None;
+        }
+        Some(match prompt.ask("name?").await {
+            Ok(reply) => format!("hello {}", reply.get_plain_text()),
+            Err(e) => e.to_string(),
+        })
+    }
+
+    async fn seen() -> String {
+        "seen".to_string()
+    }
+
+    mod signup_plugin {
+        use super::*;
+
+        define_plugin! {
+            name: "signup",
+            handlers: [on_message().handler(signup)],
+            metadata: { priority: 10 },
+        }
+    }
+
+    mod seen_plugin {
+        use super::*;
+
+        define_plugin! {
+            name: "seen",
+            handlers: [on_message().handler(seen)],
+        }
+    }
+
+    async fn signup_tester(config: ConversationConfig) -> PluginTester {
+        PluginTester::builder()
+            .plugin(&signup_plugin::SIGNUP_PLUGIN)
+            .conversation_config(config)
+            .build()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_conversation_receives_next_message() {
+        let tester = PluginTester::builder()
+            .plugin(&signup_plugin::SIGNUP_PLUGIN)
+            .plugin(&seen_plugin::SEEN_PLUGIN)
+            .build()
+            .await;
+
+        // The waiting handler does not hold up the lower tier.
+        assert_eq!(
+            tester.replies(onebot::private_message(1, "signup")).await,
+            ["name?", "seen"]
+        );
+        // Other users still reach the plugins.
+        tester
+            .assert_reply(onebot::private_message(2, "bob"), "seen")
+            .await;
+        // The answer goes to the conversation, not to the plugins.
+        assert!(
+            tester
+                .dispatch(onebot::private_message(1, "alice"))
+                .await
+                .is_empty()
+        );
+
+        tester.until_calls(4).await;
+        assert_eq!(tester.bot().replies().last().unwrap(), "hello alice");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_conversation_timeout() {
+        let tester = signup_tester(ConversationConfig {
+            default_timeout_secs: 5,
+            ..Default::default()
+        })
+        .await;
+
+        tester
+            .assert_reply(onebot::private_message(1, "signup"), "name?")
+            .await;
+        tokio::time::sleep(Duration::from_secs(4)).await;
+        assert_eq!(tester.bot().call_count(), 1);
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert_eq!(
+            tester.bot().replies(),
+            ["name?", "timed out waiting for a reply"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_conversation_cancel_word() {
+        let tester = signup_tester(ConversationConfig::default()).await;
+
+        tester
+            .assert_reply(onebot::group_message(1, 2, "signup"), "name?")
+            .await;
+        tester
+            .assert_no_reply(onebot::group_message(1, 2, " Cancel "))
+            .await;
+
+        tester.until_calls(2).await;
+        assert_eq!(
+            tester.bot().replies(),
+            ["name?", "conversation cancelled by the user"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_conversation_limit_per_user() {
+        let tester = signup_tester(ConversationConfig::default()).await;
+
+        tester
+            .assert_reply(onebot::group_message(1, 2, "signup"), "name?")
+            .await;
+        // The same user in another group is not an answer, and may not open
+        // a second conversation.
+        assert_eq!(
+            tester.replies(onebot::group_message(3, 2, "signup")).await,
+            [
+                "name?",
+                "too many open conversations for this user (limit 1)"
+            ]
+        );
+
+        tester
+            .assert_no_reply(onebot::group_message(1, 2, "alice"))
+            .await;
+        tester.until_calls(4).await;
+        assert_eq!(tester.bot().replies().last().unwrap(), "hello alice");
+    }
+}
@@ -13,13 +13,38 @@ use alloy_core::{
     TransportResult,
 };
 
+/// Routes the client through the proxy selected for `config.api_url`.
+///
+/// The choice is made here rather than by reqwest, so that the explicit
+/// settings, the environment and the no-proxy list follow the same rules as
+/// in the WebSocket client.
+fn with_proxy(
+    builder: ClientBuilder,
+    bot_id: &str,
+    config: &HttpClientConfig,
+) -> TransportResult<ClientBuilder> {
+    let url = reqwest::Url::parse(&config.api_url)
+        .map_err(|e| TransportError::InvalidConfig(format!("{}: {e}", config.api_url)))?;
+    let host = url.host_str().unwrap_or_default();
+    let proxy = crate::proxy::select(&config.proxy, url.scheme(), host)
+        .map_err(|e| TransportError::InvalidConfig(e.to_string()))?;
+    let Some(proxy) = proxy else {
+        return Ok(builder.no_proxy());
+    };
+
+    info!(bot_id = %bot_id, proxy = %proxy.host, "Using proxy for HTTP API");
+    let proxy = reqwest::Proxy::all(proxy.url())
+        .map_err(|e| TransportError::InvalidConfig(e.to_string()))?;
+    Ok(builder.proxy(proxy))
+}
+
 /// Registers an HTTP outbound API-client bot.
 ///
 /// The returned [`ConnectionHandle`] lets the bot send JSON API calls; it does
 /// not receive events through this connection.
 ///
-/// This function is registered as the `HttpStartClientFn` capability.
-#[register_capability(http_client)]
+/// This function is registered as the [`HttpClient`](alloy_core::HttpClient) capability.
+#[register_capability(alloy_core::HttpClient)]
 pub async fn http_start_client(
     bot_id: String,
     config: HttpClientConfig,
@@ -27,11 +52,26 @@ pub async fn http_start_client(
 ) -> TransportResult<ConnectionHandle> {
     info!(bot_id = %bot_id, url = %config.api_url, "Registering HTTP API client bot");
 
-    let client = ClientBuilder::new()
-        .timeout(config.timeout)
+    let mut builder = ClientBuilder::new().timeout(config.timeout);
+    if let Some(path) = &config.unix_socket {
+        #[cfg(unix)]
+        {
+            info!(bot_id = %bot_id, socket = %path.display(), "Using Unix socket for HTTP API");
+            builder = builder.unix_socket(path.clone());
+        }
+        #[cfg(not(unix))]
+        return Err(TransportError::InvalidConfig(format!(
+            "Unix socket {} is not supported on this platform",
+            path.display()
+        )));
+    } else {
+        builder = with_proxy(builder, &bot_id, &config)?;
+    }
+    let client = builder
         .build()
         .map_err(|e| TransportError::Io(e.to_string()))?;
-    let post_json: PostJsonFn = Arc::new(move |body| {
+    let call_timeout = config.timeout;
+    let post_json: PostJsonFn = Arc::new(move |body, timeout| {
         let client = client.clone();
         let url = config.api_url.clone();
         let token = config.access_token.clone();
@@ -40,10 +80,10 @@ pub async fn http_start_client(
             if let Some(t) = &token {
                 req = req.bearer_auth(t);
             }
-            let resp = req
-                .send()
-                .await
-                .map_err(|e| TransportError::Io(e.to_string()))?;
+            if let Some(timeout) = timeout {
+                req = req.timeout(timeout);
+            }
+            let resp = req.send().await.map_err(request_error)?;
             let status = resp.status();
             if !status.is_success() {
                 let text = resp.text().await.unwrap_or_default();
@@ -53,18 +93,118 @@ pub async fn http_start_client(
                     text
                 )));
             }
-            resp.json()
-                .await
-                .map_err(|e| TransportError::Io(e.to_string()))
+            resp.json().await.map_err(request_error)
         }
         .boxed()
     });
 
     let shutdown_token = CancellationToken::new();
-    let connection = ConnectionHandle::new_http_client(&bot_id, post_json, shutdown_token.clone());
+    let connection = ConnectionHandle::new_http_client(&bot_id, post_json, shutdown_token.clone())
+        .with_call_timeout(call_timeout);
 
     handler.create_bot(&bot_id, connection.clone());
 
     info!(bot_id = %bot_id, "HTTP API client bot registered");
     Ok(connection)
 }
+
+/// Converts a failed request, telling timeouts and requests that never got
+/// through to the server apart from other failures.
+fn request_error(e: reqwest::Error) -> TransportError {
+    if e.is_timeout() {
+        TransportError::Timeout
+    } else if e.is_connect() {
+        TransportError::ConnectionFailed {
+            url: e.url().map(ToString::to_string).unwrap_or_default(),
+            reason: e.to_string(),
+        }
+    } else {
+        TransportError::Io(e.to_string())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use alloy_core::{ConnectionInfo, ConnectionKind, Frame};
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use serde_json::json;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixListener;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct TestHandler {
+        created: Mutex<Vec<ConnectionHandle>>,
+    }
+
+    #[async_trait]
+    impl ConnectionHandler for TestHandler {
+        fn get_bot_id(&self, _conn_info: ConnectionInfo) -> TransportResult<String> {
+            unreachable!("HTTP client bots are created with a known ID")
+        }
+
+        fn create_bot(&self, _bot_id: &str, connection: ConnectionHandle) {
+            self.created.lock().push(connection);
+        }
+
+        async fn on_message(&self, _bot_id: &str, _frame: &Frame) {}
+
+        async fn on_disconnect(&self, _connection: &ConnectionHandle) {}
+    }
+
+    #[tokio::test]
+    async fn test_unix_socket() {
+        let path =
+            std::env::temp_dir().join(format!("alloy-http-client-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(stream.read_u8().await.unwrap());
+            }
+            let head = String::from_utf8(request).unwrap();
+            let length: usize = head
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length: "))
+                .unwrap()
+                .parse()
+                .unwrap();
+            let mut body = vec![0; length];
+            stream.read_exact(&mut body).await.unwrap();
+
+            let reply = r#"{"retcode":0,"data":null}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+                reply.len()
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            (head, body)
+        });
+
+        let handler = Arc::new(TestHandler::default());
+        let config = HttpClientConfig::new("http://localhost/send_msg")
+            .with_token("secret")
+            .with_unix_socket(&path);
+        let connection = http_start_client("10001".into(), config, handler.clone())
+            .await
+            .unwrap();
+        assert_eq!(handler.created.lock().len(), 1);
+
+        let ConnectionKind::HttpClient { post_json } = &connection.kind else {
+            panic!("expected an HTTP client connection");
+        };
+        let reply = post_json(json!({ "message": "hi" }), None).await.unwrap();
+        assert_eq!(reply["retcode"], 0);
+
+        let (head, body) = server.await.unwrap();
+        assert!(head.starts_with("POST /send_msg HTTP/1.1"), "{head}");
+        assert!(head.contains("authorization: Bearer secret"), "{head}");
+        assert_eq!(body, br#"{"message":"hi"}"#);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -4,7 +4,8 @@
 //!
 //! This crate provides concrete implementations of transport capabilities registered through
 //! `#[register_capability(...)]` attribute macros. These implementations are automatically
-//! discovered and collected by `TransportContext::collect_all()` at runtime.
+//! discovered and collected by `TransportContext::collect()` at runtime, under the
+//! provider name `alloy-transport`.
 //!
 //! ## Features
 //!
@@ -12,6 +13,12 @@
 //! - `ws-server`: WebSocket server capability
 //! - `http-client`: HTTP client capability
 //! - `http-server`: HTTP server capability
+//! - `loopback`: In-process loopback capability (no network)
+//!
+//! Both clients honor the proxy settings of their config (HTTP `CONNECT`,
+//! SOCKS5 and SOCKS5h), falling back to `HTTPS_PROXY` / `HTTP_PROXY` /
+//! `ALL_PROXY` / `NO_PROXY` from the environment.
+//! - `tls`: TLS listeners for the servers (rustls); implied by `ws-client`
 //! - `full`: All capabilities
 //!
 //! ## Architecture
@@ -22,7 +29,7 @@
 //! │  Calls: ws_connect(), http_start_client(), etc.
 //! ├─────────────────────────────────────────┤
 //! │  alloy-core TransportContext            │
-//! │  Holds: Arc<dyn Fn(...) -> BoxFuture>   │
+//! │  Holds: Capability::Fn by name          │
 //! ├─────────────────────────────────────────┤
 //! │  alloy-transport async fn implementations
 //! │  Registered via #[register_capability] macro
@@ -33,12 +40,13 @@
 //!
 //! ## Capability Implementations
 //!
-//! | Function | Feature | Request Type | Response Type |
-//! |----------|---------|--------------|---------------|
-//! | `ws_connect()` | `ws-client` | `(url, handler, config)` | `ConnectionHandle` |
-//! | `ws_listen()` | `ws-server` | `(addr, path, handler)` | `ListenerHandle` |
-//! | `http_start_client()` | `http-client` | `(bot_id, api_url, token, handler)` | `ConnectionHandle` |
-//! | `http_listen()` | `http-server` | `(addr, path, handler)` | `ListenerHandle` |
+//! | Function | Capability | Feature | Request Type | Response Type |
+//! |----------|------------|---------|--------------|---------------|
+//! | `ws_connect()` | `WsClient` | `ws-client` | `(config, handler)` | `ConnectionHandle` |
+//! | `ws_listen()` | `WsServer` | `ws-server` | `(config, handler)` | `ListenerHandle` |
+//! | `http_start_client()` | `HttpClient` | `http-client` | `(bot_id, config, handler)` | `ConnectionHandle` |
+//! | `http_listen()` | `HttpServer` | `http-server` | `(config, handler)` | `ListenerHandle` |
+//! | `loopback_connect()` | `Loopback` | `loopback` | `(conn_info, handler)` | `(ConnectionHandle, LoopbackPeer)` |
 //!
 //! All capabilities are automatically discovered via `linkme::distributed_slice` registration
 //! and collected into a [`TransportContext`] at startup.
@@ -56,6 +64,18 @@ mod http_client;
 #[cfg(feature = "ws-client")]
 mod ws_client;
 
+#[cfg(feature = "loopback")]
+mod loopback;
+
+#[cfg(any(feature = "http-client", feature = "ws-client"))]
+mod proxy;
+
+#[cfg(all(
+    feature = "tls",
+    any(feature = "http-server", feature = "ws-server", feature = "ws-client")
+))]
+mod tls;
+
 // ─── Capability re-exports ───────────────────────────────────────────────────
 // Server capabilities (all from crate::server module)
 #[cfg(feature = "http-server")]
@@ -70,3 +90,6 @@ pub use http_client::http_start_client;
 
 #[cfg(feature = "ws-client")]
 pub use ws_client::ws_connect;
+
+#[cfg(feature = "loopback")]
+pub use loopback::loopback_connect;
@@ -0,0 +1,159 @@
+//! In-process loopback transport.
+//!
+//! Connects a [`ConnectionHandler`] to a [`LoopbackPeer`] through a pair of
+//! channels — no sockets, no global server registry.  Useful for integration
+//! tests and for embedding a protocol implementation in the same binary.
+//!
+//! ```rust,ignore
+//! let loopback = ctx.transport().get::<Loopback>().unwrap();
+//! let info = ConnectionInfo::new("loopback").with_metadata("x-self-id", "10001");
+//! let (_handle, mut peer) = loopback(info, ctx.clone().as_connection_handler()).await?;
+//!
+//! peer.send(r#"{"post_type":"meta_event",...}"#).await?;
+//! let api_call = peer.recv().await;
+//! ```
+
+use std::sync::Arc;
+
+use alloy_core::{
+    ConnectionHandle, ConnectionHandler, ConnectionInfo, Frame, LoopbackPeer, TransportResult,
+};
+use alloy_macros::register_capability;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+/// Frames buffered in each direction before senders wait.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// Opens a loopback connection.
+///
+/// Identifies the bot from `conn_info`, registers it with `handler`, and
+/// spawns a task feeding the peer's frames to
+/// [`ConnectionHandler::on_message`].  The bot is disconnected when either
+/// side closes, or after the peer sends a close frame.
+///
+/// This function is registered as the [`Loopback`](alloy_core::Loopback) capability.
+#[register_capability(alloy_core::Loopback)]
+pub async fn loopback_connect(
+    conn_info: ConnectionInfo,
+    handler: Arc<dyn ConnectionHandler>,
+) -> TransportResult<(ConnectionHandle, LoopbackPeer)> {
+    let bot_id = handler.get_bot_id(conn_info)?;
+
+    let (inbound_tx, mut inbound_rx) = mpsc::channel::<Frame>(CHANNEL_CAPACITY);
+    let (outbound_tx, outbound_rx) = mpsc::channel::<Frame>(CHANNEL_CAPACITY);
+    let shutdown_token = CancellationToken::new();
+
+    let handle = ConnectionHandle::new_ws(bot_id.clone(), outbound_tx, shutdown_token.clone());
+    let peer = LoopbackPeer::new(inbound_tx, outbound_rx, shutdown_token.clone());
+
+    handler.create_bot(&bot_id, handle.clone());
+    info!(bot_id = %bot_id, conn_id = handle.conn_id(), "Loopback connection opened");
+
+    let connection = handle.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                // Deliver frames the peer queued before closing.
+                biased;
+                frame = inbound_rx.recv() => match frame {
+                    Some(frame) => {
+                        handler.on_message(&bot_id, &frame).await;
+                        if frame.is_close() {
+                            break;
+                        }
+                    }
+                    None => break,
+                },
+                _ = shutdown_token.cancelled() => break,
+            }
+        }
+        // Whichever side ended the loop, make the other one see it.
+        shutdown_token.cancel();
+        debug!(bot_id = %bot_id, "Loopback connection closed");
+        handler.on_disconnect(&connection).await;
+    });
+
+    Ok((handle, peer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_core::{ConnectionKind, TransportError};
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+
+    /// Records everything the transport reports.
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ConnectionHandler for Recorder {
+        fn get_bot_id(&self, conn_info: ConnectionInfo) -> TransportResult<String> {
+            conn_info.metadata.get("x-self-id").cloned().ok_or_else(|| {
+                TransportError::BotIdMissing {
+                    reason: "no x-self-id".into(),
+                }
+            })
+        }
+
+        fn create_bot(&self, bot_id: &str, _connection: ConnectionHandle) {
+            self.events.lock().push(format!("create {bot_id}"));
+        }
+
+        async fn on_message(&self, bot_id: &str, frame: &Frame) {
+            let event = match frame {
+                Frame::Text(text) => format!("{bot_id}: {text}"),
+                Frame::Binary(data) => format!("{bot_id}: {data:?}"),
+                Frame::Close { code, .. } => format!("{bot_id}: close {code}"),
+            };
+            self.events.lock().push(event);
+        }
+
+        async fn on_disconnect(&self, connection: &ConnectionHandle) {
+            self.events
+                .lock()
+                .push(format!("disconnect {}", connection.id));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_loopback_round_trip() {
+        let recorder = Arc::new(Recorder::default());
+        let info = ConnectionInfo::new("loopback").with_metadata("x-self-id", "42");
+        let (handle, mut peer) = loopback_connect(info, recorder.clone()).await.unwrap();
+
+        peer.send("ping").await.unwrap();
+        peer.send(vec![0x80, 0x01]).await.unwrap();
+        let ConnectionKind::Ws { message_tx } = &handle.kind else {
+            panic!("expected a ws handle");
+        };
+        message_tx.send(Frame::Binary(vec![0xff])).await.unwrap();
+        assert_eq!(peer.recv().await, Some(Frame::Binary(vec![0xff])));
+
+        peer.send(Frame::close(Frame::CLOSE_NORMAL, "bye"))
+            .await
+            .unwrap();
+        for _ in 0..100 {
+            if recorder.events.lock().len() == 5 {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(
+            *recorder.events.lock(),
+            [
+                "create 42",
+                "42: ping",
+                "42: [128, 1]",
+                "42: close 1000",
+                "disconnect 42"
+            ]
+        );
+        assert!(peer.is_closed());
+    }
+}
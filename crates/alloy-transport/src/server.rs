@@ -21,43 +21,135 @@
 //! ├── POST /webhook  → HttpRouteHandler (Adapter A events)
 //! └── POST /events   → HttpRouteHandler (Adapter B events)
 //! ```
+//!
+//! ## Unix domain sockets
+//!
+//! On Unix, an address of the form `unix:<path>` binds a Unix socket instead
+//! of a TCP port, e.g. `unix:/run/alloy/onebot.sock`.  The socket file is
+//! created with mode `0660` unless the listener's config sets another
+//! `socket_mode`.  A stale
+//! socket left behind by a crashed process is removed before binding; a socket
+//! another process is still serving is not.  The file is removed again when
+//! the server stops.
+//!
+//! ## TLS
+//!
+//! With the `tls` feature, a listener on a TCP address can be given a
+//! certificate chain and private key in the `tls` field of its config
+//! ([`ServerTlsConfig`]).  Every route on that address is then served over
+//! TLS (`https://`, `wss://`); asking for TLS on a Unix socket is an error.  TLS settings belong to the bind address, so all listeners
+//! sharing it must pass the same files.  Changed files are picked up within
+//! ten seconds, without dropping connections.
+//!
+//! ## WebSocket connections
+//!
+//! Every WebSocket listener applies its [`WsServerConfig`]: clients are
+//! pinged every `ping_interval` and dropped if they stay silent for
+//! `pong_timeout` after a ping, so a half-open TCP connection does not keep
+//! its bot registered.  Frames the bot sends wait in a queue of
+//! `send_queue_capacity` frames; when a slow client lets it fill up, the
+//! [`SendOverflow`] policy decides whether the bot waits, the oldest frame
+//! is dropped, or the client is disconnected.
+//!
+//! [`WsServerConfig`]: alloy_core::WsServerConfig
+//! [`SendOverflow`]: alloy_core::SendOverflow
+//! [`ServerTlsConfig`]: alloy_core::ServerTlsConfig
+//!
+//! ## HTTP responses
+//!
+//! Each POST is handed to [`ConnectionHandler::on_request`] with a
+//! [`ResponseSlot`](alloy_core::ResponseSlot).  The request stays open until
+//! a handler fills the slot (answered with `200` and the JSON body), every
+//! copy of the slot is dropped, or the listener's
+//! [`response_deadline`](alloy_core::HttpServerConfig::response_deadline)
+//! passes (both answered with `204`).
+//!
+//! ## Server-sent events
+//!
+//! A plain `GET` on an HTTP path opens an SSE stream of the frames the bot's
+//! adapter sends to its [`ConnectionKind::HttpServer`] connection, which makes
+//! HTTP-only deployments bidirectional.  The subscriber is identified like a
+//! POST (`authenticate`, then `get_bot_id`), with `protocol` set to `"sse"`.
+//! Every frame carries an increasing event ID; a subscriber reconnecting with
+//! `Last-Event-ID` first receives the frames it missed, as far as the last
+//! 256 frames go.  Subscribers connecting without it only see new frames.
+//!
+//! [`ConnectionKind::HttpServer`]: alloy_core::ConnectionKind::HttpServer
 
-use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::collections::{HashMap, VecDeque};
+use std::future::IntoFuture;
+use std::io;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, LazyLock, Weak};
+#[cfg(feature = "ws-server")]
+use std::time::Duration;
 
 use axum::{
     Router,
-    extract::{ConnectInfo, State},
+    extract::{ConnectInfo, Query, State, connect_info::Connected},
     http::{HeaderMap, StatusCode, Uri},
-    response::IntoResponse,
+    response::{IntoResponse, Response},
+    routing::get,
+    serve::IncomingStream,
 };
+use futures::StreamExt;
+use futures::future::BoxFuture;
 use parking_lot::{Mutex, RwLock};
 use tokio::net::TcpListener;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, trace, warn};
 
 use alloy_core::{
-    ConnectionHandle, ConnectionHandler, ConnectionInfo, ListenerHandle, TransportResult,
+    ConnectionHandle, ConnectionHandler, ConnectionInfo, ListenerHandle, ServerTlsConfig,
+    TransportResult,
 };
 use alloy_macros::register_capability;
 
+#[cfg(feature = "tls")]
+use crate::tls::{ReloadingCert, TlsListener};
+
 #[cfg(feature = "http-server")]
-use axum::{body::Bytes, response::Response, routing::post};
+use {
+    alloy_core::{HttpServerConfig, ResponseSlot},
+    axum::{
+        body::Bytes,
+        http::header::CONTENT_TYPE,
+        response::sse::{Event as SseEvent, KeepAlive, Sse},
+        routing::post,
+    },
+    std::convert::Infallible,
+    tokio::sync::broadcast,
+};
+
+/// Frames kept per HTTP bot for SSE subscribers resuming with `Last-Event-ID`.
+#[cfg(feature = "http-server")]
+const SSE_REPLAY_CAPACITY: usize = 256;
 
 #[cfg(feature = "ws-server")]
 use {
-    axum::{
-        extract::{
-            WebSocketUpgrade,
-            ws::{Message, WebSocket},
-        },
-        routing::get,
+    alloy_core::{Frame, SendOverflow, WsServerConfig},
+    axum::extract::{
+        WebSocketUpgrade,
+        ws::{CloseFrame, Message, WebSocket, rejection::WebSocketUpgradeRejection},
     },
-    futures::{SinkExt, StreamExt},
+    futures::{
+        SinkExt,
+        stream::{SplitSink, SplitStream},
+    },
+    std::{
+        net::{IpAddr, SocketAddr},
+        sync::atomic::{AtomicBool, Ordering},
+    },
+    tokio::{sync::Notify, time::Instant},
 };
 
+/// How long a connection closed by the server waits for its close frame to
+/// be written.
+#[cfg(feature = "ws-server")]
+const CLOSE_GRACE: Duration = Duration::from_secs(1);
+
 // ─── Route handlers (concrete types, no trait objects) ────────────────────────
 
 /// Handles all POST events for a single registered HTTP path.
@@ -67,18 +159,49 @@ use {
 #[cfg(feature = "http-server")]
 struct HttpBotHandler {
     handler: Arc<dyn ConnectionHandler>,
-    known_bots: Mutex<HashMap<String, ConnectionHandle>>,
+    config: HttpServerConfig,
+    /// Known bots: bot_id → the SSE channel their outgoing frames go to.
+    known_bots: Mutex<HashMap<String, Arc<PushChannel>>>,
+}
+
+/// Fans one HTTP bot's outgoing frames out to its SSE subscribers.
+#[cfg(feature = "http-server")]
+struct PushChannel {
+    /// Recent frames for replay; the lock also orders publishing against
+    /// subscribing, so no frame falls between replay and live delivery.
+    history: Mutex<PushHistory>,
+    live: broadcast::Sender<SseFrame>,
+}
+
+/// An SSE frame: `(event ID, data)`.
+#[cfg(feature = "http-server")]
+type SseFrame = (u64, Arc<str>);
+
+#[cfg(feature = "http-server")]
+struct PushHistory {
+    next_id: u64,
+    /// Oldest first.
+    frames: VecDeque<SseFrame>,
 }
 
 /// Handles all WebSocket connections arriving at a single registered path.
 ///
-/// Maintains an active-connection table (bot_id → send channel) so that the
-/// adapter's [`ConnectionHandler`] can address individual bots by ID.
+/// Counts open connections by connection rather than bot, since one bot may
+/// connect more than once, and applies the listener's [`WsServerConfig`] to
+/// each of them.
 #[cfg(feature = "ws-server")]
 struct WsBotHandler {
     handler: Arc<dyn ConnectionHandler>,
-    /// Active connections: bot_id → sender half of the outgoing message channel.
-    connections: Mutex<HashMap<String, mpsc::Sender<Vec<u8>>>>,
+    config: WsServerConfig,
+    /// Connections holding a [`ConnectionSlot`].
+    open: Mutex<OpenConnections>,
+}
+
+#[cfg(feature = "ws-server")]
+#[derive(Default)]
+struct OpenConnections {
+    total: usize,
+    per_ip: HashMap<IpAddr, usize>,
 }
 
 // ─── Shared runtime state (one per bound address) ───────────────────────────────
@@ -121,11 +244,184 @@ struct ServerEntry {
     state: Arc<SharedState>,
     /// Cancellation token for graceful shutdown.
     shutdown_token: CancellationToken,
+    /// Certificate files, for TLS servers.
+    tls: Option<ServerTlsConfig>,
+    /// Socket file to remove on shutdown, for Unix socket servers.
+    #[cfg(unix)]
+    socket_path: Option<PathBuf>,
 }
 
 impl Drop for ServerEntry {
     fn drop(&mut self) {
         self.shutdown_token.cancel();
+        #[cfg(unix)]
+        if let Some(path) = &self.socket_path
+            && let Err(e) = std::fs::remove_file(path)
+        {
+            warn!(path = %path.display(), error = %e, "Failed to remove socket file");
+        }
+    }
+}
+
+// ─── Bind addresses ───────────────────────────────────────────────────────────
+
+/// Permissions of a Unix socket file unless the listener overrides them.
+#[cfg(unix)]
+const DEFAULT_SOCKET_MODE: u32 = 0o660;
+
+/// Where and how a server listens, from a listener's config.
+#[derive(Debug)]
+enum BindAddr {
+    /// `host:port`, optionally with TLS.
+    Tcp {
+        addr: String,
+        tls: Option<ServerTlsConfig>,
+    },
+    /// `unix:<path>`.
+    #[cfg(unix)]
+    Unix { path: PathBuf, mode: u32 },
+}
+
+impl BindAddr {
+    /// Combines `addr` with the TLS and socket settings of its listener,
+    /// refusing settings that do not apply to that kind of address.
+    fn new(addr: &str, tls: Option<&ServerTlsConfig>, mode: Option<u32>) -> io::Result<Self> {
+        let invalid = |reason: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid listener on `{addr}`: {reason}"),
+            )
+        };
+
+        match addr.strip_prefix("unix:") {
+            None => {
+                if mode.is_some() {
+                    return Err(invalid("a socket mode only applies to Unix sockets"));
+                }
+                Ok(BindAddr::Tcp {
+                    addr: addr.to_string(),
+                    tls: tls.cloned(),
+                })
+            }
+
+            Some(_) if tls.is_some() => Err(invalid("TLS is not supported on Unix sockets")),
+
+            #[cfg(unix)]
+            Some(path) => Ok(BindAddr::Unix {
+                path: PathBuf::from(path),
+                mode: mode.unwrap_or(DEFAULT_SOCKET_MODE),
+            }),
+
+            #[cfg(not(unix))]
+            Some(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("Unix socket address `{addr}` is not supported on this platform"),
+            )),
+        }
+    }
+
+    /// TLS settings requested for this address.
+    fn tls(&self) -> Option<&ServerTlsConfig> {
+        match self {
+            BindAddr::Tcp { tls, .. } => tls.as_ref(),
+            #[cfg(unix)]
+            BindAddr::Unix { .. } => None,
+        }
+    }
+}
+
+/// Removes `path` if it is a socket nobody is listening on.
+///
+/// Fails with [`io::ErrorKind::AddrInUse`] if a live server owns the socket,
+/// and refuses to touch anything that is not a socket.
+#[cfg(unix)]
+async fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    if !metadata.file_type().is_socket() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists and is not a socket", path.display()),
+        ));
+    }
+    match tokio::net::UnixStream::connect(path).await {
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("{} is in use by another process", path.display()),
+        )),
+        Err(_) => {
+            info!(path = %path.display(), "Removing stale socket file");
+            std::fs::remove_file(path)
+        }
+    }
+}
+
+/// Binds a Unix socket at `path` that is never reachable with other
+/// permissions than `mode`.
+///
+/// Binding creates the socket file with the process umask applied, so it is
+/// created in a fresh owner-only directory next to `path`, given `mode` there
+/// and only then renamed into place.
+#[cfg(unix)]
+fn bind_private(path: &Path, mode: u32) -> io::Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    static NEXT_DIR: AtomicU32 = AtomicU32::new(0);
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let dir = parent.join(format!(
+        ".alloy-{}-{}",
+        std::process::id(),
+        NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::DirBuilder::new().mode(0o700).create(&dir)?;
+    let staged = dir.join("s");
+    let bound = tokio::net::UnixListener::bind(&staged).and_then(|listener| {
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(mode))?;
+        std::fs::rename(&staged, path)?;
+        Ok(listener)
+    });
+    if bound.is_err() {
+        let _ = std::fs::remove_file(&staged);
+    }
+    let _ = std::fs::remove_dir(&dir);
+    bound
+}
+
+/// Peer address made available to handlers, for TCP and Unix listeners alike.
+#[derive(Clone)]
+struct RemoteAddr(String);
+
+impl Connected<IncomingStream<'_, TcpListener>> for RemoteAddr {
+    fn connect_info(stream: IncomingStream<'_, TcpListener>) -> Self {
+        Self(stream.remote_addr().to_string())
+    }
+}
+
+#[cfg(feature = "tls")]
+impl Connected<IncomingStream<'_, TlsListener>> for RemoteAddr {
+    fn connect_info(stream: IncomingStream<'_, TlsListener>) -> Self {
+        Self(stream.remote_addr().to_string())
+    }
+}
+
+#[cfg(unix)]
+impl Connected<IncomingStream<'_, tokio::net::UnixListener>> for RemoteAddr {
+    fn connect_info(stream: IncomingStream<'_, tokio::net::UnixListener>) -> Self {
+        // Clients rarely bind their end, so there is usually no path.
+        match stream.remote_addr().as_pathname() {
+            Some(path) => Self(format!("unix:{}", path.display())),
+            None => Self("unix".to_string()),
+        }
     }
 }
 
@@ -138,49 +434,163 @@ static SERVER_REGISTRY: LazyLock<Mutex<HashMap<String, Weak<ServerEntry>>>> =
 
 /// Returns the live [`ServerEntry`] for `addr`, creating one if needed.
 ///
-/// The first call for a given address binds the TCP listener and spawns the
-/// axum serve loop.  Subsequent calls for the same address (while the first
-/// `Arc<ServerEntry>` is still live) re-use the existing server; only route
-/// table entries are added.
-async fn get_or_create_server(addr: &str) -> std::io::Result<Arc<ServerEntry>> {
+/// The first call for a given address binds the TCP (or Unix) listener and
+/// spawns the axum serve loop.  Subsequent calls for the same address (while
+/// the first `Arc<ServerEntry>` is still live) re-use the existing server; only
+/// route table entries are added.
+///
+/// The registry is keyed by the address; a second caller asking for
+/// different TLS settings on a live address is refused.
+async fn get_or_create_server(
+    addr: &str,
+    tls: Option<&ServerTlsConfig>,
+    mode: Option<u32>,
+) -> io::Result<Arc<ServerEntry>> {
+    let bind = BindAddr::new(addr, tls, mode)?;
+    let key = addr;
+
     // ── Fast path: server already exists ──────────────────────────────────────
     {
         let registry = SERVER_REGISTRY.lock();
-        if let Some(weak) = registry.get(addr)
+        if let Some(weak) = registry.get(key)
             && let Some(entry) = weak.upgrade()
         {
+            if entry.tls.as_ref() != bind.tls() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{key} is already served with different TLS settings"),
+                ));
+            }
             return Ok(entry);
         }
     }
 
     // ── Slow path: bind a new listener and start serving ──────────────────────
     let state = Arc::new(SharedState::new());
-    let listener = TcpListener::bind(addr).await?;
-    let actual_addr = listener.local_addr()?;
-    let actual_addr_str = actual_addr.to_string();
-
     let router = build_router(state.clone());
     let shutdown_token = CancellationToken::new();
 
+    let (server, actual_addr, tls) = match bind {
+        BindAddr::Tcp { addr, tls } => {
+            let listener = TcpListener::bind(&addr).await?;
+            let actual_addr = listener.local_addr()?.to_string();
+            let server: BoxFuture<'static, io::Result<()>> = match &tls {
+                None => Box::pin(
+                    axum::serve(
+                        listener,
+                        router.into_make_service_with_connect_info::<RemoteAddr>(),
+                    )
+                    .into_future(),
+                ),
+                Some(files) => serve_tls(listener, files, router, shutdown_token.clone())?,
+            };
+            (server, actual_addr, tls)
+        }
+        #[cfg(unix)]
+        BindAddr::Unix { path, mode } => {
+            return bind_unix(key, path, mode, state, router, shutdown_token).await;
+        }
+    };
+
     let entry = Arc::new(ServerEntry {
-        actual_addr: actual_addr_str.clone(),
+        actual_addr: actual_addr.clone(),
         state: state.clone(),
         shutdown_token: shutdown_token.clone(),
+        tls,
+        #[cfg(unix)]
+        socket_path: None,
     });
 
     // Store a weak reference so the registry does not prevent cleanup.
     {
         let mut registry = SERVER_REGISTRY.lock();
-        registry.insert(addr.to_string(), Arc::downgrade(&entry));
+        registry.insert(key.to_string(), Arc::downgrade(&entry));
     }
 
-    debug!(addr = %actual_addr, "Shared TCP server started");
+    debug!(addr = %actual_addr, tls = entry.tls.is_some(), "Shared TCP server started");
 
-    tokio::spawn(async move {
-        let server = axum::serve(
+    spawn_server(server, actual_addr, shutdown_token);
+
+    Ok(entry)
+}
+
+/// Binds a Unix socket server for [`get_or_create_server`], registering it
+/// under `key`.
+#[cfg(unix)]
+async fn bind_unix(
+    key: &str,
+    path: PathBuf,
+    mode: u32,
+    state: Arc<SharedState>,
+    router: Router,
+    shutdown_token: CancellationToken,
+) -> io::Result<Arc<ServerEntry>> {
+    remove_stale_socket(&path).await?;
+    let listener = bind_private(&path, mode)?;
+    let actual_addr = format!("unix:{}", path.display());
+
+    let entry = Arc::new(ServerEntry {
+        actual_addr: actual_addr.clone(),
+        state,
+        shutdown_token: shutdown_token.clone(),
+        tls: None,
+        socket_path: Some(path),
+    });
+    SERVER_REGISTRY
+        .lock()
+        .insert(key.to_string(), Arc::downgrade(&entry));
+
+    debug!(addr = %actual_addr, mode = format_args!("{mode:o}"), "Shared Unix socket server started");
+
+    let server = axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<RemoteAddr>(),
+    )
+    .into_future();
+    spawn_server(server, actual_addr, shutdown_token);
+
+    Ok(entry)
+}
+
+/// Serves `router` over TLS on `listener`, with the certificate in `files`.
+#[cfg(feature = "tls")]
+fn serve_tls(
+    listener: TcpListener,
+    files: &ServerTlsConfig,
+    router: Router,
+    shutdown_token: CancellationToken,
+) -> io::Result<BoxFuture<'static, io::Result<()>>> {
+    let cert = Arc::new(ReloadingCert::load(&files.cert, &files.key)?);
+    let listener = TlsListener::new(listener, cert, shutdown_token)?;
+    Ok(Box::pin(
+        axum::serve(
             listener,
-            router.into_make_service_with_connect_info::<SocketAddr>(),
-        );
+            router.into_make_service_with_connect_info::<RemoteAddr>(),
+        )
+        .into_future(),
+    ))
+}
+
+#[cfg(not(feature = "tls"))]
+fn serve_tls(
+    _listener: TcpListener,
+    _files: &ServerTlsConfig,
+    _router: Router,
+    _shutdown_token: CancellationToken,
+) -> io::Result<BoxFuture<'static, io::Result<()>>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "TLS listeners need the `tls` feature of alloy-transport",
+    ))
+}
+
+/// Drives `server` until it fails or `shutdown_token` is cancelled.
+fn spawn_server(
+    server: impl Future<Output = io::Result<()>> + Send + 'static,
+    actual_addr: String,
+    shutdown_token: CancellationToken,
+) {
+    tokio::spawn(async move {
         tokio::select! {
             result = server => {
                 if let Err(e) = result {
@@ -192,8 +602,6 @@ async fn get_or_create_server(addr: &str) -> std::io::Result<Arc<ServerEntry>> {
             }
         }
     });
-
-    Ok(entry)
 }
 
 // ─── Router construction ──────────────────────────────────────────────────────
@@ -201,7 +609,8 @@ async fn get_or_create_server(addr: &str) -> std::io::Result<Arc<ServerEntry>> {
 /// Builds the axum [`Router`] for this server.
 ///
 /// Routes are added conditionally:
-/// * `GET  /{*path}` and `GET  /` → [`ws_dispatch`]    (only with `ws-server`)
+/// * `GET  /{*path}` and `GET  /` → [`get_dispatch`]   (WebSocket upgrades and
+///   SSE subscriptions)
 /// * `POST /{*path}` and `POST /` → [`http_dispatch`]  (only with `http-server`)
 ///
 /// A fallback returns **404** for any method/path combination that has no
@@ -217,17 +626,35 @@ fn build_router(state: Arc<SharedState>) -> Router {
             .route("/", post(http_dispatch));
     }
 
-    // ── WebSocket (GET + Upgrade) ──────────────────────────────────────────────
-    #[cfg(feature = "ws-server")]
-    {
-        router = router
-            .route("/{*path}", get(ws_dispatch))
-            .route("/", get(ws_dispatch));
-    }
+    // ── GET (WebSocket upgrade or SSE) ─────────────────────────────────────────
+    router = router
+        .route("/{*path}", get(get_dispatch))
+        .route("/", get(get_dispatch));
 
     router.with_state(state)
 }
 
+/// Describes a request for the [`ConnectionHandler`]: remote address, path,
+/// query parameters and headers (as metadata).
+fn request_info(
+    protocol: &str,
+    addr: &str,
+    uri: &Uri,
+    query: HashMap<String, String>,
+    headers: &HeaderMap,
+) -> ConnectionInfo {
+    let mut conn_info = ConnectionInfo::new(protocol)
+        .with_remote_addr(addr)
+        .with_path(uri.path())
+        .with_query(query);
+    for (name, value) in headers {
+        if let Ok(v) = value.to_str() {
+            conn_info = conn_info.with_metadata(name.as_str(), v);
+        }
+    }
+    conn_info
+}
+
 // ─── HTTP dispatch ────────────────────────────────────────────────────────────
 
 /// Axum handler for HTTP POST requests.
@@ -237,8 +664,9 @@ fn build_router(state: Arc<SharedState>) -> Router {
 #[cfg(feature = "http-server")]
 async fn http_dispatch(
     State(state): State<Arc<SharedState>>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    ConnectInfo(RemoteAddr(addr)): ConnectInfo<RemoteAddr>,
     uri: Uri,
+    Query(query): Query<HashMap<String, String>>,
     headers: HeaderMap,
     body: Bytes,
 ) -> impl IntoResponse {
@@ -246,7 +674,10 @@ async fn http_dispatch(
     let handler = state.http_routes.read().get(&path).cloned();
 
     match handler {
-        Some(h) => h.handle(addr, headers, body).await,
+        Some(h) => {
+            let conn_info = request_info("http", &addr, &uri, query, &headers);
+            h.handle(addr, conn_info, body).await
+        }
         None => (
             StatusCode::NOT_FOUND,
             format!("No HTTP handler for path: {path}"),
@@ -255,40 +686,88 @@ async fn http_dispatch(
     }
 }
 
+// ─── GET dispatch ─────────────────────────────────────────────────────────────
+
+/// Axum handler for GET requests.
+///
+/// WebSocket upgrade requests go to [`ws_dispatch`].  Other requests to a
+/// registered HTTP path subscribe to its SSE stream; to a WebSocket-only path
+/// they are rejected as failed upgrades, and anywhere else with **404**.
+async fn get_dispatch(
+    State(state): State<Arc<SharedState>>,
+    ConnectInfo(RemoteAddr(addr)): ConnectInfo<RemoteAddr>,
+    uri: Uri,
+    Query(query): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    #[cfg(feature = "ws-server")] ws: Result<WebSocketUpgrade, WebSocketUpgradeRejection>,
+) -> Response {
+    #[cfg(feature = "ws-server")]
+    let ws_rejection = match ws {
+        Ok(ws) => return ws_dispatch(ws, state, addr, uri, query, headers),
+        Err(rejection) => rejection,
+    };
+
+    let path = uri.path().to_string();
+
+    #[cfg(feature = "http-server")]
+    {
+        let handler = state.http_routes.read().get(&path).cloned();
+        if let Some(h) = handler {
+            let conn_info = request_info("sse", &addr, &uri, query, &headers);
+            return h.subscribe(addr, conn_info);
+        }
+    }
+
+    #[cfg(feature = "ws-server")]
+    if state.ws_routes.read().contains_key(&path) {
+        return ws_rejection.into_response();
+    }
+
+    let _ = (query, headers);
+    (StatusCode::NOT_FOUND, format!("No handler for GET {path}")).into_response()
+}
+
 // ─── WebSocket dispatch ───────────────────────────────────────────────────────
 
-/// Axum handler for WebSocket upgrade requests.
+/// Handles a WebSocket upgrade request from [`get_dispatch`].
 ///
 /// Looks up the request path in `SharedState::ws_routes` and upgrades the
-/// connection, delegating the socket to the registered [`WsRouteHandler`].
-/// Non-WebSocket GET requests (without the upgrade header) receive **404**.
+/// connection, delegating the socket to the registered [`WsRouteHandler`],
+/// or returns **404** if none is found.  Requests the handler does not
+/// [`authenticate`](ConnectionHandler::authenticate) receive **401** and are
+/// never upgraded.
 #[cfg(feature = "ws-server")]
-async fn ws_dispatch(
+fn ws_dispatch(
     ws: WebSocketUpgrade,
-    State(state): State<Arc<SharedState>>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    state: Arc<SharedState>,
+    addr: String,
     uri: Uri,
+    query: HashMap<String, String>,
     headers: HeaderMap,
-) -> impl IntoResponse {
+) -> Response {
     let path = uri.path().to_string();
     let handler = state.ws_routes.read().get(&path).cloned();
 
     match handler {
         Some(h) => {
-            // Collect headers as a plain map (lowercase keys) before the move.
-            let metadata: HashMap<String, String> = headers
-                .iter()
-                .filter_map(|(name, value)| {
-                    value
-                        .to_str()
-                        .ok()
-                        .map(|v| (name.as_str().to_lowercase(), v.to_string()))
-                })
-                .collect();
+            let conn_info = request_info("websocket", &addr, &uri, query, &headers);
+            if let Err(e) = h.handler.authenticate(&conn_info) {
+                warn!(remote_addr = %addr, path = %path, error = %e, "Rejected WebSocket connection");
+                return (StatusCode::UNAUTHORIZED, e.to_string()).into_response();
+            }
+
+            let slot = match h.reserve(&addr) {
+                Ok(slot) => slot,
+                Err((status, reason)) => {
+                    warn!(remote_addr = %addr, path = %path, reason, "Rejected WebSocket connection");
+                    return (status, reason).into_response();
+                }
+            };
 
             debug!(remote_addr = %addr, path = %path, "New WebSocket connection request");
             ws.on_upgrade(move |socket| async move {
-                h.handle(addr, metadata, socket).await;
+                h.handle(addr, conn_info, socket).await;
+                drop(slot);
             })
             .into_response()
         }
@@ -304,28 +783,27 @@ async fn ws_dispatch(
 // HTTP SERVER CAPABILITY IMPLEMENTATION
 // ═════════════════════════════════════════════════════════════════════════════
 
-/// Starts (or re-uses) a TCP server on `addr` and registers a POST handler
-/// for `path`.
+/// Starts (or re-uses) a TCP or Unix socket server on `config.addr` and registers a POST
+/// handler for `config.path`.
 ///
 /// Multiple calls with the **same `addr` but different `path`** values will
 /// share one TCP listener; the shared dispatcher routes each request to the
 /// correct handler.
 ///
-/// This function is registered as the `HttpListenFn` capability.
+/// This function is registered as the [`HttpServer`](alloy_core::HttpServer) capability.
 #[cfg(feature = "http-server")]
-#[register_capability(http_server)]
+#[register_capability(alloy_core::HttpServer)]
 pub async fn http_listen(
-    addr: String,
-    path: String,
+    config: HttpServerConfig,
     handler: Arc<dyn ConnectionHandler>,
 ) -> TransportResult<ListenerHandle> {
-    let path = if path.starts_with('/') {
-        path
+    let path = if config.path.starts_with('/') {
+        config.path.clone()
     } else {
-        format!("/{path}")
+        format!("/{}", config.path)
     };
 
-    let entry = get_or_create_server(&addr).await?;
+    let entry = get_or_create_server(&config.addr, config.tls.as_ref(), config.socket_mode).await?;
     info!(
         addr = %entry.actual_addr,
         path = %path,
@@ -334,6 +812,7 @@ pub async fn http_listen(
 
     let route_handler = Arc::new(HttpBotHandler {
         handler,
+        config,
         known_bots: Mutex::new(HashMap::new()),
     });
     entry
@@ -359,13 +838,15 @@ pub async fn http_listen(
 #[cfg(feature = "http-server")]
 impl HttpBotHandler {
     /// Handles an HTTP POST request from a bot.
-    async fn handle(&self, addr: SocketAddr, headers: HeaderMap, body: Bytes) -> Response {
-        // Build connection info from request headers.
-        let mut conn_info = ConnectionInfo::new("http").with_remote_addr(addr.to_string());
-        for (name, value) in &headers {
-            if let Ok(v) = value.to_str() {
-                conn_info = conn_info.with_metadata(name.as_str().to_lowercase(), v.to_string());
-            }
+    async fn handle(&self, addr: String, conn_info: ConnectionInfo, body: Bytes) -> Response {
+        // Reject unauthenticated or tampered requests before anything else.
+        if let Err(e) = self
+            .handler
+            .authenticate(&conn_info)
+            .and_then(|()| self.handler.verify_request(&conn_info, &body))
+        {
+            warn!(remote_addr = %addr, error = %e, "Rejected HTTP request");
+            return (StatusCode::UNAUTHORIZED, e.to_string()).into_response();
         }
 
         // Ask the adapter to identify which bot this request belongs to.
@@ -381,40 +862,169 @@ impl HttpBotHandler {
             }
         };
 
-        // First request from this bot → create the bot and its outgoing channel.
-        {
-            let mut known = self.known_bots.lock();
-            if !known.contains_key(&bot_id) {
-                let (message_tx, mut message_rx) = mpsc::channel::<Vec<u8>>(256);
-                let shutdown_token = CancellationToken::new();
-
-                let connection_handle =
-                    ConnectionHandle::new_http_server(bot_id.clone(), message_tx, shutdown_token);
-
-                // Drain the outgoing queue; adapters can plug in SSE or similar
-                // by overriding this logic in their own transport wrapper.
-                let bot_id_clone = bot_id.clone();
-                tokio::spawn(async move {
-                    while let Some(data) = message_rx.recv().await {
-                        warn!(
-                            bot_id = %bot_id_clone,
-                            len    = data.len(),
-                            "HTTP bot sent message but no push mechanism configured \
-                             (consider SSE)",
-                        );
+        self.push_channel(&bot_id, &addr);
+
+        debug!(bot_id = %bot_id, len = body.len(), "Received HTTP POST");
+        let (response, response_rx) = ResponseSlot::channel();
+        self.handler.on_request(&bot_id, &body, response).await;
+
+        // Answer with whatever the event's handlers put in the slot; once
+        // every copy of it is gone (or the deadline passes) there is nothing.
+        match tokio::time::timeout(self.config.response_deadline, response_rx).await {
+            Ok(Ok(body)) => {
+                debug!(bot_id = %bot_id, len = body.len(), "Answering HTTP POST");
+                ([(CONTENT_TYPE, "application/json")], body).into_response()
+            }
+            Ok(Err(_)) => StatusCode::NO_CONTENT.into_response(),
+            Err(_) => {
+                debug!(bot_id = %bot_id, "No response before deadline");
+                StatusCode::NO_CONTENT.into_response()
+            }
+        }
+    }
+
+    /// Handles an SSE subscription (`GET`) from a bot.
+    fn subscribe(&self, addr: String, conn_info: ConnectionInfo) -> Response {
+        if let Err(e) = self.handler.authenticate(&conn_info) {
+            warn!(remote_addr = %addr, error = %e, "Rejected SSE subscription");
+            return (StatusCode::UNAUTHORIZED, e.to_string()).into_response();
+        }
+
+        let last_event_id = conn_info
+            .metadata
+            .get("last-event-id")
+            .and_then(|id| id.trim().parse::<u64>().ok());
+        let bot_id = match self.handler.get_bot_id(conn_info) {
+            Ok(id) => id,
+            Err(e) => {
+                error!(
+                    error       = %e,
+                    remote_addr = %addr,
+                    "Failed to extract bot ID from SSE subscription",
+                );
+                return (StatusCode::BAD_REQUEST, "Failed to extract bot ID").into_response();
+            }
+        };
+
+        let push = self.push_channel(&bot_id, &addr);
+        let (replay, live) = push.subscribe(&bot_id, last_event_id);
+        info!(
+            bot_id = %bot_id,
+            remote_addr = %addr,
+            last_event_id = ?last_event_id,
+            replayed = replay.len(),
+            "SSE subscriber connected",
+        );
+
+        let live = futures::stream::unfold(live, move |mut live| {
+            let bot_id = bot_id.clone();
+            async move {
+                match live.recv().await {
+                    Ok(frame) => Some((frame, live)),
+                    // End the stream; the client resumes from its last event
+                    // ID and gets the skipped frames from the replay buffer.
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(bot_id = %bot_id, skipped, "SSE subscriber fell behind, closing");
+                        None
                     }
-                });
+                    Err(broadcast::error::RecvError::Closed) => None,
+                }
+            }
+        });
+        let events = futures::stream::iter(replay).chain(live).map(|(id, data)| {
+            Ok::<_, Infallible>(SseEvent::default().id(id.to_string()).data(&*data))
+        });
+
+        Sse::new(events)
+            .keep_alive(KeepAlive::default())
+            .into_response()
+    }
 
-                self.handler.create_bot(&bot_id, connection_handle.clone());
-                known.insert(bot_id.clone(), connection_handle);
-                info!(bot_id = %bot_id, remote_addr = %addr, "HTTP bot created");
+    /// Returns the push channel of `bot_id`, creating the bot on first contact.
+    fn push_channel(&self, bot_id: &str, addr: &str) -> Arc<PushChannel> {
+        let mut known = self.known_bots.lock();
+        if let Some(push) = known.get(bot_id) {
+            return push.clone();
+        }
+
+        let (message_tx, mut message_rx) = mpsc::channel::<Vec<u8>>(256);
+        let connection_handle =
+            ConnectionHandle::new_http_server(bot_id, message_tx, CancellationToken::new());
+
+        // Move the outgoing queue into the push channel, for SSE subscribers.
+        let push = Arc::new(PushChannel::new());
+        let push_clone = push.clone();
+        let bot_id_clone = bot_id.to_string();
+        tokio::spawn(async move {
+            while let Some(data) = message_rx.recv().await {
+                let id = push_clone.publish(&data);
+                trace!(bot_id = %bot_id_clone, id, len = data.len(), "Queued SSE event");
             }
+        });
+
+        self.handler.create_bot(bot_id, connection_handle);
+        known.insert(bot_id.to_string(), push.clone());
+        info!(bot_id = %bot_id, remote_addr = %addr, "HTTP bot created");
+        push
+    }
+}
+
+#[cfg(feature = "http-server")]
+impl PushChannel {
+    fn new() -> Self {
+        Self {
+            history: Mutex::new(PushHistory {
+                next_id: 1,
+                frames: VecDeque::with_capacity(SSE_REPLAY_CAPACITY),
+            }),
+            live: broadcast::channel(SSE_REPLAY_CAPACITY).0,
         }
+    }
 
-        debug!(bot_id = %bot_id, len = body.len(), "Received HTTP POST");
-        self.handler.on_message(&bot_id, &body).await;
+    /// Records a frame and sends it to current subscribers; returns its
+    /// event ID.
+    fn publish(&self, data: &[u8]) -> u64 {
+        let data: Arc<str> = String::from_utf8_lossy(data).into();
+        let mut history = self.history.lock();
+        let id = history.next_id;
+        history.next_id += 1;
+        if history.frames.len() == SSE_REPLAY_CAPACITY {
+            history.frames.pop_front();
+        }
+        history.frames.push_back((id, data.clone()));
+        // No receivers just means nobody is subscribed right now.
+        let _ = self.live.send((id, data));
+        id
+    }
 
-        (StatusCode::OK, "ok").into_response()
+    /// Returns the frames after `last_event_id` and a receiver for the rest.
+    fn subscribe(
+        &self,
+        bot_id: &str,
+        last_event_id: Option<u64>,
+    ) -> (Vec<SseFrame>, broadcast::Receiver<SseFrame>) {
+        let history = self.history.lock();
+        let replay = match last_event_id {
+            Some(last) => {
+                if let Some(&(oldest, _)) = history.frames.front()
+                    && last + 1 < oldest
+                {
+                    warn!(
+                        bot_id = %bot_id,
+                        missed = oldest - last - 1,
+                        "SSE subscriber resumed past the replay buffer",
+                    );
+                }
+                history
+                    .frames
+                    .iter()
+                    .filter(|(id, _)| *id > last)
+                    .cloned()
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        (replay, self.live.subscribe())
     }
 }
 
@@ -422,27 +1032,30 @@ impl HttpBotHandler {
 // WEBSOCKET SERVER CAPABILITY IMPLEMENTATION
 // ═════════════════════════════════════════════════════════════════════════════
 
-/// Starts (or re-uses) a TCP server on `addr` and registers a WebSocket
-/// upgrade handler for `path`.
+/// Starts (or re-uses) a TCP or Unix socket server on `config.addr` and registers a
+/// WebSocket upgrade handler for `config.path`.
 ///
 /// Multiple calls with the **same `addr` but different `path`** values share
 /// one TCP listener; the dispatcher routes each request to the correct handler.
 ///
-/// This function is registered as the `WsListenFn` capability.
+/// Each accepted connection is pinged and timed out as `config` says.
+/// Upgrades beyond the connection limits are refused with `503 Service
+/// Unavailable` (limit of the path) or `429 Too Many Requests` (limit per IP).
+///
+/// This function is registered as the [`WsServer`](alloy_core::WsServer) capability.
 #[cfg(feature = "ws-server")]
-#[register_capability(ws_server)]
+#[register_capability(alloy_core::WsServer)]
 pub async fn ws_listen(
-    addr: String,
-    path: String,
+    config: WsServerConfig,
     handler: Arc<dyn ConnectionHandler>,
 ) -> TransportResult<ListenerHandle> {
-    let path = if path.starts_with('/') {
-        path
+    let path = if config.path.starts_with('/') {
+        config.path.clone()
     } else {
-        format!("/{path}")
+        format!("/{}", config.path)
     };
 
-    let entry = get_or_create_server(&addr).await?;
+    let entry = get_or_create_server(&config.addr, config.tls.as_ref(), config.socket_mode).await?;
     info!(
         addr = %entry.actual_addr,
         path = %path,
@@ -451,7 +1064,8 @@ pub async fn ws_listen(
 
     let route_handler = Arc::new(WsBotHandler {
         handler,
-        connections: Mutex::new(HashMap::new()),
+        config,
+        open: Mutex::new(OpenConnections::default()),
     });
     entry
         .state
@@ -475,15 +1089,44 @@ pub async fn ws_listen(
 
 #[cfg(feature = "ws-server")]
 impl WsBotHandler {
-    /// Handles a WebSocket upgrade and manages the connection lifecycle.
-    async fn handle(&self, addr: SocketAddr, headers: HashMap<String, String>, socket: WebSocket) {
-        let (mut ws_tx, mut ws_rx) = socket.split();
+    /// Reserves a place for a connection from `addr` within the configured
+    /// limits, or returns the status to refuse the upgrade with.
+    fn reserve(self: &Arc<Self>, addr: &str) -> Result<ConnectionSlot, (StatusCode, &'static str)> {
+        // Unix socket peers have no IP and only count towards the path limit.
+        let ip = addr.parse::<SocketAddr>().ok().map(|a| a.ip());
+        let mut open = self.open.lock();
+        if self
+            .config
+            .max_connections
+            .is_some_and(|max| open.total >= max)
+        {
+            return Err((
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Too many WebSocket connections",
+            ));
+        }
+        if let (Some(ip), Some(max)) = (ip, self.config.max_connections_per_ip)
+            && open.per_ip.get(&ip).is_some_and(|&count| count >= max)
+        {
+            return Err((
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many WebSocket connections from this address",
+            ));
+        }
 
-        // Build connection info from the HTTP upgrade headers.
-        let mut conn_info = ConnectionInfo::new("websocket").with_remote_addr(addr.to_string());
-        for (key, value) in &headers {
-            conn_info = conn_info.with_metadata(key.clone(), value.clone());
+        open.total += 1;
+        if let Some(ip) = ip {
+            *open.per_ip.entry(ip).or_default() += 1;
         }
+        Ok(ConnectionSlot {
+            route: self.clone(),
+            ip,
+        })
+    }
+
+    /// Handles a WebSocket upgrade and manages the connection lifecycle.
+    async fn handle(&self, addr: String, conn_info: ConnectionInfo, socket: WebSocket) {
+        let (mut ws_tx, ws_rx) = socket.split();
 
         // Let the adapter identify which bot this connection belongs to.
         let bot_id = match self.handler.get_bot_id(conn_info) {
@@ -501,61 +1144,912 @@ impl WsBotHandler {
 
         info!(bot_id = %bot_id, remote_addr = %addr, "WebSocket connection established");
 
-        // Per-connection outgoing channel: adapter writes here → forwarded to ws_tx.
-        let (tx, mut rx) = mpsc::channel::<Vec<u8>>(256);
+        // Per-connection outgoing channel: adapter writes here → forwarded to
+        // ws_tx.  Pings and closes from the receive loop bypass it.
+        let capacity = self.config.send_queue_capacity.max(1);
+        let (tx, rx) = mpsc::channel::<Frame>(capacity);
+        let (control_tx, control_rx) = mpsc::channel::<Message>(4);
         let shutdown_token = CancellationToken::new();
-        let connection_handle =
-            ConnectionHandle::new_ws(bot_id.clone(), tx.clone(), shutdown_token);
-
-        self.handler.create_bot(&bot_id, connection_handle);
-        self.connections.lock().insert(bot_id.clone(), tx.clone());
-
-        // ── Send task: forwards outgoing frames to the WebSocket write half ───────
-        let bot_id_send = bot_id.clone();
-        let send_task = tokio::spawn(async move {
-            while let Some(data) = rx.recv().await {
-                let text = String::from_utf8_lossy(&data).to_string();
-                if ws_tx.send(Message::Text(text.into())).await.is_err() {
-                    warn!(bot_id = %bot_id_send, "Failed to send message, connection closed");
-                    break;
+        let mut connection_handle =
+            ConnectionHandle::new_ws(bot_id.clone(), tx, shutdown_token.clone());
+        if let Some(timeout) = self.config.call_timeout {
+            connection_handle = connection_handle.with_call_timeout(timeout);
+        }
+        self.handler.create_bot(&bot_id, connection_handle.clone());
+
+        let outbox = Outbox::new(rx, &self.config, &bot_id, &shutdown_token);
+        let mut send_task = tokio::spawn(send_loop(ws_tx, outbox, control_rx, bot_id.clone()));
+
+        let reason = self
+            .receive_loop(&bot_id, ws_rx, &control_tx, &shutdown_token)
+            .await;
+
+        // ── Cleanup ───────────────────────────────────────────────────────────────
+        if let Some(close) = reason.close_frame() {
+            // Say goodbye, unless the client stopped reading long ago.
+            let _ = control_tx.try_send(close);
+            let _ = tokio::time::timeout(CLOSE_GRACE, &mut send_task).await;
+        }
+        send_task.abort();
+        shutdown_token.cancel();
+        self.handler.on_disconnect(&connection_handle).await;
+        info!(bot_id = %bot_id, reason = ?reason, "WebSocket connection closed");
+    }
+
+    /// Forwards inbound frames to the adapter and keeps the connection
+    /// alive, until it ends; returns why.
+    async fn receive_loop(
+        &self,
+        bot_id: &str,
+        mut ws_rx: SplitStream<WebSocket>,
+        control_tx: &mpsc::Sender<Message>,
+        shutdown_token: &CancellationToken,
+    ) -> CloseReason {
+        let config = &self.config;
+        let ping_interval = config.ping_interval.filter(|p| !p.is_zero());
+        let mut next_ping = ping_interval.map(|p| Instant::now() + p);
+        let mut pong_due: Option<Instant> = None;
+        let mut last_data = Instant::now();
+
+        loop {
+            let idle_at = config.idle_timeout.map(|t| last_data + t);
+            let wake = [next_ping, pong_due, idle_at].into_iter().flatten().min();
+
+            let result = tokio::select! {
+                result = ws_rx.next() => result,
+                () = sleep_until(wake) => {
+                    let now = Instant::now();
+                    if pong_due.is_some_and(|due| now >= due) {
+                        return CloseReason::PongTimeout;
+                    }
+                    if idle_at.is_some_and(|at| now >= at) {
+                        return CloseReason::IdleTimeout;
+                    }
+                    if let (Some(at), Some(interval)) = (next_ping, ping_interval)
+                        && now >= at
+                    {
+                        trace!(bot_id = %bot_id, "Sending ping");
+                        let _ = control_tx.try_send(Message::Ping(Default::default()));
+                        pong_due.get_or_insert(now + config.pong_timeout);
+                        next_ping = Some(now + interval);
+                    }
+                    continue;
                 }
-            }
-        });
+                () = shutdown_token.cancelled() => return CloseReason::Shutdown,
+            };
 
-        // ── Receive loop: forwards inbound frames to the adapter ─────────────────
-        let handler_ref = self.handler.clone();
-        let bot_id_recv = bot_id.clone();
-        while let Some(result) = ws_rx.next().await {
+            // Anything from the client shows it is still there.
+            pong_due = None;
             match result {
-                Ok(Message::Text(text)) => {
-                    debug!(bot_id = %bot_id_recv, len = text.len(), "Received text message");
-                    handler_ref.on_message(&bot_id_recv, text.as_bytes()).await;
+                Some(Ok(Message::Text(text))) => {
+                    debug!(bot_id = %bot_id, len = text.len(), "Received text message");
+                    last_data = Instant::now();
+                    self.handler
+                        .on_message(bot_id, &Frame::Text(text.to_string()))
+                        .await;
                 }
-                Ok(Message::Binary(data)) => {
-                    debug!(bot_id = %bot_id_recv, len = data.len(), "Received binary message");
-                    handler_ref.on_message(&bot_id_recv, &data).await;
+                Some(Ok(Message::Binary(data))) => {
+                    debug!(bot_id = %bot_id, len = data.len(), "Received binary message");
+                    last_data = Instant::now();
+                    self.handler
+                        .on_message(bot_id, &Frame::Binary(data.to_vec()))
+                        .await;
                 }
-                Ok(Message::Ping(_)) => {
-                    debug!(bot_id = %bot_id_recv, "Received ping");
+                Some(Ok(Message::Ping(_))) => {
+                    debug!(bot_id = %bot_id, "Received ping");
                 }
-                Ok(Message::Pong(_)) => {
-                    debug!(bot_id = %bot_id_recv, "Received pong");
+                Some(Ok(Message::Pong(_))) => {
+                    debug!(bot_id = %bot_id, "Received pong");
                 }
-                Ok(Message::Close(_)) => {
-                    info!(bot_id = %bot_id_recv, "WebSocket connection closed by client");
-                    break;
+                Some(Ok(Message::Close(close))) => {
+                    let frame = match close {
+                        Some(close) => Frame::close(close.code, close.reason.as_str()),
+                        None => Frame::close(Frame::CLOSE_NO_STATUS, ""),
+                    };
+                    info!(bot_id = %bot_id, frame = ?frame, "WebSocket connection closed by client");
+                    self.handler.on_message(bot_id, &frame).await;
+                    return CloseReason::ClientClosed;
                 }
-                Err(e) => {
-                    warn!(bot_id = %bot_id_recv, error = %e, "WebSocket error");
-                    break;
+                Some(Err(e)) => {
+                    warn!(bot_id = %bot_id, error = %e, "WebSocket error");
+                    return CloseReason::Error;
                 }
+                None => return CloseReason::ClientClosed,
             }
         }
+    }
+}
 
-        // ── Cleanup ───────────────────────────────────────────────────────────────
-        send_task.abort();
-        self.connections.lock().remove(&bot_id);
-        self.handler.on_disconnect(&bot_id).await;
-        info!(bot_id = %bot_id, "WebSocket connection closed");
+/// A connection's place in its route's connection limits, given back when
+/// dropped.
+#[cfg(feature = "ws-server")]
+struct ConnectionSlot {
+    route: Arc<WsBotHandler>,
+    ip: Option<IpAddr>,
+}
+
+#[cfg(feature = "ws-server")]
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        let mut open = self.route.open.lock();
+        open.total -= 1;
+        if let Some(ip) = self.ip
+            && let Some(count) = open.per_ip.get_mut(&ip)
+        {
+            *count -= 1;
+            if *count == 0 {
+                open.per_ip.remove(&ip);
+            }
+        }
+    }
+}
+
+/// Why a WebSocket server connection ended.
+#[cfg(feature = "ws-server")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CloseReason {
+    /// The client closed the connection.
+    ClientClosed,
+    /// Reading from the socket failed.
+    Error,
+    /// A ping went unanswered.
+    PongTimeout,
+    /// No data frame arrived within the idle timeout.
+    IdleTimeout,
+    /// The connection handle was closed, or the send queue overflowed with
+    /// [`SendOverflow::Disconnect`].
+    Shutdown,
+}
+
+#[cfg(feature = "ws-server")]
+impl CloseReason {
+    /// The close frame to send the client, if it is likely still listening.
+    fn close_frame(self) -> Option<Message> {
+        let reason = match self {
+            Self::ClientClosed | Self::Error | Self::PongTimeout => return None,
+            Self::IdleTimeout => "idle timeout",
+            Self::Shutdown => "",
+        };
+        Some(Message::Close(Some(CloseFrame {
+            code: Frame::CLOSE_NORMAL,
+            reason: reason.into(),
+        })))
+    }
+}
+
+/// Waits until `deadline`; never completes without one.
+#[cfg(feature = "ws-server")]
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Writes the bot's frames, and the pings and closes of the receive loop,
+/// to the socket.
+#[cfg(feature = "ws-server")]
+async fn send_loop(
+    mut ws_tx: SplitSink<WebSocket, Message>,
+    mut outbox: Outbox,
+    mut control_rx: mpsc::Receiver<Message>,
+    bot_id: String,
+) {
+    loop {
+        let msg = tokio::select! {
+            biased;
+            Some(msg) = control_rx.recv() => msg,
+            frame = outbox.next() => match frame {
+                Some(frame) => ws_message(frame),
+                None => break,
+            },
+        };
+        let closing = matches!(msg, Message::Close(_));
+        if ws_tx.send(msg).await.is_err() {
+            warn!(bot_id = %bot_id, "Failed to send message, connection closed");
+            break;
+        }
+        if closing {
+            // The receive loop ends once the client answers the close.
+            break;
+        }
+    }
+}
+
+#[cfg(feature = "ws-server")]
+fn ws_message(frame: Frame) -> Message {
+    match frame {
+        Frame::Text(text) => Message::Text(text.into()),
+        Frame::Binary(data) => Message::Binary(data.into()),
+        Frame::Close { code, reason } => Message::Close(Some(CloseFrame {
+            code,
+            reason: reason.into(),
+        })),
+    }
+}
+
+/// The frames a connection's bot sent that are waiting to be written.
+#[cfg(feature = "ws-server")]
+enum Outbox {
+    /// [`SendOverflow::Block`]: the bounded channel itself, whose senders
+    /// wait while it is full.
+    Direct(mpsc::Receiver<Frame>),
+    /// Other policies: frames leave the channel as soon as they are sent and
+    /// wait here, where the policy is applied.
+    Queued(Arc<SendQueue>),
+}
+
+#[cfg(feature = "ws-server")]
+impl Outbox {
+    fn new(
+        rx: mpsc::Receiver<Frame>,
+        config: &WsServerConfig,
+        bot_id: &str,
+        shutdown_token: &CancellationToken,
+    ) -> Self {
+        match config.send_overflow {
+            SendOverflow::Block => Self::Direct(rx),
+            policy => {
+                let queue = Arc::new(SendQueue {
+                    frames: Mutex::new(VecDeque::new()),
+                    capacity: config.send_queue_capacity.max(1),
+                    ready: Notify::new(),
+                    closed: AtomicBool::new(false),
+                });
+                tokio::spawn(queue.clone().fill(
+                    rx,
+                    policy,
+                    bot_id.to_string(),
+                    shutdown_token.clone(),
+                ));
+                Self::Queued(queue)
+            }
+        }
+    }
+
+    /// Returns the next frame to write, or `None` once the bot can send no
+    /// more.
+    async fn next(&mut self) -> Option<Frame> {
+        match self {
+            Self::Direct(rx) => rx.recv().await,
+            Self::Queued(queue) => queue.pop().await,
+        }
+    }
+}
+
+#[cfg(feature = "ws-server")]
+struct SendQueue {
+    /// Oldest first.
+    frames: Mutex<VecDeque<Frame>>,
+    capacity: usize,
+    /// Signalled when a frame is queued or the queue is closed.
+    ready: Notify,
+    closed: AtomicBool,
+}
+
+#[cfg(feature = "ws-server")]
+impl SendQueue {
+    /// Moves frames from `rx` into the queue until the connection closes,
+    /// applying `policy` whenever the queue is full.
+    async fn fill(
+        self: Arc<Self>,
+        mut rx: mpsc::Receiver<Frame>,
+        policy: SendOverflow,
+        bot_id: String,
+        shutdown_token: CancellationToken,
+    ) {
+        let mut dropped = 0u64;
+        loop {
+            let frame = tokio::select! {
+                frame = rx.recv() => frame,
+                () = shutdown_token.cancelled() => None,
+            };
+            let Some(frame) = frame else {
+                break;
+            };
+
+            // Frames still in the channel count too, so the client never
+            // falls more than `capacity` frames behind.
+            let mut frames = self.frames.lock();
+            let full = |frames: &VecDeque<Frame>| frames.len() + rx.len() >= self.capacity;
+            if policy == SendOverflow::Disconnect && full(&frames) {
+                warn!(bot_id = %bot_id, capacity = self.capacity, "Send queue full, disconnecting");
+                shutdown_token.cancel();
+                break;
+            }
+            while full(&frames) && frames.pop_front().is_some() {
+                dropped += 1;
+                if dropped == 1 {
+                    warn!(bot_id = %bot_id, capacity = self.capacity, "Send queue full, dropping oldest frames");
+                } else {
+                    debug!(bot_id = %bot_id, dropped, "Dropped oldest queued frame");
+                }
+            }
+            frames.push_back(frame);
+            drop(frames);
+            self.ready.notify_one();
+        }
+        self.closed.store(true, Ordering::Release);
+        self.ready.notify_one();
+    }
+
+    /// Takes the oldest frame, waiting for one; `None` once the queue is
+    /// closed and empty.
+    async fn pop(&self) -> Option<Frame> {
+        loop {
+            if let Some(frame) = self.frames.lock().pop_front() {
+                return Some(frame);
+            }
+            if self.closed.load(Ordering::Acquire) {
+                return None;
+            }
+            self.ready.notified().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[cfg(any(feature = "http-server", feature = "ws-server"))]
+    use {
+        alloy_core::{Frame, TransportError},
+        async_trait::async_trait,
+        tokio::io::{AsyncReadExt, AsyncWriteExt},
+        tokio::net::TcpStream,
+    };
+
+    /// Accepts requests carrying `Authorization: Bearer token` (any request
+    /// with `open` set) and records the bots it is asked to create.
+    ///
+    /// HTTP requests are answered with `answer`, or left pending.
+    #[cfg(any(feature = "http-server", feature = "ws-server"))]
+    #[derive(Default)]
+    struct TestHandler {
+        open: bool,
+        bots: Mutex<Vec<ConnectionHandle>>,
+        disconnected: Mutex<Vec<String>>,
+        #[cfg(feature = "http-server")]
+        answer: Option<&'static str>,
+        #[cfg(feature = "http-server")]
+        pending: Mutex<Vec<ResponseSlot>>,
+    }
+
+    #[cfg(any(feature = "http-server", feature = "ws-server"))]
+    #[async_trait]
+    impl ConnectionHandler for TestHandler {
+        fn authenticate(&self, conn_info: &ConnectionInfo) -> TransportResult<()> {
+            if self.open
+                || conn_info.metadata.get("authorization").map(String::as_str)
+                    == Some("Bearer token")
+            {
+                return Ok(());
+            }
+            Err(TransportError::Unauthorized {
+                reason: "missing access token".into(),
+            })
+        }
+
+        fn get_bot_id(&self, conn_info: ConnectionInfo) -> TransportResult<String> {
+            conn_info.metadata.get("x-self-id").cloned().ok_or_else(|| {
+                TransportError::BotIdMissing {
+                    reason: "no x-self-id".into(),
+                }
+            })
+        }
+
+        fn create_bot(&self, _bot_id: &str, connection: ConnectionHandle) {
+            self.bots.lock().push(connection);
+        }
+
+        async fn on_message(&self, _bot_id: &str, _frame: &Frame) {}
+
+        #[cfg(feature = "http-server")]
+        async fn on_request(&self, _bot_id: &str, _data: &[u8], response: ResponseSlot) {
+            match self.answer {
+                Some(answer) => {
+                    response.respond(answer.into());
+                }
+                None => self.pending.lock().push(response),
+            }
+        }
+
+        async fn on_disconnect(&self, connection: &ConnectionHandle) {
+            self.disconnected.lock().push(connection.id.clone());
+        }
+    }
+
+    /// Serves `state` on an ephemeral port of this test's runtime, bypassing
+    /// the shared registry.
+    #[cfg(any(feature = "http-server", feature = "ws-server"))]
+    async fn serve(state: Arc<SharedState>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let router = build_router(state);
+        tokio::spawn(
+            axum::serve(
+                listener,
+                router.into_make_service_with_connect_info::<RemoteAddr>(),
+            )
+            .into_future(),
+        );
+        addr
+    }
+
+    /// Sends a raw HTTP/1.1 request and returns the connection and the
+    /// response head.
+    #[cfg(any(feature = "http-server", feature = "ws-server"))]
+    async fn request(addr: &str, request: &str) -> (TcpStream, String) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            head.push(stream.read_u8().await.unwrap());
+        }
+        (stream, String::from_utf8(head).unwrap())
+    }
+
+    /// Sends a raw HTTP/1.1 request and returns the response head.
+    #[cfg(feature = "http-server")]
+    async fn request_head(addr: &str, raw: &str) -> String {
+        request(addr, raw).await.1
+    }
+
+    #[cfg(feature = "http-server")]
+    fn http_route(
+        state: &SharedState,
+        config: HttpServerConfig,
+        path: &str,
+        handler: Arc<TestHandler>,
+    ) {
+        let route = Arc::new(HttpBotHandler {
+            handler,
+            config,
+            known_bots: Mutex::new(HashMap::new()),
+        });
+        state.http_routes.write().insert(path.to_string(), route);
+    }
+
+    #[cfg(feature = "http-server")]
+    #[tokio::test]
+    async fn test_sse_subscription_requires_authentication() {
+        let state = Arc::new(SharedState::new());
+        let handler = Arc::new(TestHandler::default());
+        http_route(
+            &state,
+            HttpServerConfig::default(),
+            "/events",
+            handler.clone(),
+        );
+        let addr = serve(state).await;
+
+        let head = request_head(
+            &addr,
+            "GET /events HTTP/1.1\r\nHost: bot\r\nX-Self-ID: 10001\r\n\r\n",
+        )
+        .await;
+        assert!(head.starts_with("HTTP/1.1 401"), "{head}");
+        assert!(handler.bots.lock().is_empty());
+
+        let head = request_head(
+            &addr,
+            "GET /events HTTP/1.1\r\nHost: bot\r\nX-Self-ID: 10001\r\n\
+             Authorization: Bearer token\r\n\r\n",
+        )
+        .await;
+        assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+        assert!(head.contains("text/event-stream"), "{head}");
+        assert_eq!(handler.bots.lock().len(), 1);
+    }
+
+    /// Posts an event for bot `10001` to `/` and returns the response head and
+    /// body.
+    #[cfg(feature = "http-server")]
+    async fn post_event(addr: &str) -> (String, String) {
+        let body = r#"{"post_type":"message"}"#;
+        let (mut stream, head) = request(
+            addr,
+            &format!(
+                "POST / HTTP/1.1\r\nHost: bot\r\nX-Self-ID: 10001\r\n\
+                 Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            ),
+        )
+        .await;
+        let length = head
+            .lines()
+            .find_map(|line| line.strip_prefix("content-length: "))
+            .map_or(0, |len| len.trim().parse().unwrap());
+        let mut body = vec![0; length];
+        stream.read_exact(&mut body).await.unwrap();
+        (head, String::from_utf8(body).unwrap())
+    }
+
+    #[cfg(feature = "http-server")]
+    #[tokio::test]
+    async fn test_http_quick_operation() {
+        let state = Arc::new(SharedState::new());
+        let handler = Arc::new(TestHandler {
+            open: true,
+            answer: Some(r#"{"reply":"pong"}"#),
+            ..Default::default()
+        });
+        http_route(&state, HttpServerConfig::default(), "/", handler);
+        let addr = serve(state).await;
+
+        let (head, body) = post_event(&addr).await;
+        assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+        assert!(head.contains("application/json"), "{head}");
+        assert_eq!(body, r#"{"reply":"pong"}"#);
+    }
+
+    #[cfg(feature = "http-server")]
+    #[tokio::test]
+    async fn test_http_response_deadline() {
+        let state = Arc::new(SharedState::new());
+        let handler = Arc::new(TestHandler {
+            open: true,
+            ..Default::default()
+        });
+        let deadline = Duration::from_millis(200);
+        let config = HttpServerConfig::default().with_response_deadline(deadline);
+        http_route(&state, config, "/", handler.clone());
+        let addr = serve(state).await;
+
+        let started = std::time::Instant::now();
+        let (head, body) = post_event(&addr).await;
+        assert!(head.starts_with("HTTP/1.1 204"), "{head}");
+        assert!(body.is_empty());
+        assert!(started.elapsed() >= deadline);
+
+        // The handler kept the slot, but the request it belonged to is over.
+        let pending = handler.pending.lock();
+        assert_eq!(pending.len(), 1);
+        assert!(!pending[0].is_open());
+    }
+
+    /// Creates an empty directory for socket files.
+    #[cfg(unix)]
+    fn socket_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("alloy-{test}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_remove_stale_socket() {
+        let dir = socket_dir("stale");
+        let path = dir.join("bot.sock");
+        remove_stale_socket(&path).await.unwrap();
+
+        // A socket somebody listens on is left alone.
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        let err = remove_stale_socket(&path).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+        assert!(path.exists());
+
+        // Once nobody does, it is removed.
+        drop(listener);
+        remove_stale_socket(&path).await.unwrap();
+        assert!(!path.exists());
+
+        // Other files are never touched.
+        std::fs::write(&path, "data").unwrap();
+        let err = remove_stale_socket(&path).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert!(path.exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(all(unix, feature = "http-server"))]
+    #[tokio::test]
+    async fn test_unix_listener() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = socket_dir("listener");
+        let path = dir.join("bot.sock");
+        let handler = Arc::new(TestHandler {
+            open: true,
+            answer: Some(r#"{"reply":"pong"}"#),
+            ..Default::default()
+        });
+        let config = HttpServerConfig::new(format!("unix:{}", path.display()), "/hook")
+            .with_socket_mode(0o600);
+        let listener = http_listen(config, handler).await.unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // The directory the socket was bound in is gone.
+        let entries: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, ["bot.sock"]);
+
+        let mut stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+        stream
+            .write_all(
+                b"POST /hook HTTP/1.1\r\nHost: bot\r\nX-Self-ID: 10001\r\n\
+                  Connection: close\r\nContent-Length: 2\r\n\r\n{}",
+            )
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.ends_with(r#"{"reply":"pong"}"#), "{response}");
+
+        listener.stop();
+        wait_for("socket removal", || !path.exists()).await;
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_bind_addr_settings() {
+        let tls = ServerTlsConfig::new("/c.pem", "/k.pem");
+        let bind = BindAddr::new("0.0.0.0:8443", Some(&tls), None).unwrap();
+        let BindAddr::Tcp { addr, tls: bound } = &bind else {
+            panic!("expected a TCP address");
+        };
+        assert_eq!(addr, "0.0.0.0:8443");
+        assert_eq!(bound.as_ref(), Some(&tls));
+
+        assert!(
+            BindAddr::new("0.0.0.0:8443", None, None)
+                .unwrap()
+                .tls()
+                .is_none()
+        );
+        assert!(BindAddr::new("0.0.0.0:8443", None, Some(0o600)).is_err());
+        let err = BindAddr::new("unix:/run/a.sock", Some(&tls), None).unwrap_err();
+        assert!(err.to_string().contains("TLS is not supported"), "{err}");
+        #[cfg(unix)]
+        {
+            assert!(matches!(
+                BindAddr::new("unix:/run/a.sock", None, Some(0o600)).unwrap(),
+                BindAddr::Unix { mode: 0o600, .. }
+            ));
+            // Paths are taken as they are.
+            let BindAddr::Unix { path, mode } =
+                BindAddr::new("unix:/run/a?b=c&d.sock", None, None).unwrap()
+            else {
+                panic!("expected a Unix socket");
+            };
+            assert_eq!(path, Path::new("/run/a?b=c&d.sock"));
+            assert_eq!(mode, DEFAULT_SOCKET_MODE);
+        }
+    }
+
+    #[cfg(feature = "http-server")]
+    #[test]
+    fn test_push_channel_replay() {
+        let push = PushChannel::new();
+        for i in 0..SSE_REPLAY_CAPACITY + 2 {
+            push.publish(i.to_string().as_bytes());
+        }
+
+        // Fresh subscribers only get new frames.
+        let (replay, mut live) = push.subscribe("1", None);
+        assert!(replay.is_empty());
+
+        // Resuming replays what is left after the given ID.
+        let last = SSE_REPLAY_CAPACITY as u64;
+        let (replay, _) = push.subscribe("1", Some(last));
+        let ids: Vec<u64> = replay.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, [last + 1, last + 2]);
+        assert_eq!(&*replay[1].1, (SSE_REPLAY_CAPACITY + 1).to_string());
+
+        // Older IDs are cut off at the buffer.
+        let (replay, _) = push.subscribe("1", Some(0));
+        assert_eq!(replay.len(), SSE_REPLAY_CAPACITY);
+        assert_eq!(replay[0].0, 3);
+
+        let id = push.publish(b"next");
+        assert_eq!(live.try_recv().unwrap(), (id, Arc::from("next")));
+    }
+
+    /// Upgrade request of a WebSocket client for bot `10001`.
+    #[cfg(feature = "ws-server")]
+    const WS_UPGRADE: &str = "GET /ws HTTP/1.1\r\nHost: bot\r\nUpgrade: websocket\r\n\
+        Connection: Upgrade\r\nSec-WebSocket-Version: 13\r\n\
+        Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nX-Self-ID: 10001\r\n\r\n";
+
+    /// Serves `config` on `/ws`, accepting every client.
+    #[cfg(feature = "ws-server")]
+    async fn serve_ws(config: WsServerConfig) -> (String, Arc<TestHandler>) {
+        let state = Arc::new(SharedState::new());
+        let handler = Arc::new(TestHandler {
+            open: true,
+            ..Default::default()
+        });
+        let route = Arc::new(WsBotHandler {
+            handler: handler.clone(),
+            config,
+            open: Mutex::new(OpenConnections::default()),
+        });
+        state.ws_routes.write().insert("/ws".into(), route);
+        (serve(state).await, handler)
+    }
+
+    /// Waits up to five seconds for `done`.
+    #[cfg(any(feature = "ws-server", all(unix, feature = "http-server")))]
+    async fn wait_for(what: &str, done: impl Fn() -> bool) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !done() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("timed out waiting for {what}"));
+    }
+
+    /// Connects a client that never reads, and returns it with the sender
+    /// of the bot behind it.
+    #[cfg(feature = "ws-server")]
+    async fn stalled_client(addr: &str, handler: &TestHandler) -> (TcpStream, mpsc::Sender<Frame>) {
+        let (stream, head) = request(addr, WS_UPGRADE).await;
+        assert!(head.starts_with("HTTP/1.1 101"), "{head}");
+        wait_for("the bot", || !handler.bots.lock().is_empty()).await;
+        let alloy_core::ConnectionKind::Ws { message_tx } = &handler.bots.lock()[0].kind else {
+            panic!("expected a WebSocket connection");
+        };
+        (stream, message_tx.clone())
+    }
+
+    /// A frame large enough to fill the socket buffers quickly.
+    #[cfg(feature = "ws-server")]
+    fn big_frame() -> Frame {
+        Frame::Binary(vec![0; 1 << 20])
+    }
+
+    #[cfg(feature = "ws-server")]
+    #[tokio::test]
+    async fn test_stalled_client_blocks_sender() {
+        let config = WsServerConfig::default().with_send_queue(2, SendOverflow::Block);
+        let (addr, handler) = serve_ws(config).await;
+        let (_client, tx) = stalled_client(&addr, &handler).await;
+
+        let mut blocked = false;
+        for _ in 0..256 {
+            let send = tokio::time::timeout(Duration::from_millis(200), tx.send(big_frame()));
+            if send.await.is_err() {
+                blocked = true;
+                break;
+            }
+        }
+        assert!(blocked, "sender never waited for the client");
+        assert!(handler.disconnected.lock().is_empty());
+    }
+
+    #[cfg(feature = "ws-server")]
+    #[tokio::test]
+    async fn test_stalled_client_drops_oldest() {
+        let config = WsServerConfig::default().with_send_queue(2, SendOverflow::DropOldest);
+        let (addr, handler) = serve_ws(config).await;
+        let (_client, tx) = stalled_client(&addr, &handler).await;
+
+        for _ in 0..64 {
+            tokio::time::timeout(Duration::from_secs(1), tx.send(big_frame()))
+                .await
+                .expect("sender waited for the client")
+                .unwrap();
+        }
+        assert!(handler.disconnected.lock().is_empty());
+    }
+
+    #[cfg(feature = "ws-server")]
+    #[tokio::test]
+    async fn test_stalled_client_disconnected() {
+        let config = WsServerConfig::default().with_send_queue(2, SendOverflow::Disconnect);
+        let (addr, handler) = serve_ws(config).await;
+        let (_client, tx) = stalled_client(&addr, &handler).await;
+
+        for _ in 0..256 {
+            if tx.send(big_frame()).await.is_err() {
+                break;
+            }
+        }
+        wait_for("the disconnect", || !handler.disconnected.lock().is_empty()).await;
+        assert_eq!(*handler.disconnected.lock(), ["10001"]);
+    }
+
+    #[cfg(feature = "ws-server")]
+    #[tokio::test]
+    async fn test_send_queue_holds_at_most_capacity() {
+        let config = WsServerConfig::default().with_send_queue(3, SendOverflow::DropOldest);
+        let (tx, rx) = mpsc::channel(3);
+        let token = CancellationToken::new();
+        let mut outbox = Outbox::new(rx, &config, "10001", &token);
+        for i in 0..10 {
+            tx.send(Frame::Text(i.to_string())).await.unwrap();
+        }
+        drop(tx);
+        // Let the queue take in everything before the stalled writer wakes.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut left = Vec::new();
+        while let Some(frame) = outbox.next().await {
+            left.push(frame);
+        }
+        assert_eq!(left, ["7", "8", "9"].map(|t| Frame::Text(t.into())));
+
+        let config = WsServerConfig::default().with_send_queue(2, SendOverflow::Disconnect);
+        let (tx, rx) = mpsc::channel(2);
+        let token = CancellationToken::new();
+        let _outbox = Outbox::new(rx, &config, "10001", &token);
+        for i in 0..2 {
+            tx.send(Frame::Text(i.to_string())).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!token.is_cancelled());
+        tx.send(Frame::Text("2".into())).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), token.cancelled())
+            .await
+            .expect("a third queued frame disconnects");
+    }
+
+    #[cfg(feature = "ws-server")]
+    #[tokio::test]
+    async fn test_connection_limits() {
+        let config = WsServerConfig::default().with_max_connections(1);
+        let (addr, handler) = serve_ws(config).await;
+        let (first, head) = request(&addr, WS_UPGRADE).await;
+        assert!(head.starts_with("HTTP/1.1 101"), "{head}");
+        let (_, head) = request(&addr, WS_UPGRADE).await;
+        assert!(head.starts_with("HTTP/1.1 503"), "{head}");
+
+        // Closing the first connection frees its place.
+        drop(first);
+        wait_for("the disconnect", || !handler.disconnected.lock().is_empty()).await;
+        let mut head = String::new();
+        for _ in 0..50 {
+            head = request(&addr, WS_UPGRADE).await.1;
+            if head.starts_with("HTTP/1.1 101") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(head.starts_with("HTTP/1.1 101"), "{head}");
+
+        let config = WsServerConfig::default().with_max_connections_per_ip(1);
+        let (addr, _handler) = serve_ws(config).await;
+        let (_first, head) = request(&addr, WS_UPGRADE).await;
+        assert!(head.starts_with("HTTP/1.1 101"), "{head}");
+        let (_, head) = request(&addr, WS_UPGRADE).await;
+        assert!(head.starts_with("HTTP/1.1 429"), "{head}");
+    }
+
+    #[cfg(feature = "ws-server")]
+    #[tokio::test]
+    async fn test_unanswered_ping_disconnects() {
+        let config = WsServerConfig::default()
+            .with_ping(Some(Duration::from_millis(50)), Duration::from_millis(50));
+        let (addr, handler) = serve_ws(config).await;
+        let (_client, _tx) = stalled_client(&addr, &handler).await;
+        wait_for("the disconnect", || !handler.disconnected.lock().is_empty()).await;
+    }
+
+    #[cfg(feature = "ws-server")]
+    #[tokio::test]
+    async fn test_idle_timeout_disconnects() {
+        let config = WsServerConfig::default().with_idle_timeout(Some(Duration::from_millis(100)));
+        let (addr, handler) = serve_ws(config).await;
+        let (mut client, _tx) = stalled_client(&addr, &handler).await;
+
+        wait_for("the disconnect", || !handler.disconnected.lock().is_empty()).await;
+        let mut rest = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), client.read_to_end(&mut rest))
+            .await
+            .expect("server did not close the socket")
+            .unwrap();
+        assert!(
+            rest.windows(12).any(|w| w == b"idle timeout"),
+            "no close frame with the reason"
+        );
     }
 }
@@ -0,0 +1,474 @@
+//! rustls setup shared by the servers and the WebSocket client.
+//!
+//! * Servers terminate TLS with a certificate and key read from PEM files.
+//!   [`ReloadingCert`] polls the files and swaps in the new pair when they
+//!   change, so renewed certificates are served without a restart; a pair
+//!   that fails to load is logged and the previous one kept.
+//! * The WebSocket client builds a [`ClientConfig`] per connection attempt from
+//!   [`ClientTlsConfig`].  The system root certificates are read once per
+//!   process.
+//!
+//! Both sides use the aws-lc-rs provider explicitly rather than the
+//! process-wide default, which is ambiguous when several providers are linked.
+
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::CertificateDer;
+use rustls::pki_types::PrivateKeyDer;
+use rustls::pki_types::pem::PemObject;
+
+#[cfg(any(feature = "http-server", feature = "ws-server"))]
+pub(crate) use server::{ReloadingCert, TlsListener};
+
+#[cfg(feature = "ws-client")]
+pub(crate) use client::client_config;
+
+fn provider() -> Arc<CryptoProvider> {
+    Arc::new(rustls::crypto::aws_lc_rs::default_provider())
+}
+
+fn invalid(message: impl std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Reads every certificate in the PEM file at `path`.
+fn load_certs(path: &Path) -> io::Result<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| invalid(format_args!("{}: {e}", path.display())))?;
+    if certs.is_empty() {
+        return Err(invalid(format_args!(
+            "{}: no certificates found",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+/// Reads the first private key in the PEM file at `path`.
+fn load_key(path: &Path) -> io::Result<PrivateKeyDer<'static>> {
+    PrivateKeyDer::from_pem_file(path).map_err(|e| invalid(format_args!("{}: {e}", path.display())))
+}
+
+// ─── Server side ──────────────────────────────────────────────────────────────
+
+#[cfg(any(feature = "http-server", feature = "ws-server"))]
+mod server {
+    use std::net::SocketAddr;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+
+    use parking_lot::{Mutex, RwLock};
+    use rustls::ServerConfig;
+    use rustls::server::{ClientHello, ResolvesServerCert};
+    use rustls::sign::CertifiedKey;
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::mpsc;
+    use tokio_rustls::TlsAcceptor;
+    use tokio_rustls::server::TlsStream;
+    use tokio_util::sync::CancellationToken;
+    use tracing::{debug, info, warn};
+
+    use super::*;
+
+    /// How often certificate files are checked for changes.
+    const RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+    /// How long a client may take to complete the TLS handshake.
+    const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Handshaken connections waiting for the server to pick them up.
+    const ACCEPT_BACKLOG: usize = 64;
+
+    /// A server certificate that follows its PEM files.
+    #[derive(Debug)]
+    pub(crate) struct ReloadingCert {
+        cert_path: PathBuf,
+        key_path: PathBuf,
+        pub(super) current: RwLock<Arc<CertifiedKey>>,
+        /// Modification times of (cert, key) when last loaded.
+        modified: Mutex<(Option<SystemTime>, Option<SystemTime>)>,
+    }
+
+    impl ReloadingCert {
+        /// Loads the certificate chain and key; fails if they do not load or
+        /// do not match.
+        pub(crate) fn load(cert_path: &Path, key_path: &Path) -> io::Result<Self> {
+            let modified = (mtime(cert_path), mtime(key_path));
+            let key = load_certified_key(cert_path, key_path)?;
+            Ok(Self {
+                cert_path: cert_path.to_path_buf(),
+                key_path: key_path.to_path_buf(),
+                current: RwLock::new(Arc::new(key)),
+                modified: Mutex::new(modified),
+            })
+        }
+
+        /// Reloads the pair if either file changed since the last attempt.
+        pub(super) fn reload_if_changed(&self) {
+            let modified = (mtime(&self.cert_path), mtime(&self.key_path));
+            {
+                let mut last = self.modified.lock();
+                if *last == modified {
+                    return;
+                }
+                // Remember failed attempts too: a half-written pair is retried
+                // once the other file changes, not logged every interval.
+                *last = modified;
+            }
+
+            match load_certified_key(&self.cert_path, &self.key_path) {
+                Ok(key) => {
+                    *self.current.write() = Arc::new(key);
+                    info!(cert = %self.cert_path.display(), "Reloaded TLS certificate");
+                }
+                Err(e) => {
+                    warn!(
+                        cert = %self.cert_path.display(),
+                        error = %e,
+                        "Failed to reload TLS certificate, keeping the previous one",
+                    );
+                }
+            }
+        }
+
+        /// Polls the files until `shutdown_token` is cancelled.
+        fn watch(self: Arc<Self>, shutdown_token: CancellationToken) {
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(RELOAD_INTERVAL);
+                interval.tick().await;
+                loop {
+                    tokio::select! {
+                        _ = interval.tick() => self.reload_if_changed(),
+                        () = shutdown_token.cancelled() => break,
+                    }
+                }
+            });
+        }
+    }
+
+    impl ResolvesServerCert for ReloadingCert {
+        fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+            Some(self.current.read().clone())
+        }
+    }
+
+    fn mtime(path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    fn load_certified_key(cert_path: &Path, key_path: &Path) -> io::Result<CertifiedKey> {
+        CertifiedKey::from_der(load_certs(cert_path)?, load_key(key_path)?, &provider())
+            .map_err(|e| invalid(format_args!("{}: {e}", key_path.display())))
+    }
+
+    /// A TCP listener that hands out connections after the TLS handshake.
+    ///
+    /// Handshakes run in their own tasks, so a slow client never holds up
+    /// the others.
+    pub(crate) struct TlsListener {
+        incoming: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+        local_addr: SocketAddr,
+    }
+
+    impl TlsListener {
+        /// Starts accepting on `listener` with the certificate `cert`, which
+        /// is watched for changes, until `shutdown_token` is cancelled.
+        pub(crate) fn new(
+            listener: TcpListener,
+            cert: Arc<ReloadingCert>,
+            shutdown_token: CancellationToken,
+        ) -> io::Result<Self> {
+            let local_addr = listener.local_addr()?;
+            let config = ServerConfig::builder_with_provider(provider())
+                .with_safe_default_protocol_versions()
+                .map_err(invalid)?
+                .with_no_client_auth()
+                .with_cert_resolver(cert.clone());
+            let acceptor = TlsAcceptor::from(Arc::new(config));
+            cert.watch(shutdown_token.clone());
+
+            let (tx, incoming) = mpsc::channel(ACCEPT_BACKLOG);
+            tokio::spawn(async move {
+                loop {
+                    let (tcp, addr) = tokio::select! {
+                        accepted = listener.accept() => match accepted {
+                            Ok(accepted) => accepted,
+                            Err(e) => {
+                                // Typically out of file descriptors; back off.
+                                warn!(error = %e, "Failed to accept TCP connection");
+                                tokio::time::sleep(Duration::from_secs(1)).await;
+                                continue;
+                            }
+                        },
+                        () = shutdown_token.cancelled() => break,
+                    };
+
+                    let acceptor = acceptor.clone();
+                    let tx = tx.clone();
+                    tokio::spawn(async move {
+                        match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(tcp)).await {
+                            Ok(Ok(tls)) => {
+                                let _ = tx.send((tls, addr)).await;
+                            }
+                            Ok(Err(e)) => {
+                                debug!(remote_addr = %addr, error = %e, "TLS handshake failed");
+                            }
+                            Err(_) => {
+                                debug!(remote_addr = %addr, "TLS handshake timed out");
+                            }
+                        }
+                    });
+                }
+            });
+
+            Ok(Self {
+                incoming,
+                local_addr,
+            })
+        }
+    }
+
+    impl axum::serve::Listener for TlsListener {
+        type Io = TlsStream<TcpStream>;
+        type Addr = SocketAddr;
+
+        async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+            match self.incoming.recv().await {
+                Some(accepted) => accepted,
+                // The accept loop only stops at shutdown, when the server
+                // future is dropped as well.
+                None => std::future::pending().await,
+            }
+        }
+
+        fn local_addr(&self) -> io::Result<Self::Addr> {
+            Ok(self.local_addr)
+        }
+    }
+}
+
+// ─── Client side ──────────────────────────────────────────────────────────────
+
+#[cfg(feature = "ws-client")]
+mod client {
+    use std::sync::OnceLock;
+
+    use alloy_core::ClientTlsConfig;
+    use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+    use rustls::pki_types::{ServerName, UnixTime};
+    use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+    use tracing::warn;
+
+    use super::*;
+
+    /// Returns the system root certificates, read on first use.
+    ///
+    /// Reading them is blocking file I/O, too slow to repeat on every
+    /// connection attempt of an async client.
+    fn native_roots() -> &'static [CertificateDer<'static>] {
+        static NATIVE_ROOTS: OnceLock<Vec<CertificateDer<'static>>> = OnceLock::new();
+        NATIVE_ROOTS.get_or_init(|| {
+            let native = rustls_native_certs::load_native_certs();
+            for e in native.errors {
+                warn!(error = %e, "Failed to load system root certificates");
+            }
+            native.certs
+        })
+    }
+
+    /// Builds the rustls client configuration described by `tls`.
+    pub(crate) fn client_config(tls: &ClientTlsConfig) -> io::Result<ClientConfig> {
+        let builder = ClientConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()
+            .map_err(invalid)?;
+
+        let builder = if tls.insecure_skip_verify {
+            warn!("TLS certificate verification is disabled");
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(SkipVerification(provider())))
+        } else {
+            let mut roots = RootCertStore::empty();
+            roots.add_parsable_certificates(native_roots().iter().cloned());
+            if let Some(ca_file) = &tls.ca_file {
+                for cert in load_certs(ca_file)? {
+                    roots
+                        .add(cert)
+                        .map_err(|e| invalid(format_args!("{}: {e}", ca_file.display())))?;
+                }
+            }
+            builder.with_root_certificates(roots)
+        };
+
+        match (&tls.client_cert, &tls.client_key) {
+            (Some(cert), Some(key)) => builder
+                .with_client_auth_cert(load_certs(cert)?, load_key(key)?)
+                .map_err(|e| invalid(format_args!("{}: {e}", key.display()))),
+            (None, None) => Ok(builder.with_no_client_auth()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "client_cert and client_key must be set together",
+            )),
+        }
+    }
+
+    /// Accepts any server certificate, while still checking that the
+    /// handshake is signed by it.
+    #[derive(Debug)]
+    struct SkipVerification(Arc<CryptoProvider>);
+
+    impl ServerCertVerifier for SkipVerification {
+        fn verify_server_cert(
+            &self,
+            _end_entity: &CertificateDer<'_>,
+            _intermediates: &[CertificateDer<'_>],
+            _server_name: &ServerName<'_>,
+            _ocsp_response: &[u8],
+            _now: UnixTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            Ok(ServerCertVerified::assertion())
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            rustls::crypto::verify_tls12_signature(
+                message,
+                cert,
+                dss,
+                &self.0.signature_verification_algorithms,
+            )
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            rustls::crypto::verify_tls13_signature(
+                message,
+                cert,
+                dss,
+                &self.0.signature_verification_algorithms,
+            )
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            self.0.signature_verification_algorithms.supported_schemes()
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "http-server", feature = "ws-server")))]
+mod tests {
+    use super::*;
+
+    /// A CA and two server certificates it signed for `localhost` and
+    /// `127.0.0.1`, valid until 2126.
+    const TESTDATA: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata");
+
+    fn testdata(name: &str) -> std::path::PathBuf {
+        Path::new(TESTDATA).join(name)
+    }
+
+    /// Copies a test certificate pair to a fresh directory.
+    fn cert_dir(test: &str, pair: u8) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("alloy-tls-{test}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::copy(testdata(&format!("cert{pair}.pem")), dir.join("cert.pem")).unwrap();
+        std::fs::copy(testdata(&format!("key{pair}.pem")), dir.join("key.pem")).unwrap();
+        dir
+    }
+
+    /// Replaces `path` with `contents`, moving its modification time forward
+    /// so the change is seen regardless of the file system's resolution.
+    fn rewrite(path: &Path, contents: &[u8]) {
+        let previous = std::fs::metadata(path).unwrap().modified().unwrap();
+        std::fs::write(path, contents).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(previous + std::time::Duration::from_secs(2))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_reloading_cert_follows_files() {
+        let dir = cert_dir("reload", 1);
+        let (cert_path, key_path) = (dir.join("cert.pem"), dir.join("key.pem"));
+        let cert = ReloadingCert::load(&cert_path, &key_path).unwrap();
+        let served = || cert.current.read().cert[0].clone();
+        assert_eq!(served(), load_certs(&testdata("cert1.pem")).unwrap()[0]);
+
+        // Unchanged files are not reloaded.
+        cert.reload_if_changed();
+        assert_eq!(served(), load_certs(&testdata("cert1.pem")).unwrap()[0]);
+
+        // A renewed pair is picked up.
+        rewrite(&key_path, &std::fs::read(testdata("key2.pem")).unwrap());
+        rewrite(&cert_path, &std::fs::read(testdata("cert2.pem")).unwrap());
+        cert.reload_if_changed();
+        let renewed = load_certs(&testdata("cert2.pem")).unwrap()[0].clone();
+        assert_eq!(served(), renewed);
+
+        // A broken pair keeps the previous one.
+        rewrite(&key_path, b"not a key");
+        cert.reload_if_changed();
+        assert_eq!(served(), renewed);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "ws-client")]
+    #[tokio::test]
+    async fn test_loopback_handshake() {
+        use alloy_core::ClientTlsConfig;
+        use axum::serve::Listener;
+        use rustls::pki_types::ServerName;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::{TcpListener, TcpStream};
+        use tokio_rustls::TlsConnector;
+        use tokio_util::sync::CancellationToken;
+
+        let cert =
+            Arc::new(ReloadingCert::load(&testdata("cert1.pem"), &testdata("key1.pem")).unwrap());
+        let token = CancellationToken::new();
+        let tcp = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut listener = TlsListener::new(tcp, cert, token.clone()).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await;
+            stream.write_all(b"hello").await.unwrap();
+            stream.flush().await.unwrap();
+            listener
+        });
+
+        let connect = |tls: ClientTlsConfig| async move {
+            let connector = TlsConnector::from(Arc::new(client_config(&tls).unwrap()));
+            let tcp = TcpStream::connect(addr).await.unwrap();
+            let name = ServerName::try_from("localhost").unwrap();
+            connector.connect(name, tcp).await
+        };
+
+        // Only trusted through the test CA.
+        assert!(connect(ClientTlsConfig::default()).await.is_err());
+
+        let trusted = ClientTlsConfig::default().with_ca_file(testdata("ca.pem"));
+        let mut stream = connect(trusted).await.unwrap();
+        let mut greeting = [0; 5];
+        stream.read_exact(&mut greeting).await.unwrap();
+        assert_eq!(&greeting, b"hello");
+
+        server.await.unwrap();
+        token.cancel();
+    }
+}
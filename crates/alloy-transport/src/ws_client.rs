@@ -5,30 +5,59 @@ use std::time::Duration;
 
 use futures::stream::{SplitSink, SplitStream};
 use futures::{SinkExt, StreamExt};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::TcpStream;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::error::UrlError;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+use tokio_tungstenite::tungstenite::http::header::{AUTHORIZATION, HeaderName, HeaderValue};
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
 use tokio_tungstenite::tungstenite::{Error, Message};
-use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream, client_async_tls_with_config};
 use tokio_util::sync::CancellationToken;
-use tracing::{error, info, trace, warn};
+use tracing::{debug, error, info, trace, warn};
 
 use alloy_core::{
-    ConnectionHandle, ConnectionHandler, ConnectionInfo, TransportError, TransportResult,
-    WsClientConfig,
+    ConnectionHandle, ConnectionHandler, ConnectionInfo, ConnectionKind, ConnectionStatus, Frame,
+    TransportError, TransportResult, WsClientConfig,
 };
 use alloy_macros::register_capability;
 
-type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+/// A TCP or Unix socket stream.
+trait Io: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Io for T {}
+
+type WsStream = WebSocketStream<MaybeTlsStream<Box<dyn Io>>>;
 type WsSink = SplitSink<WsStream, Message>;
 type WsSource = SplitStream<WsStream>;
 
 /// State for managing WebSocket client loop interactions.
+///
+/// The loop is a small state machine, published as [`ConnectionStatus`]:
+///
+/// ```text
+/// Connected ──lost──▶ Backoff ──delay──▶ Reconnecting ──ok──▶ Connected
+///                        ▲                    │
+///                        └──────failed────────┘
+///           (reconnect disabled / retries exhausted / shutdown) ──▶ GaveUp
+/// ```
 struct ClientLoopState {
     handler: Arc<dyn ConnectionHandler>,
-    bot_id: String,
+    /// The handle given to the bot; replaced if the bot ID changes on
+    /// reconnect.
+    connection: ConnectionHandle,
     config: WsClientConfig,
-    retry_count: u32,
-    current_delay: Duration,
+    status_tx: watch::Sender<ConnectionStatus>,
+    shutdown_token: CancellationToken,
+    /// Reconnect attempts since the last successful connection.
+    attempt: u32,
+    /// Backoff delay before jitter for the next attempt.
+    next_delay: Duration,
+    /// A ping is outstanding and nothing has arrived since it was sent.
+    awaiting_pong: bool,
     ws_tx: WsSink,
     ws_rx: WsSource,
 }
@@ -37,8 +66,10 @@ impl ClientLoopState {
     /// Creates a new client loop state.
     fn new(
         handler: Arc<dyn ConnectionHandler>,
-        bot_id: String,
+        connection: ConnectionHandle,
         config: WsClientConfig,
+        status_tx: watch::Sender<ConnectionStatus>,
+        shutdown_token: CancellationToken,
         ws_stream: WsStream,
     ) -> Self {
         let initial_delay = config.initial_delay;
@@ -46,126 +77,392 @@ impl ClientLoopState {
 
         Self {
             handler,
-            bot_id,
+            connection,
             config,
-            retry_count: 0,
-            current_delay: initial_delay,
+            status_tx,
+            shutdown_token,
+            attempt: 0,
+            next_delay: initial_delay,
+            awaiting_pong: false,
             ws_tx,
             ws_rx,
         }
     }
 
-    /// Handles incoming message and resets retry counters on success.
-    async fn handle_message_received(&mut self, message_type: &str, data: &[u8]) {
-        trace!(bot_id = %self.bot_id, len = data.len(), message_type = message_type, "Received");
-        self.handler.on_message(&self.bot_id, data).await;
-        self.retry_count = 0;
-        self.current_delay = self.config.initial_delay;
+    fn bot_id(&self) -> &str {
+        &self.connection.id
     }
 
-    /// Handles reconnection logic when connection is lost or error occurs.
-    /// Returns true if should continue loop, false if should break.
+    fn set_status(&self, status: ConnectionStatus) {
+        debug!(bot_id = %self.bot_id(), status = ?status, "WebSocket client status");
+        self.status_tx.send_replace(status);
+    }
+
+    /// Handles incoming message.
+    async fn handle_message_received(&mut self, frame: Frame) {
+        trace!(bot_id = %self.bot_id(), len = frame.payload().len(), binary = frame.is_binary(), "Received");
+        self.handler.on_message(&self.connection.id, &frame).await;
+        self.awaiting_pong = false;
+    }
+
+    /// Sends a frame queued by the bot.
+    async fn send_frame(&mut self, frame: Frame) {
+        let msg = match frame {
+            Frame::Text(text) => Message::Text(text.into()),
+            Frame::Binary(data) => Message::Binary(data.into()),
+            Frame::Close { code, reason } => Message::Close(Some(CloseFrame {
+                code: code.into(),
+                reason: reason.into(),
+            })),
+        };
+        if let Err(e) = self.ws_tx.send(msg).await {
+            warn!(bot_id = %self.bot_id(), error = %e, "Failed to send message");
+        }
+    }
+
+    /// Closes the connection for good and disconnects the bot.
+    async fn give_up(&mut self) {
+        let _ = self.ws_tx.close().await;
+        self.set_status(ConnectionStatus::GaveUp);
+        self.handler.on_disconnect(&self.connection).await;
+    }
+
+    /// Reconnects after the connection was lost, backing off between
+    /// attempts.
+    /// Returns true once reconnected, false if the client gave up (the bot
+    /// has then been disconnected).
     async fn handle_reconnect(&mut self) -> bool {
         if !self.config.auto_reconnect {
-            self.handler.on_disconnect(&self.bot_id).await;
+            info!(bot_id = %self.bot_id(), "Auto-reconnect disabled, closing");
+            self.give_up().await;
             return false;
         }
 
-        // Check max retries
-        if let Some(max) = self.config.max_retries {
-            if self.retry_count >= max {
-                error!(bot_id = %self.bot_id, "Max retries reached, giving up");
-                self.handler.on_disconnect(&self.bot_id).await;
+        loop {
+            if let Some(max) = self.config.max_retries
+                && self.attempt >= max
+            {
+                error!(bot_id = %self.bot_id(), attempts = self.attempt, "Max retries reached, giving up");
+                self.give_up().await;
                 return false;
             }
-        }
-
-        warn!(bot_id = %self.bot_id, delay = ?self.current_delay, "Reconnecting...");
-        tokio::time::sleep(self.current_delay).await;
+            self.attempt += 1;
 
-        match connect_async(&self.config.url).await {
-            Ok((new_stream, _)) => {
-                let (new_tx, new_rx) = new_stream.split();
-                info!(bot_id = %self.bot_id, "Reconnected successfully");
-                self.retry_count = 0;
-                self.current_delay = self.config.initial_delay;
-                self.ws_tx = new_tx;
-                self.ws_rx = new_rx;
-                true
+            let delay = backoff_delay(&mut self.next_delay, &self.config);
+            warn!(bot_id = %self.bot_id(), attempt = self.attempt, delay = ?delay, "Reconnecting...");
+            self.set_status(ConnectionStatus::Backoff {
+                attempt: self.attempt,
+                delay,
+            });
+            tokio::select! {
+                _ = self.shutdown_token.cancelled() => {
+                    info!(bot_id = %self.bot_id(), "WebSocket client shutting down");
+                    self.give_up().await;
+                    return false;
+                }
+                _ = tokio::time::sleep(delay) => {}
             }
-            Err(e) => {
-                warn!(bot_id = %self.bot_id, error = %e, "Reconnection failed");
-                self.retry_count += 1;
-                self.current_delay = std::cmp::min(
-                    Duration::from_secs_f64(
-                        self.current_delay.as_secs_f64() * self.config.backoff_multiplier,
-                    ),
-                    self.config.max_delay,
-                );
-                false
+
+            self.set_status(ConnectionStatus::Reconnecting {
+                attempt: self.attempt,
+            });
+            let connected = tokio::select! {
+                _ = self.shutdown_token.cancelled() => {
+                    info!(bot_id = %self.bot_id(), "WebSocket client shutting down");
+                    self.give_up().await;
+                    return false;
+                }
+                connected = connect(&self.config) => connected,
+            };
+            let (stream, conn_info) = match connected {
+                Ok(connected) => connected,
+                Err(e) => {
+                    warn!(bot_id = %self.bot_id(), attempt = self.attempt, error = %e, "Reconnection failed");
+                    continue;
+                }
+            };
+            let bot_id = match self.handler.get_bot_id(conn_info) {
+                Ok(bot_id) => bot_id,
+                Err(e) => {
+                    warn!(bot_id = %self.bot_id(), error = %e, "Could not identify bot after reconnect");
+                    continue;
+                }
+            };
+
+            if bot_id != self.connection.id {
+                self.rebind(bot_id).await;
             }
+            let (new_tx, new_rx) = stream.split();
+            self.ws_tx = new_tx;
+            self.ws_rx = new_rx;
+            self.attempt = 0;
+            self.next_delay = self.config.initial_delay;
+            self.awaiting_pong = false;
+            info!(bot_id = %self.bot_id(), "Reconnected successfully");
+            self.set_status(ConnectionStatus::Connected);
+            return true;
         }
     }
 
+    /// Moves the connection to a bot that now reports a different ID: the
+    /// old bot is disconnected and a new handle is registered for the new one.
+    async fn rebind(&mut self, bot_id: String) {
+        warn!(old = %self.bot_id(), new = %bot_id, "Bot ID changed on reconnect");
+        self.handler.on_disconnect(&self.connection).await;
+
+        let ConnectionKind::Ws { message_tx } = &self.connection.kind else {
+            unreachable!("WebSocket client handles are always ConnectionKind::Ws");
+        };
+        self.connection = connection_handle(
+            bot_id,
+            message_tx.clone(),
+            self.shutdown_token.clone(),
+            self.status_tx.subscribe(),
+            &self.config,
+        );
+        self.handler
+            .create_bot(&self.connection.id, self.connection.clone());
+    }
+
     /// Handles incoming WebSocket messages.
     /// Returns true if should continue loop, false if should break.
     async fn handle_message(&mut self, msg: Option<Result<Message, Error>>) -> bool {
         match msg {
             Some(Ok(Message::Text(text))) => {
-                self.handle_message_received("text", text.as_bytes()).await;
+                self.handle_message_received(Frame::Text(text.to_string()))
+                    .await;
                 true
             }
             Some(Ok(Message::Binary(data))) => {
-                self.handle_message_received("binary", &data).await;
+                self.handle_message_received(Frame::Binary(data.to_vec()))
+                    .await;
                 true
             }
             Some(Ok(Message::Ping(data))) => {
-                trace!(bot_id = %self.bot_id, "Received ping, sending pong");
+                trace!(bot_id = %self.bot_id(), "Received ping, sending pong");
                 let _ = self.ws_tx.send(Message::Pong(data)).await;
                 true
             }
             Some(Ok(Message::Pong(_))) => {
-                trace!(bot_id = %self.bot_id, "Received pong");
+                trace!(bot_id = %self.bot_id(), "Received pong");
+                self.awaiting_pong = false;
                 true
             }
-            Some(Ok(Message::Close(_))) | Some(Ok(Message::Frame(_))) => {
-                info!(bot_id = %self.bot_id, "Server closed connection");
+            Some(Ok(Message::Close(close))) => {
+                let frame = match close {
+                    Some(close) => Frame::close(close.code.into(), close.reason.as_str()),
+                    None => Frame::close(Frame::CLOSE_NO_STATUS, ""),
+                };
+                info!(bot_id = %self.bot_id(), frame = ?frame, "Server closed connection");
+                self.handler.on_message(&self.connection.id, &frame).await;
+                self.handle_reconnect().await
+            }
+            Some(Ok(Message::Frame(_))) => {
+                info!(bot_id = %self.bot_id(), "Server closed connection");
                 self.handle_reconnect().await
             }
             Some(Err(e)) => {
-                warn!(bot_id = %self.bot_id, error = %e, "WebSocket error");
+                warn!(bot_id = %self.bot_id(), error = %e, "WebSocket error");
                 self.handle_reconnect().await
             }
             None => {
-                info!(bot_id = %self.bot_id, "WebSocket stream ended");
+                info!(bot_id = %self.bot_id(), "WebSocket stream ended");
                 self.handle_reconnect().await
             }
         }
     }
+
+    /// Sends a keep-alive ping, or reconnects if the previous one went
+    /// unanswered.
+    /// Returns true if should continue loop, false if should break.
+    async fn handle_heartbeat(&mut self) -> bool {
+        if self.awaiting_pong {
+            warn!(bot_id = %self.bot_id(), "No pong within heartbeat interval, connection is dead");
+            let _ = self.ws_tx.close().await;
+            return self.handle_reconnect().await;
+        }
+
+        trace!(bot_id = %self.bot_id(), "Sending ping");
+        if let Err(e) = self.ws_tx.send(Message::Ping(Default::default())).await {
+            warn!(bot_id = %self.bot_id(), error = %e, "Failed to send ping");
+            return self.handle_reconnect().await;
+        }
+        self.awaiting_pong = true;
+        true
+    }
+}
+
+/// Returns the jittered delay before the current attempt and grows the base
+/// delay in `next_delay` for the next one, up to `config.max_delay`.
+fn backoff_delay(next_delay: &mut Duration, config: &WsClientConfig) -> Duration {
+    let base = *next_delay;
+    *next_delay = std::cmp::min(
+        base.mul_f64(config.backoff_multiplier.max(1.0)),
+        config.max_delay,
+    );
+    let jitter = config.jitter.clamp(0.0, 1.0);
+    base.mul_f64(1.0 - jitter * rand::random::<f64>())
+}
+
+/// Creates the keep-alive timer for `config`, first firing one period from now.
+fn heartbeat_timer(config: &WsClientConfig) -> Option<Interval> {
+    let period = config.heartbeat_interval.filter(|p| !p.is_zero())?;
+    let mut timer = tokio::time::interval_at(Instant::now() + period, period);
+    timer.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    Some(timer)
+}
+
+/// Waits for the next heartbeat tick; never completes if heartbeats are off.
+async fn next_heartbeat(timer: &mut Option<Interval>) {
+    match timer {
+        Some(timer) => {
+            timer.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Builds the handshake request: `config.url` plus the bearer token and
+/// custom headers.
+fn handshake_request(config: &WsClientConfig) -> Result<Request, Error> {
+    let mut request = config.url.as_str().into_client_request()?;
+    let headers = request.headers_mut();
+    if let Some(token) = config.access_token.as_deref().filter(|t| !t.is_empty()) {
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}"))?,
+        );
+    }
+    for (name, value) in &config.headers {
+        headers.insert(
+            HeaderName::from_bytes(name.as_bytes())?,
+            HeaderValue::from_str(value)?,
+        );
+    }
+    Ok(request)
+}
+
+/// Creates the handle registered for the bot behind the connection.
+fn connection_handle(
+    bot_id: String,
+    message_tx: mpsc::Sender<Frame>,
+    shutdown_token: CancellationToken,
+    status: watch::Receiver<ConnectionStatus>,
+    config: &WsClientConfig,
+) -> ConnectionHandle {
+    let handle = ConnectionHandle::new_ws(bot_id, message_tx, shutdown_token).with_status(status);
+    match config.call_timeout {
+        Some(timeout) => handle.with_call_timeout(timeout),
+        None => handle,
+    }
+}
+
+/// Opens the socket described by `config` and performs the WebSocket
+/// handshake over it, giving up after `config.connect_timeout`.
+///
+/// Returns the stream and the [`ConnectionInfo`] used to identify the bot:
+/// the URL plus the handshake response headers (lowercase names).
+async fn connect(config: &WsClientConfig) -> Result<(WsStream, ConnectionInfo), Error> {
+    tokio::time::timeout(config.connect_timeout, open(config))
+        .await
+        .unwrap_or_else(|_| {
+            Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("no connection within {:?}", config.connect_timeout),
+            )))
+        })
+}
+
+/// Connects without a deadline; see [`connect`].
+async fn open(config: &WsClientConfig) -> Result<(WsStream, ConnectionInfo), Error> {
+    let request = handshake_request(config)?;
+
+    let stream: Box<dyn Io> = match &config.unix_socket {
+        #[cfg(unix)]
+        Some(path) => Box::new(tokio::net::UnixStream::connect(path).await?),
+        #[cfg(not(unix))]
+        Some(path) => {
+            return Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!(
+                    "Unix socket {} is not supported on this platform",
+                    path.display()
+                ),
+            )));
+        }
+        None => {
+            let uri = request.uri();
+            let host = uri.host().ok_or(Error::Url(UrlError::NoHostName))?;
+            // IPv6 literals keep their brackets in the URI.
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            let port = uri
+                .port_u16()
+                .unwrap_or(if uri.scheme_str() == Some("wss") {
+                    443
+                } else {
+                    80
+                });
+            let scheme = uri.scheme_str().unwrap_or("ws");
+            let tcp = match crate::proxy::select(&config.proxy, scheme, host)? {
+                Some(proxy) => {
+                    debug!(proxy = %proxy.host, target = %host, "Connecting through proxy");
+                    crate::proxy::connect(&proxy, host, port).await?
+                }
+                None => TcpStream::connect((host, port)).await?,
+            };
+            tcp.set_nodelay(true)?;
+            Box::new(tcp)
+        }
+    };
+
+    // rustls with the configured certificates; plain `ws://` ignores it.
+    let connector = match request.uri().scheme_str() {
+        Some("wss") => Some(Connector::Rustls(Arc::new(crate::tls::client_config(
+            &config.tls,
+        )?))),
+        _ => None,
+    };
+    let (ws_stream, response) =
+        client_async_tls_with_config(request, stream, None, connector).await?;
+
+    let mut conn_info = ConnectionInfo::new("websocket").with_metadata("url", &config.url);
+    for (name, value) in response.headers() {
+        if let Ok(value) = value.to_str() {
+            conn_info = conn_info.with_metadata(name.as_str(), value);
+        }
+    }
+    Ok((ws_stream, conn_info))
 }
 
 /// Connects to a WebSocket server.
 ///
 /// Creates channels, performs the initial connection, spawns a background loop
-/// that handles send/receive and automatic reconnect per `config`.
+/// that handles send/receive, keep-alive pings and automatic reconnect per
+/// `config`.  Every handshake, including reconnects, carries the configured
+/// access token and headers, and the bot is re-identified after each
+/// reconnect.  Each attempt is bounded by `config.connect_timeout`.
 ///
-/// This function is registered as the `WsConnectFn` capability.
-#[register_capability(ws_client)]
+/// The returned handle reports the reconnect state through
+/// [`ConnectionHandle::watch_status`].  The bot is disconnected only once the
+/// client gives up.  A close frame sent by the bot closes the current
+/// connection, after which the client reconnects as configured; shut the
+/// handle down to stop for good, which also abandons a pending attempt.
+///
+/// This function is registered as the [`WsClient`](alloy_core::WsClient) capability.
+#[register_capability(alloy_core::WsClient)]
 pub async fn ws_connect(
     config: WsClientConfig,
     handler: Arc<dyn ConnectionHandler>,
 ) -> TransportResult<ConnectionHandle> {
     // Create channels
-    let (message_tx, mut message_rx) = mpsc::channel::<Vec<u8>>(256);
+    let (message_tx, mut message_rx) = mpsc::channel::<Frame>(256);
     let shutdown_token = CancellationToken::new();
 
     // Initial connection
-    let conn_info = ConnectionInfo::new("websocket").with_metadata("url", &config.url);
-
-    info!(url = %config.url, "Connecting to WebSocket server");
+    info!(url = %config.url, unix_socket = ?config.unix_socket, "Connecting to WebSocket server");
 
-    let (ws_stream, _response) =
-        connect_async(&config.url)
+    let (ws_stream, conn_info) =
+        connect(&config)
             .await
             .map_err(|e| TransportError::ConnectionFailed {
                 url: config.url.clone(),
@@ -177,12 +474,27 @@ pub async fn ws_connect(
 
     info!(bot_id = %bot_id, url = %config.url, "WebSocket client connected");
 
-    let handle = ConnectionHandle::new_ws(bot_id.clone(), message_tx, shutdown_token.clone());
+    let (status_tx, status_rx) = watch::channel(ConnectionStatus::Connected);
+    let handle = connection_handle(
+        bot_id.clone(),
+        message_tx,
+        shutdown_token.clone(),
+        status_rx,
+        &config,
+    );
 
     // Create and register the bot
     handler.create_bot(&bot_id, handle.clone());
 
-    let mut state = ClientLoopState::new(handler, bot_id, config, ws_stream);
+    let mut heartbeat = heartbeat_timer(&config);
+    let mut state = ClientLoopState::new(
+        handler,
+        handle.clone(),
+        config,
+        status_tx,
+        shutdown_token.clone(),
+        ws_stream,
+    );
 
     // Spawn connection manager task
     tokio::spawn(async move {
@@ -190,18 +502,14 @@ pub async fn ws_connect(
             tokio::select! {
                 // Check for shutdown
                 _ = shutdown_token.cancelled() => {
-                    info!(bot_id = %state.bot_id, "WebSocket client shutting down");
-                    let _ = state.ws_tx.close().await;
-                    state.handler.on_disconnect(&state.bot_id).await;
+                    info!(bot_id = %state.bot_id(), "WebSocket client shutting down");
+                    state.give_up().await;
                     break;
                 }
 
                 // Receive messages to send
-                Some(data) = message_rx.recv() => {
-                    let msg = Message::Text(String::from_utf8_lossy(&data).to_string().into());
-                    if let Err(e) = state.ws_tx.send(msg).await {
-                        warn!(bot_id = %state.bot_id, error = %e, "Failed to send message");
-                    }
+                Some(frame) = message_rx.recv() => {
+                    state.send_frame(frame).await;
                 }
 
                 // Receive messages from server
@@ -210,9 +518,335 @@ pub async fn ws_connect(
                         break;
                     }
                 }
+
+                // Keep-alive
+                _ = next_heartbeat(&mut heartbeat) => {
+                    if !state.handle_heartbeat().await {
+                        break;
+                    }
+                }
             }
         }
     });
 
     Ok(handle)
 }
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_hdr_async;
+    use tokio_tungstenite::tungstenite::handshake::server::{
+        Request as ServerRequest, Response as ServerResponse,
+    };
+
+    use super::*;
+
+    /// Identifies bots by the `x-self-id` handshake response header and
+    /// records what the client reports.
+    #[derive(Default)]
+    struct TestHandler {
+        created: Mutex<Vec<ConnectionHandle>>,
+        disconnected: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ConnectionHandler for TestHandler {
+        fn get_bot_id(&self, conn_info: ConnectionInfo) -> TransportResult<String> {
+            conn_info.metadata.get("x-self-id").cloned().ok_or_else(|| {
+                TransportError::BotIdMissing {
+                    reason: "no x-self-id".into(),
+                }
+            })
+        }
+
+        fn create_bot(&self, _bot_id: &str, connection: ConnectionHandle) {
+            self.created.lock().push(connection);
+        }
+
+        async fn on_message(&self, _bot_id: &str, _frame: &Frame) {}
+
+        async fn on_disconnect(&self, connection: &ConnectionHandle) {
+            self.disconnected.lock().push(connection.id.clone());
+        }
+    }
+
+    /// Serves one WebSocket connection per entry of `ids`, answering the
+    /// handshake with that `X-Self-ID`.  Every connection but the last is
+    /// closed right away; the listener is dropped after the last one if
+    /// `close_after` is set.
+    async fn serve(ids: &'static [&'static str], close_after: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut open = Vec::new();
+            for (i, id) in ids.iter().enumerate() {
+                let (stream, _) = listener.accept().await.unwrap();
+                // The error type is fixed by tungstenite's `Callback`.
+                #[allow(clippy::result_large_err)]
+                let callback = |_: &ServerRequest, mut response: ServerResponse| {
+                    response
+                        .headers_mut()
+                        .insert("x-self-id", HeaderValue::from_static(id));
+                    Ok(response)
+                };
+                let mut ws = accept_hdr_async(stream, callback).await.unwrap();
+                if i + 1 < ids.len() || close_after {
+                    tokio::spawn(async move {
+                        ws.close(None).await.unwrap();
+                        while ws.next().await.is_some() {}
+                    });
+                } else {
+                    open.push(ws);
+                }
+            }
+            drop(listener);
+            std::future::pending::<()>().await;
+        });
+        url
+    }
+
+    fn test_config(url: &str) -> WsClientConfig {
+        WsClientConfig {
+            jitter: 0.0,
+            ..WsClientConfig::new(url)
+                .with_heartbeat(None)
+                .with_reconnect(true, Duration::from_millis(20))
+        }
+    }
+
+    /// Collects status changes until `last` is published.
+    async fn statuses_until(
+        mut rx: watch::Receiver<ConnectionStatus>,
+        last: ConnectionStatus,
+    ) -> Vec<ConnectionStatus> {
+        let mut seen = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while rx.changed().await.is_ok() {
+                let status = rx.borrow_and_update().clone();
+                seen.push(status.clone());
+                if status == last {
+                    break;
+                }
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("never reached {last:?}, saw {seen:?}"));
+        seen
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        let config = WsClientConfig {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5),
+            backoff_multiplier: 2.0,
+            jitter: 0.0,
+            ..Default::default()
+        };
+        let mut next = config.initial_delay;
+        let delays: Vec<_> = (0..5)
+            .map(|_| backoff_delay(&mut next, &config).as_secs())
+            .collect();
+        assert_eq!(delays, [1, 2, 4, 5, 5]);
+
+        // A multiplier below 1 never shrinks the delay.
+        let flat = WsClientConfig {
+            backoff_multiplier: 0.5,
+            ..config.clone()
+        };
+        let mut next = flat.initial_delay;
+        assert_eq!(backoff_delay(&mut next, &flat), Duration::from_secs(1));
+        assert_eq!(next, Duration::from_secs(1));
+
+        // Jitter only ever shortens the delay, by at most its fraction.
+        let jittered = WsClientConfig {
+            jitter: 0.5,
+            ..config
+        };
+        for _ in 0..100 {
+            let mut next = Duration::from_secs(4);
+            let delay = backoff_delay(&mut next, &jittered);
+            assert!(delay >= Duration::from_secs(2) && delay <= Duration::from_secs(4));
+            assert_eq!(next, Duration::from_secs(5));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_after_close() {
+        let url = serve(&["10001", "10001"], false).await;
+        let handler = Arc::new(TestHandler::default());
+        let handle = ws_connect(test_config(&url), handler.clone())
+            .await
+            .unwrap();
+        let statuses = handle.watch_status().unwrap();
+        assert_eq!(handle.status(), Some(ConnectionStatus::Connected));
+
+        let seen = statuses_until(statuses, ConnectionStatus::Connected).await;
+        assert_eq!(
+            seen[0],
+            ConnectionStatus::Backoff {
+                attempt: 1,
+                delay: Duration::from_millis(20),
+            }
+        );
+        // The same bot came back: no new handle, no disconnect.
+        assert_eq!(handler.created.lock().len(), 1);
+        assert!(handler.disconnected.lock().is_empty());
+        handle.close();
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let url = serve(&["10001"], true).await;
+        let handler = Arc::new(TestHandler::default());
+        let config = test_config(&url).with_max_retries(2);
+        let handle = ws_connect(config, handler.clone()).await.unwrap();
+
+        let seen = statuses_until(handle.watch_status().unwrap(), ConnectionStatus::GaveUp).await;
+        assert!(seen.contains(&ConnectionStatus::Reconnecting { attempt: 2 }));
+        assert!(!seen.contains(&ConnectionStatus::Reconnecting { attempt: 3 }));
+        assert!(!seen.contains(&ConnectionStatus::Connected));
+        assert_eq!(*handler.disconnected.lock(), ["10001"]);
+    }
+
+    #[tokio::test]
+    async fn test_rebinds_when_bot_id_changes() {
+        let url = serve(&["10001", "20002"], false).await;
+        let handler = Arc::new(TestHandler::default());
+        let handle = ws_connect(test_config(&url), handler.clone())
+            .await
+            .unwrap();
+
+        statuses_until(handle.watch_status().unwrap(), ConnectionStatus::Connected).await;
+        assert_eq!(*handler.disconnected.lock(), ["10001"]);
+        let created = handler.created.lock().clone();
+        assert_eq!(created.len(), 2);
+        let rebound = &created[1];
+        assert_eq!(rebound.id, "20002");
+        // The new handle reports the same connection's state.
+        assert!(
+            rebound
+                .watch_status()
+                .unwrap()
+                .same_channel(&handle.watch_status().unwrap())
+        );
+        rebound.clone().close();
+    }
+
+    /// Serves one WebSocket connection answering with `X-Self-ID: 10001`
+    /// and closes it, then accepts TCP connections without ever answering
+    /// their handshakes.
+    async fn serve_then_stall() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            #[allow(clippy::result_large_err)]
+            let callback = |_: &ServerRequest, mut response: ServerResponse| {
+                response
+                    .headers_mut()
+                    .insert("x-self-id", HeaderValue::from_static("10001"));
+                Ok(response)
+            };
+            let mut ws = accept_hdr_async(stream, callback).await.unwrap();
+            ws.close(None).await.unwrap();
+            let mut stalled = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                stalled.push(stream);
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move { listener.accept().await.unwrap() });
+
+        let config = test_config(&url).with_connect_timeout(Duration::from_millis(50));
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            ws_connect(config, Arc::new(TestHandler::default())),
+        )
+        .await
+        .expect("the handshake was not given up");
+        assert!(matches!(
+            result,
+            Err(TransportError::ConnectionFailed { .. })
+        ));
+        drop(server.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_close_interrupts_reconnect() {
+        let url = serve_then_stall().await;
+        let handler = Arc::new(TestHandler::default());
+        let config = test_config(&url).with_connect_timeout(Duration::from_secs(60));
+        let handle = ws_connect(config, handler.clone()).await.unwrap();
+
+        let statuses = handle.watch_status().unwrap();
+        statuses_until(
+            statuses.clone(),
+            ConnectionStatus::Reconnecting { attempt: 1 },
+        )
+        .await;
+        handle.clone().close();
+        statuses_until(statuses, ConnectionStatus::GaveUp).await;
+        assert_eq!(*handler.disconnected.lock(), ["10001"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_unix_socket() {
+        let path =
+            std::env::temp_dir().join(format!("alloy-ws-client-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            #[allow(clippy::result_large_err)]
+            let callback = |request: &ServerRequest, mut response: ServerResponse| {
+                assert_eq!(request.uri().path(), "/ws");
+                response
+                    .headers_mut()
+                    .insert("x-self-id", HeaderValue::from_static("10001"));
+                Ok(response)
+            };
+            let mut ws = accept_hdr_async(stream, callback).await.unwrap();
+            ws.send(Message::text("hello")).await.unwrap();
+            ws
+        });
+
+        let handler = Arc::new(TestHandler::default());
+        let config = test_config("ws://localhost/ws").with_unix_socket(&path);
+        let handle = ws_connect(config, handler.clone()).await.unwrap();
+        assert_eq!(handle.status(), Some(ConnectionStatus::Connected));
+        assert_eq!(handler.created.lock()[0].id, "10001");
+
+        drop(server.await.unwrap());
+        handle.close();
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_handshake_request_headers() {
+        let config = WsClientConfig::new("ws://127.0.0.1:6700/ws")
+            .with_token("secret")
+            .with_header("X-Self-ID", "10001")
+            .with_header("X-Client-Role", "Universal");
+        let request = handshake_request(&config).unwrap();
+        let headers = request.headers();
+
+        assert_eq!(headers["authorization"], "Bearer secret");
+        assert_eq!(headers["x-self-id"], "10001");
+        assert_eq!(headers["x-client-role"], "Universal");
+        assert!(headers.contains_key("sec-websocket-key"));
+
+        let bad = WsClientConfig::new("ws://127.0.0.1/ws").with_header("X-Bad", "a\nb");
+        assert!(handshake_request(&bad).is_err());
+    }
+}
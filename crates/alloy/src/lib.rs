@@ -83,13 +83,16 @@ pub mod prelude {
     pub use alloy_macros::define_plugin;
 
     // Event system - for building handlers
-    pub use alloy_core::AsText;
+    pub use alloy_core::{AsText, BotChange, BotConnectedEvent, BotDisconnectedEvent, BotRegistry};
     pub use alloy_framework::handler::{HandlerService, Layer, ServiceBuilderExt};
 
     // Extractors - for handler parameters
     pub use alloy_framework::context::AlloyContext;
     pub use alloy_framework::extractor::{Bot, Event, FromContext, PluginConfig, ServiceRef};
 
+    // Conversations - waiting for follow-up messages
+    pub use alloy_framework::conversation::{ConversationSession, Prompt};
+
     // Route convenience functions (from framework layer)
     pub use alloy_framework::routing::{on, on_event_type, on_message};
 
@@ -98,7 +101,7 @@ pub mod prelude {
     pub use alloy_framework::command::{AtSegment, CommandArgs, ImageSegment, on_command};
 
     // Bot types - for interacting with bots in handlers
-    pub use alloy_core::{Bot as __Bot, BoxedBot};
+    pub use alloy_core::{Bot as __Bot, BoxedBot, Target};
 
     // Core traits for custom implementations
     pub use alloy_core::{Event as __Event, Message, RichText, RichTextSegment};